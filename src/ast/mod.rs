@@ -0,0 +1,227 @@
+use markdown_ppp::ast::{
+    Block, CodeBlockKind, Document, FootnoteDefinition, GitHubAlertType, Heading, HeadingKind,
+    Inline, LinkDefinition, SetextHeading,
+};
+use std::collections::HashMap;
+
+/// Heading level from 1 to 6.
+pub fn heading_level(heading: &Heading) -> u8 {
+    match heading.kind {
+        HeadingKind::Atx(level) => level,
+        HeadingKind::Setext(SetextHeading::Level1) => 1,
+        HeadingKind::Setext(SetextHeading::Level2) => 2,
+    }
+}
+
+/// Plain text of inline content with all markup removed.
+pub fn inline_text(inlines: &[Inline]) -> String {
+    let mut text = String::new();
+    push_inline_text(&mut text, inlines);
+    text
+}
+
+fn push_inline_text(text: &mut String, inlines: &[Inline]) {
+    for inline in inlines {
+        match inline {
+            Inline::Text(value) | Inline::Code(value) | Inline::Autolink(value) => {
+                text.push_str(value)
+            }
+            Inline::LineBreak => text.push(' '),
+            Inline::Link(link) => push_inline_text(text, &link.children),
+            Inline::LinkReference(link) => push_inline_text(text, &link.text),
+            Inline::Image(image) => text.push_str(&image.alt),
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children) => push_inline_text(text, children),
+            Inline::Html(_) | Inline::FootnoteReference(_) | Inline::Empty => {}
+        }
+    }
+}
+
+/// Plain text of blocks: the text of paragraphs, headings, table cells and
+/// code, separated by spaces.
+pub fn block_text(blocks: &[Block]) -> String {
+    let mut parts = Vec::new();
+    push_block_text(&mut parts, blocks);
+    parts.join(" ")
+}
+
+fn push_block_text(parts: &mut Vec<String>, blocks: &[Block]) {
+    for block in blocks {
+        match block {
+            Block::Paragraph(inlines) => parts.push(inline_text(inlines)),
+            Block::Heading(heading) => parts.push(inline_text(&heading.content)),
+            Block::CodeBlock(code_block) => parts.push(code_block.literal.clone()),
+            Block::BlockQuote(blocks) => push_block_text(parts, blocks),
+            Block::List(list) => {
+                for item in &list.items {
+                    push_block_text(parts, &item.blocks);
+                }
+            }
+            Block::Table(table) => {
+                for cell in table.rows.iter().flatten() {
+                    parts.push(inline_text(cell));
+                }
+            }
+            Block::FootnoteDefinition(footnote) => push_block_text(parts, &footnote.blocks),
+            Block::GitHubAlert(alert) => push_block_text(parts, &alert.blocks),
+            Block::ThematicBreak | Block::HtmlBlock(_) | Block::Definition(_) | Block::Empty => {}
+        }
+    }
+}
+
+/// Language and attributes of a fenced code block info string like
+/// `sh file=setup.sh title="Set up"` or `{.sh file=setup.sh}`.
+#[derive(Default)]
+pub struct CodeInfo {
+    pub language: Option<String>,
+    pub attributes: Vec<(String, String)>,
+}
+
+impl CodeInfo {
+    pub fn parse(kind: &CodeBlockKind) -> Self {
+        let mut info = Self::default();
+        let CodeBlockKind::Fenced { info: Some(text) } = kind else {
+            return info;
+        };
+        let text = text.trim();
+        let text = match text
+            .strip_prefix('{')
+            .and_then(|text| text.strip_suffix('}'))
+        {
+            Some(inner) => inner,
+            None => text,
+        };
+
+        for word in split_info_words(text) {
+            match word.split_once('=') {
+                Some((key, value)) => info
+                    .attributes
+                    .push((key.to_string(), unquote(value).to_string())),
+                None if info.language.is_none() => {
+                    let language = word.strip_prefix('.').unwrap_or(&word);
+                    if !language.is_empty() {
+                        info.language = Some(language.to_string());
+                    }
+                }
+                None => {}
+            }
+        }
+        info
+    }
+
+    /// Value of the first attribute named `key`.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Split at whitespace outside of quotes.
+fn split_info_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.push(c);
+            }
+            (Some(open), c) if c == open => {
+                quote = None;
+                word.push(c);
+            }
+            (None, c) if c.is_whitespace() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            (_, c) => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Title of a GitHub alert, e.g. `Note`.
+pub fn alert_title(alert_type: &GitHubAlertType) -> &'static str {
+    match alert_type {
+        GitHubAlertType::Note => "Note",
+        GitHubAlertType::Tip => "Tip",
+        GitHubAlertType::Important => "Important",
+        GitHubAlertType::Warning => "Warning",
+        GitHubAlertType::Caution => "Caution",
+    }
+}
+
+/// Normalized form of a link label used to match references with definitions.
+pub fn link_label(label: &[Inline]) -> String {
+    inline_text(label)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Link reference definitions and footnote definitions of a document, by label.
+#[derive(Default)]
+pub struct Definitions<'a> {
+    pub links: HashMap<String, &'a LinkDefinition>,
+    pub footnotes: HashMap<&'a str, &'a FootnoteDefinition>,
+}
+
+impl<'a> Definitions<'a> {
+    pub fn collect(document: &'a Document) -> Self {
+        let mut definitions = Self::default();
+        definitions.add_blocks(&document.blocks);
+        definitions
+    }
+
+    fn add_blocks(&mut self, blocks: &'a [Block]) {
+        for block in blocks {
+            match block {
+                Block::Definition(definition) => {
+                    // The first definition of a label wins
+                    self.links
+                        .entry(link_label(&definition.label))
+                        .or_insert(definition);
+                }
+                Block::FootnoteDefinition(footnote) => {
+                    self.footnotes.entry(&footnote.label).or_insert(footnote);
+                }
+                Block::BlockQuote(blocks) => self.add_blocks(blocks),
+                Block::List(list) => {
+                    for item in &list.items {
+                        self.add_blocks(&item.blocks);
+                    }
+                }
+                Block::GitHubAlert(alert) => self.add_blocks(&alert.blocks),
+                _ => {}
+            }
+        }
+    }
+
+    /// Destination of a reference link.
+    pub fn link_destination(&self, label: &[Inline]) -> Option<&'a str> {
+        self.links
+            .get(&link_label(label))
+            .map(|definition| definition.destination.as_str())
+    }
+}
@@ -0,0 +1,61 @@
+//! Built-in formatter for JSON code blocks.
+
+use serde::de::IgnoredAny;
+
+const INDENT: &str = "  ";
+
+/// Indent JSON with two spaces per level, keeping the order of object keys
+/// and the exact spelling of strings and numbers.
+pub fn format_json(input: &str) -> Result<String, String> {
+    serde_json::from_str::<IgnoredAny>(input).map_err(|err| format!("invalid JSON: {err}"))?;
+
+    let mut output = String::with_capacity(input.len());
+    let mut depth = 0;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                output.push(c);
+                while let Some(c) = chars.next() {
+                    output.push(c);
+                    match c {
+                        '\\' => output.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '{' | '[' => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                output.push(c);
+                // Empty objects and arrays stay on one line
+                if let Some(close) = chars.next_if(|next| matches!(next, '}' | ']')) {
+                    output.push(close);
+                } else {
+                    depth += 1;
+                    new_line(&mut output, depth);
+                }
+            }
+            '}' | ']' => {
+                depth -= 1;
+                new_line(&mut output, depth);
+                output.push(c);
+            }
+            ',' => {
+                output.push(c);
+                new_line(&mut output, depth);
+            }
+            ':' => output.push_str(": "),
+            c if c.is_whitespace() => {}
+            c => output.push(c),
+        }
+    }
+    Ok(output)
+}
+
+fn new_line(output: &mut String, depth: usize) {
+    output.push('\n');
+    for _ in 0..depth {
+        output.push_str(INDENT);
+    }
+}
@@ -0,0 +1,176 @@
+//! Formatting the code of code blocks with external commands.
+//!
+//! Each code block whose language has a formatter configured is piped through
+//! the formatter command, and the command's output replaces the code. A
+//! formatter that fails or runs too long leaves the block unchanged.
+
+mod json;
+
+use crate::ast::CodeInfo;
+use crate::config::CodeFormatConfig;
+use markdown_ppp::ast::{Block, CodeBlock};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Formatter command that uses the built-in JSON formatter.
+pub const BUILTIN_JSON: &str = "builtin:json";
+
+/// Format the code blocks of `blocks` in place, returning a warning for every
+/// block a formatter failed on. `source` names the file in warnings.
+pub fn format_code_blocks(
+    blocks: &mut [Block],
+    config: &CodeFormatConfig,
+    source: &Path,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if config.formatters.is_empty() {
+        return warnings;
+    }
+
+    let mut code_blocks = Vec::new();
+    collect_code_blocks(blocks, &mut code_blocks);
+    for code_block in code_blocks {
+        let Some(language) = CodeInfo::parse(&code_block.kind).language else {
+            continue;
+        };
+        let Some(command) = config.formatter(&language) else {
+            continue;
+        };
+        match run_formatter(command, &code_block.literal, config.timeout()) {
+            Ok(code) => code_block.literal = code,
+            Err(reason) => warnings.push(format!(
+                "{}: {language} code block left unchanged, `{command}` {reason}",
+                source.display()
+            )),
+        }
+    }
+    warnings
+}
+
+fn collect_code_blocks<'a>(blocks: &'a mut [Block], code_blocks: &mut Vec<&'a mut CodeBlock>) {
+    for block in blocks {
+        match block {
+            Block::CodeBlock(code_block) => code_blocks.push(code_block),
+            Block::BlockQuote(blocks) => collect_code_blocks(blocks, code_blocks),
+            Block::List(list) => {
+                for item in &mut list.items {
+                    collect_code_blocks(&mut item.blocks, code_blocks);
+                }
+            }
+            Block::FootnoteDefinition(footnote) => {
+                collect_code_blocks(&mut footnote.blocks, code_blocks)
+            }
+            Block::GitHubAlert(alert) => collect_code_blocks(&mut alert.blocks, code_blocks),
+            _ => {}
+        }
+    }
+}
+
+/// Formatted code, or why the formatter failed.
+fn run_formatter(command: &str, code: &str, timeout: Duration) -> Result<String, String> {
+    let formatted = match command {
+        BUILTIN_JSON => json::format_json(code)?,
+        _ => run_command(command, code, timeout)?,
+    };
+    let formatted = formatted.trim_end_matches(['\n', '\r']);
+    if formatted.trim().is_empty() && !code.trim().is_empty() {
+        return Err("produced no output".to_string());
+    }
+    Ok(formatted.to_string())
+}
+
+/// Standard output of the command run with `input` on its standard input.
+fn run_command(command: &str, input: &str, timeout: Duration) -> Result<String, String> {
+    let words = split_command(command);
+    let Some((program, args)) = words.split_first() else {
+        return Err("is empty".to_string());
+    };
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // A process group of its own, so that stopping the formatter also stops
+    // the processes it started, e.g. a shell script's commands
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("could not be started: {err}"))?;
+
+    // Feed and drain the pipes on their own threads so a formatter that
+    // writes before reading all of its input cannot block on a full pipe
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.as_bytes().to_vec();
+    thread::spawn(move || stdin.write_all(&input));
+    let stdout = read_in_background(child.stdout.take().expect("stdout is piped"));
+    let stderr = read_in_background(child.stderr.take().expect("stderr is piped"));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                kill(&mut child);
+                return Err(format!("timed out after {}s", timeout.as_secs()));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(err) => return Err(format!("failed: {err}")),
+        }
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        return Err(match stderr.lines().find(|line| !line.trim().is_empty()) {
+            Some(line) => format!("failed ({status}): {}", line.trim()),
+            None => format!("failed ({status})"),
+        });
+    }
+    String::from_utf8(stdout).map_err(|_| "produced output that is not UTF-8".to_string())
+}
+
+/// Stop the formatter and everything else in its process group.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: kill has no memory safety requirements; the group is the
+        // child's own, which is not reaped before the wait below
+        unsafe { libc::kill(-pid, libc::SIGKILL) };
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut content = Vec::new();
+        let _ = pipe.read_to_end(&mut content);
+        content
+    })
+}
+
+/// Split a command at whitespace outside of quotes, removing the quotes.
+fn split_command(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut quote = None;
+    for c in command.chars() {
+        match (quote, c) {
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (Some(open), c) if c == open => quote = None,
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (_, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
@@ -0,0 +1,43 @@
+use crate::config::ProjectConfig;
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct Config {
+    #[clap(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the settings merged from the configuration file and defaults
+    Show(ShowConfig),
+}
+
+#[derive(Args)]
+pub struct ShowConfig {
+    /// Apply per-file overrides for this file
+    pub file: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn run(&self, project: &ProjectConfig) -> Result<()> {
+        match &self.command {
+            ConfigCommand::Show(cmd) => cmd.run(project),
+        }
+    }
+}
+
+impl ShowConfig {
+    pub fn run(&self, project: &ProjectConfig) -> Result<()> {
+        match &project.path {
+            Some(path) => println!("# Loaded from {}", path.display()),
+            None => println!("# No configuration file found, using defaults"),
+        }
+
+        let settings = project.settings_for(self.file.as_deref())?.resolved();
+        print!("{}", toml::to_string(&settings)?);
+        Ok(())
+    }
+}
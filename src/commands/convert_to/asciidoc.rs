@@ -0,0 +1,40 @@
+use super::InputFiles;
+use crate::config::{AsciidocConfig, Settings};
+use crate::input::ParsedDocument;
+use crate::printer::asciidoc::{escape_text, render_asciidoc};
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args)]
+pub struct ConvertToAsciidoc {
+    #[clap(flatten)]
+    pub config: AsciidocConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
+
+impl ConvertToAsciidoc {
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.config.merge(&settings.asciidoc);
+        let body = render_asciidoc(&document.document, &config.to_printer_config());
+        Ok(format!("{}{body}", document_header(document)))
+    }
+}
+
+/// Document header with the title, author and date from the front matter.
+fn document_header(document: &ParsedDocument) -> String {
+    let Some(title) = document.metadata("title") else {
+        return String::new();
+    };
+
+    let mut header = format!("= {}\n", escape_text(&title));
+    if let Some(author) = document.metadata("author") {
+        header.push_str(&format!("{author}\n"));
+    }
+    if let Some(date) = document.metadata("date") {
+        header.push_str(&format!(":revdate: {date}\n"));
+    }
+    header.push('\n');
+    header
+}
@@ -1,13 +1,49 @@
+use super::InputFiles;
+use crate::config::{AstJsonConfig, Settings};
+use crate::input::schema::omit_defaults;
+use crate::input::ParsedDocument;
 use anyhow::Result;
 use clap::Args;
+use serde::Serialize;
 
 #[derive(Args)]
-pub struct ConvertToAstJson;
+pub struct ConvertToAstJson {
+    #[clap(flatten)]
+    pub config: AstJsonConfig,
+
+    /// Print the JSON Schema of the AST instead of converting
+    #[clap(long, conflicts_with = "inputs")]
+    pub schema: bool,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
 
 impl ConvertToAstJson {
-    pub fn run(&self, ast: &markdown_ppp::ast::Document) -> Result<()> {
-        let result = serde_json::to_string(ast)?;
-        println!("{result}");
-        Ok(())
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.config.merge(&settings.ast_json);
+        if !config.omit_defaults.unwrap_or(false) {
+            return to_json(document, config.indent());
+        }
+
+        let mut value = serde_json::to_value(document)?;
+        omit_defaults(&mut value);
+        to_json(&value, config.indent())
     }
 }
+
+/// Serialize `value` on one line, or indented by `indent` spaces per level.
+pub fn to_json(value: &impl Serialize, indent: Option<usize>) -> Result<String> {
+    let Some(indent) = indent else {
+        return Ok(serde_json::to_string(value)?);
+    };
+
+    let indent = " ".repeat(indent);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut output = Vec::new();
+    value.serialize(&mut serde_json::Serializer::with_formatter(
+        &mut output,
+        formatter,
+    ))?;
+    Ok(String::from_utf8(output)?)
+}
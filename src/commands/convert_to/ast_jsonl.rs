@@ -0,0 +1,32 @@
+use super::InputFiles;
+use crate::config::Settings;
+use crate::input::{FrontMatter, ParsedDocument};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct FrontMatterLine<'a> {
+    front_matter: &'a FrontMatter,
+}
+
+#[derive(Args)]
+pub struct ConvertToAstJsonl {
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
+
+impl ConvertToAstJsonl {
+    /// One JSON line per top-level block, preceded by a `{"front_matter": ...}`
+    /// line if the document has front matter.
+    pub fn render(&self, document: &ParsedDocument, _settings: &Settings) -> Result<String> {
+        let mut lines = Vec::new();
+        if let Some(front_matter) = &document.front_matter {
+            lines.push(serde_json::to_string(&FrontMatterLine { front_matter })?);
+        }
+        for block in &document.document.blocks {
+            lines.push(serde_json::to_string(block)?);
+        }
+        Ok(lines.join("\n"))
+    }
+}
@@ -1,13 +1,30 @@
+use super::InputFiles;
+use crate::config::{AstYamlConfig, Settings};
+use crate::input::schema::omit_defaults;
+use crate::input::ParsedDocument;
 use anyhow::Result;
 use clap::Args;
 
 #[derive(Args)]
-pub struct ConvertToAstYaml;
+pub struct ConvertToAstYaml {
+    #[clap(flatten)]
+    pub config: AstYamlConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
 
 impl ConvertToAstYaml {
-    pub fn run(&self, ast: &markdown_ppp::ast::Document) -> Result<()> {
-        let result = serde_yaml::to_string(ast)?;
-        println!("{result}");
-        Ok(())
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.config.merge(&settings.ast_yaml);
+        if !config.omit_defaults.unwrap_or(false) {
+            return Ok(serde_yaml::to_string(document)?);
+        }
+
+        // Enum variants become single-key maps instead of YAML tags, which
+        // `--from ast-yaml` reads as well
+        let mut value = serde_json::to_value(document)?;
+        omit_defaults(&mut value);
+        Ok(serde_yaml::to_string(&value)?)
     }
 }
@@ -0,0 +1,35 @@
+use super::InputFiles;
+use crate::config::{DocxConfig, Settings};
+use crate::input::ParsedDocument;
+use crate::printer::docx::render_docx;
+use anyhow::Result;
+use clap::Args;
+use std::path::Path;
+
+#[derive(Args)]
+pub struct ConvertToDocx {
+    #[clap(flatten)]
+    pub config: DocxConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
+
+impl ConvertToDocx {
+    /// Render the DOCX package; images are looked up next to `source`.
+    pub fn render(
+        &self,
+        document: &ParsedDocument,
+        settings: &Settings,
+        source: Option<&Path>,
+    ) -> Result<Vec<u8>> {
+        let base_dir = source
+            .and_then(Path::parent)
+            .unwrap_or_else(|| Path::new(""));
+        let config = self
+            .config
+            .merge(&settings.docx)
+            .to_printer_config(|key| document.metadata(key), base_dir)?;
+        render_docx(&document.document, &config)
+    }
+}
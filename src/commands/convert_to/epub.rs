@@ -0,0 +1,237 @@
+use super::html::{document_title, render_body};
+use super::InputFiles;
+use crate::ast::{heading_level, inline_text};
+use crate::config::{EpubConfig, Settings};
+use crate::input::ParsedDocument;
+use crate::printer::epub::{render_epub, Chapter};
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use markdown_ppp::ast::{Block, Document, Inline};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Args)]
+pub struct ConvertToEpub {
+    #[clap(flatten)]
+    pub config: EpubConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
+
+impl ConvertToEpub {
+    /// Render the documents as the chapters of one book; the metadata comes
+    /// from the options or the front matter of the first document.
+    pub fn render(
+        &self,
+        documents: &[(&ParsedDocument, Option<&Path>)],
+        settings: &Settings,
+    ) -> Result<Vec<u8>> {
+        let config = self.config.merge(&settings.epub);
+        let Some((first, _)) = documents.first() else {
+            bail!("EPUB output requires at least one input");
+        };
+
+        let mut chapters = Vec::new();
+        for (document, source) in documents {
+            let base_dir = source
+                .and_then(Path::parent)
+                .unwrap_or_else(|| Path::new(""));
+            let parts = if config.split_chapters.unwrap_or(false) {
+                split_chapters(&document.document)
+            } else {
+                vec![document.document.clone()]
+            };
+            for part in parts {
+                let title = chapter_title(&part)
+                    .or_else(|| document.metadata("title"))
+                    .or_else(|| {
+                        source
+                            .and_then(Path::file_stem)
+                            .map(|stem| stem.to_string_lossy().to_string())
+                    })
+                    .unwrap_or_else(|| format!("Chapter {}", chapters.len() + 1));
+                let html = render_body(&part, &settings.html);
+                chapters.push(Chapter {
+                    title,
+                    html,
+                    base_dir: base_dir.to_path_buf(),
+                });
+            }
+        }
+
+        let mut stylesheets = Vec::new();
+        for (index, path) in config.css.iter().enumerate() {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read stylesheet: {}", path.display()))?;
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            stylesheets.push((format!("{}-{name}", index + 1), content));
+        }
+
+        let title = config
+            .title
+            .clone()
+            .unwrap_or_else(|| document_title(first));
+        let modified = documents
+            .iter()
+            .filter_map(|(_, source)| std::fs::metadata((*source)?).ok()?.modified().ok())
+            .max()
+            .unwrap_or_else(SystemTime::now);
+        let config = crate::printer::epub::Config {
+            identifier: first
+                .metadata("identifier")
+                .unwrap_or_else(|| identifier(&title, &chapters)),
+            author: config.author.clone().or_else(|| first.metadata("author")),
+            language: config.language(|key| first.metadata(key)),
+            modified: timestamp(modified),
+            stylesheets,
+            title,
+        };
+        render_epub(&chapters, &config)
+    }
+}
+
+/// Text of the first level 1 heading.
+fn chapter_title(document: &Document) -> Option<String> {
+    document.blocks.iter().find_map(|block| match block {
+        Block::Heading(heading) if heading_level(heading) == 1 => {
+            Some(inline_text(&heading.content).trim().to_string())
+        }
+        _ => None,
+    })
+}
+
+#[derive(Default)]
+struct FootnoteReferences {
+    labels: HashSet<String>,
+}
+
+impl Visitor for FootnoteReferences {
+    fn visit_inline(&mut self, inline: &Inline) {
+        if let Inline::FootnoteReference(label) = inline {
+            self.labels.insert(label.clone());
+        }
+        self.walk_inline(inline);
+    }
+}
+
+/// Split the document before every level 1 heading. Footnotes move to the
+/// first chapter that references them, and every chapter gets the link
+/// reference definitions.
+fn split_chapters(document: &Document) -> Vec<Document> {
+    let mut chapters: Vec<Document> = Vec::new();
+    let mut footnotes = Vec::new();
+    let mut definitions = Vec::new();
+    for block in &document.blocks {
+        match block {
+            Block::FootnoteDefinition(footnote) => footnotes.push(footnote),
+            Block::Definition(_) => definitions.push(block.clone()),
+            Block::Heading(heading) if heading_level(heading) == 1 => chapters.push(Document {
+                blocks: vec![block.clone()],
+            }),
+            _ => match chapters.last_mut() {
+                Some(chapter) => chapter.blocks.push(block.clone()),
+                None => chapters.push(Document {
+                    blocks: vec![block.clone()],
+                }),
+            },
+        }
+    }
+    if chapters.is_empty() {
+        chapters.push(Document { blocks: Vec::new() });
+    }
+
+    let references: Vec<HashSet<String>> = chapters
+        .iter()
+        .map(|chapter| {
+            let mut collector = FootnoteReferences::default();
+            chapter.visit_with(&mut collector);
+            collector.labels
+        })
+        .collect();
+    for footnote in footnotes {
+        let index = references
+            .iter()
+            .position(|labels| labels.contains(&footnote.label))
+            .unwrap_or(chapters.len() - 1);
+        chapters[index]
+            .blocks
+            .push(Block::FootnoteDefinition(footnote.clone()));
+    }
+    for chapter in &mut chapters {
+        chapter.blocks.extend(definitions.iter().cloned());
+    }
+    chapters
+}
+
+/// Namespace of the name-based UUIDs of books
+const BOOK_NAMESPACE: [u8; 16] = [
+    0xab, 0x7e, 0x79, 0xc1, 0xd0, 0x95, 0x44, 0xd1, 0x91, 0x16, 0x09, 0xcf, 0xeb, 0xb9, 0x8f, 0x47,
+];
+
+/// Version 5 UUID derived from the content, stable while the book doesn't
+/// change.
+fn identifier(title: &str, chapters: &[Chapter]) -> String {
+    let mut hasher = sha1_smol::Sha1::new();
+    hasher.update(&BOOK_NAMESPACE);
+    // Every text is preceded by its length so that texts can't run together
+    let texts = chapters
+        .iter()
+        .flat_map(|chapter| [chapter.title.as_str(), chapter.html.as_str()]);
+    for text in std::iter::once(title).chain(texts) {
+        hasher.update(&(text.len() as u64).to_le_bytes());
+        hasher.update(text.as_bytes());
+    }
+
+    let mut bytes = hasher.digest().bytes();
+    bytes[6] = (bytes[6] & 0x0f) | 0x50;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!(
+        "urn:uuid:{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// UTC time formatted like `2024-01-31T12:00:00Z`.
+fn timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+
+    // Civil date from days since 1970-01-01, after Howard Hinnant's algorithm
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
@@ -1,19 +1,193 @@
-use crate::config::HtmlConfig;
+use super::InputFiles;
+use crate::ast::{heading_level, inline_text};
+use crate::config::{HtmlConfig, Settings};
+use crate::input::ParsedDocument;
+use crate::template::{metadata_variables, Template};
+use crate::toc;
 use anyhow::Result;
 use clap::Args;
+use markdown_ppp::ast::{Block, Document, Heading, Inline};
+use markdown_ppp::ast_transform::{TransformWith, Transformer};
+use serde_json::{Map, Value};
 
 #[derive(Args)]
 pub struct ConvertToHtml {
     #[clap(flatten)]
     pub config: HtmlConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
 }
 
 impl ConvertToHtml {
-    pub fn run(&self, ast: &markdown_ppp::ast::Document) -> Result<()> {
-        let config = self.config.to_printer_config();
-        let result = markdown_ppp::html_printer::render_html(ast, config);
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let mut config = self.config.merge(&settings.html);
+        let template = config.template.as_deref().map(Template::load).transpose()?;
+        // The links of `toc` need heading ids to resolve
+        if template
+            .as_ref()
+            .is_some_and(|template| template.uses("toc"))
+        {
+            config.heading_ids.get_or_insert(true);
+        }
+        let mut body = render_body(&document.document, &config);
+
+        if config.title_block.unwrap_or(false) {
+            body = format!("{}{body}", title_block(document));
+        }
+        if let Some(template) = template {
+            let variables = template_variables(document, &config, settings, body);
+            return Ok(template.render(&variables));
+        }
+        if !config.standalone.unwrap_or(false) {
+            return Ok(body);
+        }
+        Ok(standalone_document(document, &config, &body))
+    }
+}
+
+/// Puts an empty `<a id>` element at the start of every heading, with the
+/// anchors of the headings in document order.
+struct HeadingAnchor {
+    anchors: std::vec::IntoIter<String>,
+}
+
+impl Transformer for HeadingAnchor {
+    fn transform_heading(&mut self, mut heading: Heading) -> Heading {
+        if let Some(anchor) = self.anchors.next() {
+            let target = format!("<a id=\"{}\"></a>", escape_html(&anchor));
+            heading.content.insert(0, Inline::Html(target));
+        }
+        self.walk_transform_heading(heading)
+    }
+}
+
+/// HTML of the document; with `heading_ids` set, headings are link targets
+/// for the anchors of the table of contents.
+pub fn render_body(document: &Document, config: &HtmlConfig) -> String {
+    let printer_config = config.to_printer_config();
+    if !config.heading_ids.unwrap_or(false) {
+        return markdown_ppp::html_printer::render_html(document, printer_config);
+    }
+
+    let anchor_prefix = config.anchor_prefix.as_deref().unwrap_or_default();
+    let anchors: Vec<String> = toc::collect_headings(document, anchor_prefix)
+        .into_iter()
+        .map(|entry| entry.anchor)
+        .collect();
+    let anchored = document.clone().transform_with(&mut HeadingAnchor {
+        anchors: anchors.into_iter(),
+    });
+    markdown_ppp::html_printer::render_html(&anchored, printer_config)
+}
+
+/// Header with the title, author and date from the front matter.
+fn title_block(document: &ParsedDocument) -> String {
+    let mut header = String::new();
+    for (key, element) in [("title", "h1"), ("author", "p"), ("date", "p")] {
+        if let Some(value) = document.metadata(key) {
+            header.push_str(&format!(
+                "<{element} class=\"{key}\">{}</{element}>\n",
+                escape_html(&value)
+            ));
+        }
+    }
 
-        println!("{result}");
-        Ok(())
+    if header.is_empty() {
+        return header;
     }
+    format!("<header>\n{header}</header>\n")
+}
+
+/// Title of the document: the front matter title, or the text of the first
+/// top-level heading.
+pub fn document_title(document: &ParsedDocument) -> String {
+    document
+        .metadata("title")
+        .or_else(|| {
+            document
+                .document
+                .blocks
+                .iter()
+                .find_map(|block| match block {
+                    Block::Heading(heading) if heading_level(heading) == 1 => {
+                        Some(inline_text(&heading.content).trim().to_string())
+                    }
+                    _ => None,
+                })
+        })
+        .unwrap_or_else(|| "Untitled".to_string())
+}
+
+/// Complete HTML document around the rendered body.
+fn standalone_document(document: &ParsedDocument, config: &HtmlConfig, body: &str) -> String {
+    let lang = document
+        .metadata("lang")
+        .map(|lang| format!(" lang=\"{}\"", escape_html(&lang)))
+        .unwrap_or_default();
+
+    let mut head = String::from(
+        "<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n",
+    );
+    head.push_str(&format!(
+        "<title>{}</title>\n",
+        escape_html(&document_title(document))
+    ));
+    for key in ["author", "description"] {
+        if let Some(value) = document.metadata(key) {
+            head.push_str(&format!(
+                "<meta name=\"{key}\" content=\"{}\">\n",
+                escape_html(&value)
+            ));
+        }
+    }
+    for stylesheet in &config.css {
+        head.push_str(&format!(
+            "<link rel=\"stylesheet\" href=\"{}\">\n",
+            escape_html(stylesheet)
+        ));
+    }
+
+    let body = body.trim_end();
+    format!(
+        "<!DOCTYPE html>\n<html{lang}>\n<head>\n{head}</head>\n<body>\n{body}\n</body>\n</html>\n"
+    )
+}
+
+/// Variables available to `--template`: front matter fields, `title`, `body`,
+/// `toc` and `css`.
+fn template_variables(
+    document: &ParsedDocument,
+    config: &HtmlConfig,
+    settings: &Settings,
+    body: String,
+) -> Map<String, Value> {
+    let mut variables = metadata_variables(document, escape_html);
+    variables.insert(
+        "title".to_string(),
+        escape_html(&document_title(document)).into(),
+    );
+    variables.insert("body".to_string(), body.into());
+
+    let anchor_prefix = config.anchor_prefix.as_deref().unwrap_or_default();
+    if let Some(toc) = toc::generate(&document.document, &settings.toc, anchor_prefix) {
+        let toc = Document { blocks: vec![toc] };
+        let rendered = markdown_ppp::html_printer::render_html(&toc, config.to_printer_config());
+        variables.insert("toc".to_string(), rendered.into());
+    }
+
+    let css: Vec<Value> = config
+        .css
+        .iter()
+        .map(|url| escape_html(url).into())
+        .collect();
+    variables.insert("css".to_string(), css.into());
+    variables
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
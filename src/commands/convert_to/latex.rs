@@ -1,19 +1,214 @@
-use crate::config::LatexConfig;
+use super::InputFiles;
+use crate::config::latex::{CodeStyle, TableStyle, DEFAULT_DOCUMENT_CLASS};
+use crate::config::{LatexConfig, Settings};
+use crate::input::ParsedDocument;
+use crate::template::{metadata_variables, Template};
+use crate::toc;
 use anyhow::Result;
 use clap::Args;
+use markdown_ppp::ast::{Block, Document, Inline};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+use serde_json::{Map, Value};
 
 #[derive(Args)]
 pub struct ConvertToLatex {
     #[clap(flatten)]
     pub config: LatexConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
 }
 
 impl ConvertToLatex {
-    pub fn run(&self, ast: &markdown_ppp::ast::Document) -> Result<()> {
-        let config = self.config.to_printer_config();
-        let result = markdown_ppp::latex_printer::render_latex(ast, config);
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.config.merge(&settings.latex);
+        let body = markdown_ppp::latex_printer::render_latex(
+            &document.document,
+            config.to_printer_config(),
+        );
+
+        let title_block = config.title_block.unwrap_or(false);
+        if let Some(path) = &config.template {
+            let variables = template_variables(document, &config, settings, body);
+            return Ok(Template::load(path)?.render(&variables));
+        }
+        if !config.standalone.unwrap_or(false) {
+            if !title_block {
+                return Ok(body);
+            }
+            return Ok(format!("{}{body}", title_block_commands(document)));
+        }
+        Ok(standalone_document(document, &config, &body, title_block))
+    }
+}
+
+/// `\maketitle` with the title, author and date from the front matter.
+fn title_block_commands(document: &ParsedDocument) -> String {
+    match title_commands(document) {
+        Some(commands) => format!("{commands}\\maketitle\n\n"),
+        None => String::new(),
+    }
+}
+
+/// `\title`, `\author` and `\date` from the front matter, if it has a title.
+fn title_commands(document: &ParsedDocument) -> Option<String> {
+    let title = document.metadata("title")?;
+
+    let mut commands = format!("\\title{{{}}}\n", escape_latex(&title));
+    if let Some(author) = document.metadata("author") {
+        commands.push_str(&format!("\\author{{{}}}\n", escape_latex(&author)));
+    }
+    // Without an explicit date LaTeX would print today's date
+    let date = document.metadata("date").unwrap_or_default();
+    commands.push_str(&format!("\\date{{{}}}\n", escape_latex(&date)));
+    Some(commands)
+}
+
+/// Complete document with a preamble loading the packages the body needs.
+fn standalone_document(
+    document: &ParsedDocument,
+    config: &LatexConfig,
+    body: &str,
+    title_block: bool,
+) -> String {
+    let class = config
+        .document_class
+        .as_deref()
+        .unwrap_or(DEFAULT_DOCUMENT_CLASS);
+
+    let mut output = format!("\\documentclass{{{class}}}\n");
+    for package in required_packages(config, &document.document) {
+        output.push_str(&format!("\\usepackage{package}\n"));
+    }
+
+    let title = title_commands(document);
+    if let Some(commands) = &title {
+        output.push('\n');
+        output.push_str(commands);
+    }
+
+    output.push_str("\n\\begin{document}\n\n");
+    if title_block && title.is_some() {
+        output.push_str("\\maketitle\n\n");
+    }
+    output.push_str(body.trim());
+    output.push_str("\n\n\\end{document}\n");
+    output
+}
+
+/// Variables available to `--template`: front matter fields, `body`, `toc`,
+/// `packages` and `documentclass`.
+fn template_variables(
+    document: &ParsedDocument,
+    config: &LatexConfig,
+    settings: &Settings,
+    body: String,
+) -> Map<String, Value> {
+    let mut variables = metadata_variables(document, escape_latex);
+    variables.insert("body".to_string(), body.into());
+
+    if toc::generate(&document.document, &settings.toc, "").is_some() {
+        variables.insert("toc".to_string(), "\\tableofcontents".into());
+    }
+
+    let packages: Vec<Value> = required_packages(config, &document.document)
+        .into_iter()
+        .map(|package| format!("\\usepackage{package}").into())
+        .collect();
+    variables.insert("packages".to_string(), packages.into());
+    let class = config
+        .document_class
+        .as_deref()
+        .unwrap_or(DEFAULT_DOCUMENT_CLASS);
+    variables.insert("documentclass".to_string(), class.into());
+    variables
+}
+
+/// Markdown constructs whose LaTeX rendering depends on extra packages.
+#[derive(Default)]
+struct ContentFeatures {
+    links: bool,
+    images: bool,
+    strikethrough: bool,
+    tables: bool,
+    code_blocks: bool,
+    task_lists: bool,
+}
+
+impl Visitor for ContentFeatures {
+    fn visit_block(&mut self, block: &Block) {
+        match block {
+            Block::Table(_) => self.tables = true,
+            Block::CodeBlock(_) => self.code_blocks = true,
+            Block::List(list) if list.items.iter().any(|item| item.task.is_some()) => {
+                self.task_lists = true
+            }
+            _ => {}
+        }
+        self.walk_block(block);
+    }
+
+    fn visit_inline(&mut self, inline: &Inline) {
+        match inline {
+            Inline::Link(_) | Inline::LinkReference(_) | Inline::Autolink(_) => self.links = true,
+            Inline::Image(_) => self.images = true,
+            Inline::Strikethrough(_) => self.strikethrough = true,
+            _ => {}
+        }
+        self.walk_inline(inline);
+    }
+}
+
+/// `\usepackage` arguments for the configured styles and the document content.
+fn required_packages(config: &LatexConfig, document: &Document) -> Vec<&'static str> {
+    let mut features = ContentFeatures::default();
+    document.visit_with(&mut features);
+
+    let mut packages = vec!["[utf8]{inputenc}", "[T1]{fontenc}"];
+    if features.tables {
+        match config.table_style {
+            Some(TableStyle::Booktabs) => packages.push("{booktabs}"),
+            Some(TableStyle::Longtabu) => packages.extend(["{longtable}", "{tabu}"]),
+            Some(TableStyle::Tabular) | None => {}
+        }
+    }
+    if features.code_blocks {
+        match config.code_style {
+            Some(CodeStyle::Listings) => packages.push("{listings}"),
+            Some(CodeStyle::Minted) => packages.push("{minted}"),
+            Some(CodeStyle::Verbatim) | None => {}
+        }
+    }
+    if features.images {
+        packages.push("{graphicx}");
+    }
+    if features.strikethrough {
+        packages.push("[normalem]{ulem}");
+    }
+    // Task checkboxes are rendered as $\boxtimes$ and $\square$
+    if features.task_lists {
+        packages.push("{amssymb}");
+    }
+    // hyperref should be loaded last
+    if features.links {
+        packages.push("{hyperref}");
+    }
+    packages
+}
 
-        println!("{result}");
-        Ok(())
+pub fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
     }
+    escaped
 }
@@ -0,0 +1,37 @@
+use super::InputFiles;
+use crate::config::{ManConfig, Settings};
+use crate::input::ParsedDocument;
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args)]
+pub struct ConvertToMan {
+    #[clap(flatten)]
+    pub config: ManConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
+
+impl ConvertToMan {
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.printer_config(document, settings)?;
+        Ok(crate::printer::man::render_man(&document.document, &config))
+    }
+
+    /// Manual section the page is rendered for, from the options, the
+    /// configuration or the front matter.
+    pub fn section(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        Ok(self.printer_config(document, settings)?.section)
+    }
+
+    fn printer_config(
+        &self,
+        document: &ParsedDocument,
+        settings: &Settings,
+    ) -> Result<crate::printer::man::Config> {
+        self.config
+            .merge(&settings.man)
+            .to_printer_config(|key| document.metadata(key))
+    }
+}
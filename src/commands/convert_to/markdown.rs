@@ -1,4 +1,6 @@
-use crate::config::MarkdownConfig;
+use super::InputFiles;
+use crate::config::{MarkdownConfig, Settings};
+use crate::input::ParsedDocument;
 use anyhow::Result;
 use clap::Args;
 
@@ -6,14 +8,17 @@
 pub struct ConvertToMarkdown {
     #[clap(flatten)]
     pub config: MarkdownConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
 }
 
 impl ConvertToMarkdown {
-    pub fn run(&self, ast: &markdown_ppp::ast::Document) -> Result<()> {
-        let config = self.config.to_printer_config();
-        let result = markdown_ppp::printer::render_markdown(ast, config);
-
-        println!("{result}");
-        Ok(())
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.config.merge(&settings.markdown).to_printer_config();
+        document.with_front_matter(markdown_ppp::printer::render_markdown(
+            &document.document,
+            config,
+        ))
     }
 }
@@ -1,20 +1,57 @@
+pub mod asciidoc;
 pub mod ast_json;
+pub mod ast_jsonl;
 pub mod ast_yaml;
+pub mod docx;
+pub mod epub;
 pub mod html;
 pub mod latex;
+pub mod man;
 pub mod markdown;
+pub mod org;
+pub mod rst;
+pub mod text;
+pub mod typst;
 
-use crate::input::{read_input, InputFormat};
+use crate::config::{ProjectConfig, Settings};
+use crate::diagnostic::report_error;
+use crate::input::paths::InputFile;
+use crate::input::schema::AST_SCHEMA;
+use crate::input::{expand_inputs, read_file, read_input, InputFormat, ParsedDocument};
+use crate::output::OutputSink;
+use crate::watch::watch;
+use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
 #[derive(Args)]
 pub struct ConvertTo {
-    /// Input format: markdown, ast-json, ast-yaml
+    /// Input format: markdown, html, org, ast-json, ast-jsonl, ast-yaml
     #[clap(long, short = 'f', default_value = "markdown")]
     pub from: InputFormat,
 
+    /// File to write the output to instead of stdout
+    #[clap(long, short = 'o', global = true, conflicts_with = "output_dir")]
+    pub output: Option<PathBuf>,
+
+    /// Directory to write one output file per input into
+    #[clap(long, global = true)]
+    pub output_dir: Option<PathBuf>,
+
+    /// Keep running and convert inputs again whenever they change
+    #[clap(long, global = true)]
+    pub watch: bool,
+
     #[clap(subcommand)]
-    pub output: OutputFormat,
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct InputFiles {
+    /// Input files or glob patterns (reads stdin if none are given)
+    pub inputs: Vec<String>,
 }
 
 #[derive(Subcommand)]
@@ -28,22 +65,282 @@ pub enum OutputFormat {
     /// Convert to AST JSON format
     #[clap(name = "ast-json")]
     AstJson(ast_json::ConvertToAstJson),
+    /// Convert to AST JSON Lines, one top-level block per line
+    #[clap(name = "ast-jsonl")]
+    AstJsonl(ast_jsonl::ConvertToAstJsonl),
     /// Convert to AST YAML format
     #[clap(name = "ast-yaml")]
     AstYaml(ast_yaml::ConvertToAstYaml),
+    /// Convert to plain text
+    Text(text::ConvertToText),
+    /// Convert to Typst markup
+    Typst(typst::ConvertToTypst),
+    /// Convert to AsciiDoc
+    Asciidoc(asciidoc::ConvertToAsciidoc),
+    /// Convert to reStructuredText
+    Rst(rst::ConvertToRst),
+    /// Convert to a roff man page
+    Man(man::ConvertToMan),
+    /// Convert to Org markup
+    Org(org::ConvertToOrg),
+    /// Convert to a Word document (DOCX)
+    Docx(docx::ConvertToDocx),
+    /// Convert to an EPUB 3 book, one chapter per input
+    Epub(epub::ConvertToEpub),
+}
+
+/// Converted document: text, or the bytes of a package format like DOCX.
+pub enum Converted {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl OutputFormat {
+    pub fn input(&self) -> &InputFiles {
+        match self {
+            OutputFormat::Markdown(cmd) => &cmd.input,
+            OutputFormat::Html(cmd) => &cmd.input,
+            OutputFormat::Latex(cmd) => &cmd.input,
+            OutputFormat::AstJson(cmd) => &cmd.input,
+            OutputFormat::AstJsonl(cmd) => &cmd.input,
+            OutputFormat::AstYaml(cmd) => &cmd.input,
+            OutputFormat::Text(cmd) => &cmd.input,
+            OutputFormat::Typst(cmd) => &cmd.input,
+            OutputFormat::Asciidoc(cmd) => &cmd.input,
+            OutputFormat::Rst(cmd) => &cmd.input,
+            OutputFormat::Man(cmd) => &cmd.input,
+            OutputFormat::Org(cmd) => &cmd.input,
+            OutputFormat::Docx(cmd) => &cmd.input,
+            OutputFormat::Epub(cmd) => &cmd.input,
+        }
+    }
+
+    /// File extension used when `document` is written into `--output-dir`
+    pub fn extension(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let extension = match self {
+            OutputFormat::Markdown(_) => "md",
+            OutputFormat::Html(_) => "html",
+            OutputFormat::Latex(_) => "tex",
+            OutputFormat::AstJson(_) => "json",
+            OutputFormat::AstJsonl(_) => "jsonl",
+            OutputFormat::AstYaml(_) => "yaml",
+            OutputFormat::Text(_) => "txt",
+            OutputFormat::Typst(_) => "typ",
+            OutputFormat::Asciidoc(_) => "adoc",
+            OutputFormat::Rst(_) => "rst",
+            // Man pages are named after their section, e.g. `tool.1`
+            OutputFormat::Man(cmd) => return cmd.section(document, settings),
+            OutputFormat::Org(_) => "org",
+            OutputFormat::Docx(_) => "docx",
+            OutputFormat::Epub(_) => "epub",
+        };
+        Ok(extension.to_string())
+    }
+
+    /// Convert the document; `source` is the input file, if any, which
+    /// DOCX output resolves image paths against.
+    pub fn render(
+        &self,
+        document: &ParsedDocument,
+        settings: &Settings,
+        source: Option<&Path>,
+    ) -> Result<Converted> {
+        let text = match self {
+            OutputFormat::Markdown(cmd) => cmd.render(document, settings),
+            OutputFormat::Html(cmd) => cmd.render(document, settings),
+            OutputFormat::Latex(cmd) => cmd.render(document, settings),
+            OutputFormat::AstJson(cmd) => cmd.render(document, settings),
+            OutputFormat::AstJsonl(cmd) => cmd.render(document, settings),
+            OutputFormat::AstYaml(cmd) => cmd.render(document, settings),
+            OutputFormat::Text(cmd) => cmd.render(document, settings),
+            OutputFormat::Typst(cmd) => cmd.render(document, settings),
+            OutputFormat::Asciidoc(cmd) => cmd.render(document, settings),
+            OutputFormat::Rst(cmd) => cmd.render(document, settings),
+            OutputFormat::Man(cmd) => cmd.render(document, settings),
+            OutputFormat::Org(cmd) => cmd.render(document, settings),
+            OutputFormat::Docx(cmd) => {
+                return Ok(Converted::Binary(cmd.render(document, settings, source)?))
+            }
+            OutputFormat::Epub(cmd) => {
+                return Ok(Converted::Binary(
+                    cmd.render(&[(document, source)], settings)?,
+                ))
+            }
+        };
+        text.map(Converted::Text)
+    }
 }
 
 impl ConvertTo {
-    pub fn run(&self) -> anyhow::Result<()> {
-        let input_text = read_input()?;
-        let ast = self.from.parse(&input_text)?;
-
-        match &self.output {
-            OutputFormat::Markdown(cmd) => cmd.run(&ast),
-            OutputFormat::Html(cmd) => cmd.run(&ast),
-            OutputFormat::Latex(cmd) => cmd.run(&ast),
-            OutputFormat::AstJson(cmd) => cmd.run(&ast),
-            OutputFormat::AstYaml(cmd) => cmd.run(&ast),
+    pub fn run(&self, project: &ProjectConfig) -> Result<()> {
+        if let OutputFormat::AstJson(cmd) = &self.format {
+            if cmd.schema {
+                let schema = Converted::Text(AST_SCHEMA.to_string());
+                write(&OutputSink::new(self.output.as_deref()), &schema)?;
+                return Ok(());
+            }
+        }
+
+        let inputs = &self.format.input().inputs;
+        if inputs.is_empty() {
+            if self.output_dir.is_some() {
+                bail!("--output-dir requires input files");
+            }
+            if self.watch {
+                bail!("--watch requires input files");
+            }
+            let input_text = read_input()?;
+            let input = self.from.parse(&input_text, None)?;
+            let settings = project.settings_for(None)?;
+            let result = self.format.render(&input, &settings, None)?;
+            write(&OutputSink::new(self.output.as_deref()), &result)?;
+            return Ok(());
+        }
+
+        let files = expand_inputs(inputs)?;
+        if let OutputFormat::Epub(book) = &self.format {
+            return self.convert_book(project, book, &files);
+        }
+        if self.output_dir.is_none() && files.len() > 1 {
+            bail!("--output-dir is required when converting more than one input");
+        }
+        if self.output_dir.is_some() {
+            check_distinct_outputs(&files)?;
+        }
+
+        if self.watch {
+            for file in &files {
+                if let Err(err) = self.convert_file(project, file) {
+                    report_error(&err);
+                }
+            }
+            let paths: Vec<PathBuf> = files.iter().map(|file| file.path.clone()).collect();
+            watch(&paths, |changed| {
+                let mut written = Vec::new();
+                for file in files
+                    .iter()
+                    .filter(|file| changed.contains(&file.path.as_path()))
+                {
+                    match self.convert_file(project, file) {
+                        Ok(output) => written.extend(output),
+                        Err(err) => report_error(&err),
+                    }
+                }
+                written
+            });
+        }
+
+        for file in &files {
+            self.convert_file(project, file)?;
+        }
+
+        Ok(())
+    }
+
+    /// Convert one input file to `--output` or stdout, or into `--output-dir`;
+    /// returns the file written, if any.
+    fn convert_file(&self, project: &ProjectConfig, file: &InputFile) -> Result<Option<PathBuf>> {
+        let input = self.parse_file(&file.path)?;
+        let settings = project.settings_for(Some(&file.path))?;
+        let result = self.format.render(&input, &settings, Some(&file.path))?;
+
+        let Some(output_dir) = &self.output_dir else {
+            write(&OutputSink::new(self.output.as_deref()), &result)?;
+            return Ok(self.output.clone());
+        };
+
+        let output_path = output_dir
+            .join(&file.relative)
+            .with_extension(self.format.extension(&input, &settings)?);
+        if !write(&OutputSink::File(output_path.clone()), &result)? {
+            return Ok(None);
+        }
+        println!(
+            "Converted: {} -> {}",
+            file.path.display(),
+            output_path.display()
+        );
+        Ok(Some(output_path))
+    }
+
+    /// Convert all inputs into one book, with the chapters in input order.
+    fn convert_book(
+        &self,
+        project: &ProjectConfig,
+        book: &epub::ConvertToEpub,
+        files: &[InputFile],
+    ) -> Result<()> {
+        if self.output_dir.is_some() {
+            bail!("--output-dir is not supported by epub, which writes one book; use --output");
+        }
+
+        let build = || -> Result<()> {
+            let documents = files
+                .iter()
+                .map(|file| self.parse_file(&file.path))
+                .collect::<Result<Vec<_>>>()?;
+            let sources: Vec<(&ParsedDocument, Option<&Path>)> = documents
+                .iter()
+                .zip(files)
+                .map(|(document, file)| (document, Some(file.path.as_path())))
+                .collect();
+            let settings = project.settings_for(files.first().map(|file| file.path.as_path()))?;
+            let result = Converted::Binary(book.render(&sources, &settings)?);
+            write(&OutputSink::new(self.output.as_deref()), &result)?;
+            Ok(())
+        };
+
+        if self.watch {
+            if let Err(err) = build() {
+                report_error(&err);
+            }
+            let paths: Vec<PathBuf> = files.iter().map(|file| file.path.clone()).collect();
+            watch(&paths, |_| {
+                if let Err(err) = build() {
+                    report_error(&err);
+                }
+                self.output.iter().cloned().collect()
+            });
+        }
+        build()
+    }
+
+    fn parse_file(&self, path: &Path) -> Result<ParsedDocument> {
+        let input_text = read_file(path)?;
+        self.from.parse(&input_text, Some(path))
+    }
+}
+
+/// Fail if two inputs would be written to the same file in `--output-dir`,
+/// before anything is written.
+fn check_distinct_outputs(files: &[InputFile]) -> Result<()> {
+    let mut outputs: HashMap<PathBuf, &Path> = HashMap::new();
+    for file in files {
+        // Outputs differ at most in the extension of the input
+        let output = file.relative.with_extension("");
+        if let Some(other) = outputs.insert(output, &file.path) {
+            bail!(
+                "Inputs {} and {} would be written to the same output file; \
+                 use a glob pattern to keep their directories apart",
+                other.display(),
+                file.path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Write the converted document; binary output is not written to a terminal.
+fn write(sink: &OutputSink, converted: &Converted) -> Result<bool> {
+    match converted {
+        Converted::Text(text) => sink.write(text),
+        Converted::Binary(bytes) => {
+            if matches!(sink, OutputSink::Stdout) && std::io::stdout().is_terminal() {
+                bail!(
+                    "Refusing to write binary output to a terminal, use --output or --output-dir"
+                );
+            }
+            sink.write_bytes(bytes)
         }
     }
 }
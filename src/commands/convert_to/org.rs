@@ -0,0 +1,56 @@
+use super::InputFiles;
+use crate::config::{OrgConfig, Settings};
+use crate::input::ParsedDocument;
+use crate::printer::org::render_org;
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args)]
+pub struct ConvertToOrg {
+    #[clap(flatten)]
+    pub config: OrgConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
+
+impl ConvertToOrg {
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.config.merge(&settings.org);
+        let body = render_org(&document.document, &config.to_printer_config());
+        Ok(format!("{}{body}", keywords(document)))
+    }
+}
+
+/// Front matter fields as `#+KEY: value` lines, title, author and date first.
+fn keywords(document: &ParsedDocument) -> String {
+    let Some(data) = document
+        .front_matter
+        .as_ref()
+        .and_then(|front_matter| front_matter.data.as_object())
+    else {
+        return String::new();
+    };
+
+    let mut keys: Vec<&str> = ["title", "author", "date"]
+        .into_iter()
+        .filter(|key| data.contains_key(*key))
+        .collect();
+    keys.extend(
+        data.keys()
+            .map(String::as_str)
+            .filter(|key| !matches!(*key, "title" | "author" | "date")),
+    );
+
+    let mut output = String::new();
+    for key in keys {
+        if let Some(value) = document.metadata(key) {
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            output.push_str(&format!("#+{}: {value}\n", key.to_uppercase()));
+        }
+    }
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    output
+}
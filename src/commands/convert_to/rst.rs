@@ -0,0 +1,47 @@
+use super::InputFiles;
+use crate::config::{RstConfig, Settings};
+use crate::input::ParsedDocument;
+use crate::printer::rst::{escape_text, render_rst};
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args)]
+pub struct ConvertToRst {
+    #[clap(flatten)]
+    pub config: RstConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
+
+impl ConvertToRst {
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.config.merge(&settings.rst);
+        let body = render_rst(&document.document, &config.to_printer_config());
+        Ok(format!("{}{body}", document_title(document)))
+    }
+}
+
+/// Document title with the author and date from the front matter as
+/// bibliographic fields.
+fn document_title(document: &ParsedDocument) -> String {
+    let Some(title) = document.metadata("title") else {
+        return String::new();
+    };
+
+    let title = escape_text(&title);
+    let rule = "=".repeat(title.chars().count());
+    let mut output = format!("{rule}\n{title}\n{rule}\n\n");
+
+    let mut has_fields = false;
+    for (key, field) in [("author", "Author"), ("date", "Date")] {
+        if let Some(value) = document.metadata(key) {
+            output.push_str(&format!(":{field}: {}\n", escape_text(&value)));
+            has_fields = true;
+        }
+    }
+    if has_fields {
+        output.push('\n');
+    }
+    output
+}
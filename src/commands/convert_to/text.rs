@@ -0,0 +1,24 @@
+use super::InputFiles;
+use crate::config::{Settings, TextConfig};
+use crate::input::ParsedDocument;
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args)]
+pub struct ConvertToText {
+    #[clap(flatten)]
+    pub config: TextConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
+
+impl ConvertToText {
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.config.merge(&settings.text).to_printer_config();
+        Ok(crate::printer::text::render_text(
+            &document.document,
+            &config,
+        ))
+    }
+}
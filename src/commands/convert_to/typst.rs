@@ -0,0 +1,62 @@
+use super::InputFiles;
+use crate::config::{Settings, TypstConfig};
+use crate::input::ParsedDocument;
+use crate::printer::typst::{escape_text, render_typst, string_literal};
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args)]
+pub struct ConvertToTypst {
+    #[clap(flatten)]
+    pub config: TypstConfig,
+
+    #[clap(flatten)]
+    pub input: InputFiles,
+}
+
+impl ConvertToTypst {
+    pub fn render(&self, document: &ParsedDocument, settings: &Settings) -> Result<String> {
+        let config = self.config.merge(&settings.typst);
+        let body = render_typst(&document.document, &config.to_printer_config());
+
+        let mut output = document_metadata(document);
+        if config.title_block.unwrap_or(false) {
+            output.push_str(&title_block(document));
+        }
+        output.push_str(&body);
+        Ok(output)
+    }
+}
+
+/// `#set document(...)` with the title and author from the front matter.
+fn document_metadata(document: &ParsedDocument) -> String {
+    let mut arguments = Vec::new();
+    if let Some(title) = document.metadata("title") {
+        arguments.push(format!("title: {}", string_literal(&title)));
+    }
+    if let Some(author) = document.metadata("author") {
+        arguments.push(format!("author: {}", string_literal(&author)));
+    }
+    if arguments.is_empty() {
+        return String::new();
+    }
+    format!("#set document({})\n\n", arguments.join(", "))
+}
+
+/// Centered title, author and date from the front matter, if it has a title.
+fn title_block(document: &ParsedDocument) -> String {
+    let Some(title) = document.metadata("title") else {
+        return String::new();
+    };
+
+    let mut lines = vec![format!(
+        "#text(size: 1.6em, weight: \"bold\")[{}]",
+        escape_text(&title)
+    )];
+    for key in ["author", "date"] {
+        if let Some(value) = document.metadata(key) {
+            lines.push(format!("#linebreak() {}", escape_text(&value)));
+        }
+    }
+    format!("#align(center)[\n  {}\n]\n\n", lines.join("\n  "))
+}
@@ -0,0 +1,262 @@
+use crate::ast::{heading_level, inline_text, CodeInfo};
+use crate::input::{expand_inputs, read_file, read_input, InputFormat};
+use crate::output::OutputSink;
+use anyhow::{bail, Result};
+use clap::Args;
+use markdown_ppp::ast::{Block, CodeBlock, Document};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Args)]
+pub struct ExtractCode {
+    /// Only extract code blocks in these languages (comma-separated or repeated)
+    #[clap(long, value_delimiter = ',')]
+    pub lang: Vec<String>,
+
+    /// Only extract code blocks whose info string has this attribute, given as
+    /// `key` or `key=value` (repeatable, all must match)
+    #[clap(long)]
+    pub attr: Vec<String>,
+
+    /// Write blocks without a `file=` attribute to one file per input and
+    /// language, e.g. `runbook.sql`
+    #[clap(long)]
+    pub per_language: bool,
+
+    /// Start every snippet with a comment naming its source file and heading
+    #[clap(long)]
+    pub comments: bool,
+
+    /// Directory to write the extracted files into
+    #[clap(long, default_value = ".")]
+    pub output_dir: PathBuf,
+
+    /// Input format: markdown, html, org, ast-json, ast-jsonl, ast-yaml
+    #[clap(long, short = 'f', default_value = "markdown")]
+    pub from: InputFormat,
+
+    /// Input files or glob patterns (reads stdin if none are given)
+    pub inputs: Vec<String>,
+}
+
+/// Code block together with the headings it is nested under.
+struct Snippet {
+    headings: Vec<String>,
+    code_block: CodeBlock,
+}
+
+#[derive(Default)]
+struct SnippetCollector {
+    /// Open headings as (level, text), innermost last
+    headings: Vec<(u8, String)>,
+    snippets: Vec<Snippet>,
+}
+
+impl Visitor for SnippetCollector {
+    fn visit_block(&mut self, block: &Block) {
+        match block {
+            Block::Heading(heading) => {
+                let level = heading_level(heading);
+                while self.headings.last().is_some_and(|(open, _)| *open >= level) {
+                    self.headings.pop();
+                }
+                self.headings
+                    .push((level, inline_text(&heading.content).trim().to_string()));
+            }
+            Block::CodeBlock(code_block) => self.snippets.push(Snippet {
+                headings: self.headings.iter().map(|(_, text)| text.clone()).collect(),
+                code_block: code_block.clone(),
+            }),
+            _ => {}
+        }
+        self.walk_block(block);
+    }
+}
+
+impl ExtractCode {
+    pub fn run(&self) -> Result<()> {
+        // Content of every output file, in the order the snippets appear
+        let mut outputs: BTreeMap<PathBuf, String> = BTreeMap::new();
+        // Input each per-language output file is named after
+        let mut named_after: HashMap<PathBuf, PathBuf> = HashMap::new();
+
+        if self.inputs.is_empty() {
+            let input = self.from.parse(&read_input()?, None)?;
+            self.collect(&input.document, None, &mut outputs, &mut named_after)?;
+        }
+        for file in expand_inputs(&self.inputs)? {
+            let input = self.from.parse(&read_file(&file.path)?, Some(&file.path))?;
+            self.collect(
+                &input.document,
+                Some(&file.path),
+                &mut outputs,
+                &mut named_after,
+            )?;
+        }
+
+        if outputs.is_empty() {
+            println!("No code blocks to extract");
+            return Ok(());
+        }
+        for (path, content) in &outputs {
+            let path = self.output_dir.join(path);
+            if OutputSink::File(path.clone()).write(content)? {
+                println!("Extracted: {}", path.display());
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the selected snippets of the document to the output files.
+    fn collect(
+        &self,
+        document: &Document,
+        source: Option<&Path>,
+        outputs: &mut BTreeMap<PathBuf, String>,
+        named_after: &mut HashMap<PathBuf, PathBuf>,
+    ) -> Result<()> {
+        let mut collector = SnippetCollector::default();
+        document.visit_with(&mut collector);
+
+        for snippet in collector.snippets {
+            let info = CodeInfo::parse(&snippet.code_block.kind);
+            if !self.is_selected(&info) {
+                continue;
+            }
+
+            let language = info.language.as_deref().unwrap_or_default();
+            let target = match info.attribute("file") {
+                Some(file) => output_path(file)?,
+                None if self.per_language && !language.is_empty() => {
+                    let stem = source
+                        .and_then(Path::file_stem)
+                        .map_or("snippets".into(), |stem| stem.to_string_lossy());
+                    let target = output_path(&format!("{stem}.{}", extension(language)))?;
+                    if let Some(source) = source {
+                        let other = named_after
+                            .entry(target.clone())
+                            .or_insert_with(|| source.to_path_buf());
+                        if other != source {
+                            bail!(
+                                "Inputs {} and {} would both be extracted to {}; \
+                                 extract them separately or name the files with `file=`",
+                                other.display(),
+                                source.display(),
+                                target.display()
+                            );
+                        }
+                    }
+                    target
+                }
+                None => continue,
+            };
+
+            let output = outputs.entry(target).or_default();
+            let mut code = snippet.code_block.literal.clone();
+            if !code.ends_with('\n') {
+                code.push('\n');
+            }
+            if self.comments {
+                let source =
+                    source.map_or("<stdin>".to_string(), |path| path.display().to_string());
+                let location = if snippet.headings.is_empty() {
+                    source
+                } else {
+                    format!("{source}: {}", snippet.headings.join(" > "))
+                };
+                code = annotate(&code, language, &location, output.is_empty());
+            }
+            output.push_str(&code);
+        }
+        Ok(())
+    }
+
+    fn is_selected(&self, info: &CodeInfo) -> bool {
+        let language_matches = self.lang.is_empty()
+            || info.language.as_ref().is_some_and(|language| {
+                self.lang
+                    .iter()
+                    .any(|wanted| wanted.eq_ignore_ascii_case(language))
+            });
+        language_matches
+            && self.attr.iter().all(|attr| match attr.split_once('=') {
+                Some((key, value)) => info.attribute(key) == Some(value),
+                None => info.attribute(attr).is_some(),
+            })
+    }
+}
+
+/// Path of an output file named by a `file=` attribute or derived from the
+/// language, which must stay inside the output directory.
+fn output_path(file: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(file);
+    let inside = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if file.is_empty() || !inside {
+        bail!("Code block file must be a relative path inside the output directory: {file}");
+    }
+    Ok(path)
+}
+
+/// Prefix the code with a comment naming its location; a shebang line stays
+/// first in the file.
+fn annotate(code: &str, language: &str, location: &str, starts_file: bool) -> String {
+    let Some((open, close)) = comment_syntax(language) else {
+        return code.to_string();
+    };
+    let comment = format!("{open}{location}{close}\n");
+    match code.split_once('\n') {
+        Some((shebang, rest)) if starts_file && shebang.starts_with("#!") => {
+            format!("{shebang}\n{comment}{rest}")
+        }
+        _ => format!("{comment}{code}"),
+    }
+}
+
+/// Comment delimiters of a language, if it has comments.
+fn comment_syntax(language: &str) -> Option<(&'static str, &'static str)> {
+    let syntax = match language.to_lowercase().as_str() {
+        "sh" | "bash" | "zsh" | "shell" | "fish" | "python" | "py" | "ruby" | "rb" | "perl"
+        | "pl" | "yaml" | "yml" | "toml" | "r" | "dockerfile" | "makefile" | "make"
+        | "powershell" | "ps1" | "nix" | "elixir" | "ex" | "conf" => ("# ", ""),
+        "sql" | "psql" | "mysql" | "plsql" | "lua" | "haskell" | "hs" | "ada" => ("-- ", ""),
+        "rust" | "rs" | "c" | "h" | "cpp" | "c++" | "java" | "javascript" | "js" | "typescript"
+        | "ts" | "go" | "kotlin" | "kt" | "swift" | "scala" | "csharp" | "cs" | "php" | "jsonc"
+        | "groovy" | "dart" | "scss" => ("// ", ""),
+        "html" | "xml" | "svg" | "markdown" | "md" => ("<!-- ", " -->"),
+        "css" => ("/* ", " */"),
+        "lisp" | "clojure" | "scheme" | "elisp" | "ini" => ("; ", ""),
+        "tex" | "latex" | "erlang" | "matlab" => ("% ", ""),
+        "vim" => ("\" ", ""),
+        _ => return None,
+    };
+    Some(syntax)
+}
+
+/// File extension for the code of a language.
+fn extension(language: &str) -> String {
+    let language = language.to_lowercase();
+    let extension = match language.as_str() {
+        "bash" | "zsh" | "shell" => "sh",
+        "python" => "py",
+        "ruby" => "rb",
+        "perl" => "pl",
+        "javascript" => "js",
+        "typescript" => "ts",
+        "rust" => "rs",
+        "yml" => "yaml",
+        "markdown" => "md",
+        "haskell" => "hs",
+        "kotlin" => "kt",
+        "csharp" => "cs",
+        "c++" => "cpp",
+        "powershell" => "ps1",
+        "elixir" => "ex",
+        "latex" => "tex",
+        "psql" | "mysql" | "plsql" => "sql",
+        _ => return language,
+    };
+    extension.to_string()
+}
@@ -1,9 +1,45 @@
-use crate::config::MarkdownConfig;
+use crate::code_format::format_code_blocks;
+use crate::commands::lint::ReportFormat;
+use crate::config::{CodeFormatConfig, MarkdownConfig, ProjectConfig};
+use crate::diagnostic::report_error;
+use crate::diff::{hunks, unified_diff, LineRange};
+use crate::input::walk::{find_files, FileFilter};
 use crate::input::InputFormat;
+use crate::output::write_file_atomically;
+use crate::toc;
+use crate::watch::watch;
 use anyhow::{Context, Result};
 use clap::Args;
+use core::str::FromStr;
+use serde::Serialize;
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+#[derive(Clone, Copy)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(format!(
+                "Invalid color mode: {s}. Supported: auto, always, never"
+            )),
+        }
+    }
+}
 
 #[derive(Args)]
 pub struct Format {
@@ -14,50 +50,131 @@ pub struct Format {
     #[clap(long, short = 'n')]
     pub dry_run: bool,
 
-    /// Files to format
+    /// Print a unified diff of the changes instead of writing them
+    #[clap(long)]
+    pub diff: bool,
+
+    /// When to color the diff: auto, always, never
+    #[clap(long, default_value = "auto", value_name = "WHEN")]
+    pub color: ColorMode,
+
+    /// Report format: human, json (file status and changed line ranges)
+    #[clap(long, default_value = "human", conflicts_with = "diff")]
+    pub report: ReportFormat,
+
+    /// Format code blocks of a language with a command, e.g. `rust=rustfmt` or
+    /// `json=builtin:json` (repeatable)
+    #[clap(long = "code-formatter", value_name = "LANG=COMMAND", value_parser = parse_formatter)]
+    pub code_formatters: Vec<(String, String)>,
+
+    /// Seconds a code formatter may run before it is stopped [default: 10]
+    #[clap(long, value_name = "SECONDS")]
+    pub code_formatter_timeout: Option<u64>,
+
+    /// Run the code formatter commands of the configuration file; without it
+    /// only its built-in formatters are used
+    #[clap(long)]
+    pub allow_config_formatters: bool,
+
+    /// Keep running and format files again whenever they change
+    #[clap(long, conflicts_with_all = ["dry_run", "diff", "report"])]
+    pub watch: bool,
+
+    /// Glob of the files to format when searching directories (repeatable)
+    /// [default: *.md, *.markdown]
+    #[clap(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Glob of the files and directories to skip when searching directories
+    /// (repeatable)
+    #[clap(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Files or directories to format
     #[clap(required = true)]
     pub files: Vec<PathBuf>,
 }
 
-impl Format {
-    pub fn run(&self) -> Result<()> {
-        let mut needs_formatting = false;
+/// Result of formatting one file.
+struct FormattedFile<'a> {
+    path: &'a Path,
+    original: String,
+    formatted: String,
+    stale_toc: bool,
+    /// Problems that did not stop the file from being formatted
+    warnings: Vec<String>,
+}
 
-        for file_path in &self.files {
-            let original_content = fs::read_to_string(file_path)
-                .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+impl FormattedFile<'_> {
+    fn changed(&self) -> bool {
+        self.original != self.formatted
+    }
+}
 
-            let ast = InputFormat::Markdown
-                .parse(&original_content)
-                .with_context(|| format!("Failed to parse file: {}", file_path.display()))?;
+#[derive(Serialize)]
+struct FileReport {
+    file: String,
+    /// `unchanged`, `needs-formatting` or `formatted`
+    status: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    changes: Vec<ChangedLines>,
+}
 
-            let printer_config = self.config.to_printer_config();
-            let mut formatted_content =
-                markdown_ppp::printer::render_markdown(&ast, printer_config);
+/// Lines of the original file replaced by lines of the formatted file.
+#[derive(Serialize)]
+struct ChangedLines {
+    original: LineRange,
+    formatted: LineRange,
+}
 
-            // Ensure the formatted content ends with a newline if the original did
-            if original_content.ends_with('\n') && !formatted_content.ends_with('\n') {
-                formatted_content.push('\n');
+impl Format {
+    pub fn run(&self, project: &ProjectConfig) -> Result<()> {
+        if let Some(path) = &project.path {
+            if !self.allow_config_formatters && project.has_formatter_commands() {
+                eprintln!(
+                    "Warning: {}: code formatter commands not run, pass --allow-config-formatters to run them",
+                    path.display()
+                );
             }
-
-            if original_content != formatted_content {
-                needs_formatting = true;
-                if self.dry_run {
-                    println!("File needs formatting: {}", file_path.display());
-                } else {
-                    self.write_file_atomically(file_path, &formatted_content)
-                        .with_context(|| {
-                            format!("Failed to write file: {}", file_path.display())
-                        })?;
-                    println!("Formatted: {}", file_path.display());
+        }
+        let files = self.collect_files()?;
+        if self.watch {
+            for file_path in &files {
+                if let Err(err) = self.process_file(project, file_path) {
+                    report_error(&err);
                 }
             }
+            watch(&files, |changed| {
+                let mut written = Vec::new();
+                for file_path in changed {
+                    match self.process_file(project, file_path) {
+                        Ok(true) => written.push(file_path.to_path_buf()),
+                        Ok(false) => {}
+                        Err(err) => report_error(&err),
+                    }
+                }
+                written
+            });
         }
 
-        if self.dry_run {
+        let mut needs_formatting = false;
+        let mut reports = Vec::new();
+        for file in self.format_files(project, &files) {
+            let file = file?;
+            needs_formatting |= file.changed();
+            self.finish(&file)?;
+            if matches!(self.report, ReportFormat::Json) {
+                reports.push(self.file_report(&file));
+            }
+        }
+
+        if matches!(self.report, ReportFormat::Json) {
+            println!("{}", serde_json::to_string_pretty(&reports)?);
+        }
+        if self.is_check() {
             if needs_formatting {
                 std::process::exit(1);
-            } else {
+            } else if matches!(self.report, ReportFormat::Human) {
                 println!("All files are already formatted");
             }
         }
@@ -65,26 +182,196 @@ pub fn run(&self) -> Result<()> {
         Ok(())
     }
 
-    fn write_file_atomically(&self, file_path: &PathBuf, content: &str) -> Result<()> {
-        let temp_file_path = file_path.with_extension(format!(
-            "{}.tmp",
-            file_path.extension().unwrap_or_default().to_string_lossy()
-        ));
-
-        fs::write(&temp_file_path, content).with_context(|| {
-            format!(
-                "Failed to write temporary file: {}",
-                temp_file_path.display()
-            )
-        })?;
-
-        fs::rename(&temp_file_path, file_path).with_context(|| {
-            format!(
-                "Failed to rename temporary file to: {}",
-                file_path.display()
-            )
-        })?;
+    /// Whether files are only checked, not written.
+    fn is_check(&self) -> bool {
+        self.dry_run || self.diff
+    }
+
+    /// Files given on the command line, with directories replaced by the files
+    /// found in them. Each file is listed once.
+    fn collect_files(&self) -> Result<Vec<PathBuf>> {
+        let filter = FileFilter::new(&self.include, &self.exclude)?;
+        let mut files = Vec::new();
+        let mut seen = HashSet::new();
+        for path in &self.files {
+            let found = if path.is_dir() {
+                find_files(path, &filter)?
+            } else {
+                vec![path.clone()]
+            };
+            for file in found {
+                // Different spellings of a path, like `a.md` and `./a.md`,
+                // name the same file; a missing file is reported later
+                let key = fs::canonicalize(&file).unwrap_or_else(|_| file.clone());
+                if seen.insert(key) {
+                    files.push(file);
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    /// Format the files on all CPU cores; the results keep the order of `files`.
+    fn format_files<'a>(
+        &self,
+        project: &ProjectConfig,
+        files: &'a [PathBuf],
+    ) -> Vec<Result<FormattedFile<'a>>> {
+        let workers = thread::available_parallelism()
+            .map_or(1, usize::from)
+            .min(files.len());
+        let next = AtomicUsize::new(0);
+        let mut results: Vec<(usize, Result<FormattedFile>)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(file_path) = files.get(index) else {
+                                break;
+                            };
+                            results.push((index, self.format_file(project, file_path)));
+                        }
+                        results
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("Formatting thread panicked"))
+                .collect()
+        });
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Format a file and write it or report it; returns whether it was written.
+    fn process_file(&self, project: &ProjectConfig, file_path: &Path) -> Result<bool> {
+        let file = self.format_file(project, file_path)?;
+        self.finish(&file)?;
+        Ok(file.changed() && !self.is_check())
+    }
+
+    /// Format a single file without writing it.
+    fn format_file<'a>(
+        &self,
+        project: &ProjectConfig,
+        file_path: &'a Path,
+    ) -> Result<FormattedFile<'a>> {
+        let original_content = fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+
+        let mut input = InputFormat::Markdown.parse(&original_content, Some(file_path))?;
+        let ast = &mut input.document;
+
+        let settings = project.settings_for(Some(file_path))?;
+        let anchor_prefix = settings.html.anchor_prefix.as_deref().unwrap_or_default();
+        let toc = toc::generate(ast, &settings.toc, anchor_prefix);
+        let stale_toc = toc::replace_in_document(ast, toc) == Some(true);
+
+        let config_code_format = if self.allow_config_formatters {
+            settings.code_format
+        } else {
+            settings.code_format.builtin_only()
+        };
+        let code_format = self.cli_code_format().merge(&config_code_format);
+        let warnings = format_code_blocks(&mut input.document.blocks, &code_format, file_path);
+
+        let printer_config = self.config.merge(&settings.markdown).to_printer_config();
+        let mut formatted_content = input.with_front_matter(
+            markdown_ppp::printer::render_markdown(&input.document, printer_config),
+        )?;
+
+        // Ensure the formatted content ends with a newline if the original did
+        if original_content.ends_with('\n') && !formatted_content.ends_with('\n') {
+            formatted_content.push('\n');
+        }
+
+        Ok(FormattedFile {
+            path: file_path,
+            original: original_content,
+            formatted: formatted_content,
+            stale_toc,
+            warnings,
+        })
+    }
 
+    /// Write a changed file, or only report it when checking.
+    fn finish(&self, file: &FormattedFile) -> Result<()> {
+        for warning in &file.warnings {
+            eprintln!("Warning: {warning}");
+        }
+        if !file.changed() {
+            return Ok(());
+        }
+        let human = matches!(self.report, ReportFormat::Human);
+        let path = file.path.display();
+
+        if self.diff {
+            let color = match self.color {
+                ColorMode::Auto => {
+                    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+                }
+                ColorMode::Always => true,
+                ColorMode::Never => false,
+            };
+            if file.stale_toc {
+                println!("Table of contents is out of date: {path}");
+            }
+            print!(
+                "{}",
+                unified_diff(&path.to_string(), &file.original, &file.formatted, color)
+            );
+        } else if self.dry_run && human && file.stale_toc {
+            println!("Table of contents is out of date: {path}");
+        } else if self.dry_run && human {
+            println!("File needs formatting: {path}");
+        } else if !self.dry_run {
+            write_file_atomically(file.path, &file.formatted)
+                .with_context(|| format!("Failed to write file: {path}"))?;
+            if human {
+                println!("Formatted: {path}");
+            }
+        }
         Ok(())
     }
+
+    fn file_report(&self, file: &FormattedFile) -> FileReport {
+        let status = match (file.changed(), self.is_check()) {
+            (false, _) => "unchanged",
+            (true, true) => "needs-formatting",
+            (true, false) => "formatted",
+        };
+        let changes = hunks(&file.original, &file.formatted, 0)
+            .into_iter()
+            .map(|hunk| ChangedLines {
+                original: hunk.old,
+                formatted: hunk.new,
+            })
+            .collect();
+        FileReport {
+            file: file.path.display().to_string(),
+            status,
+            changes,
+        }
+    }
+
+    fn cli_code_format(&self) -> CodeFormatConfig {
+        CodeFormatConfig {
+            formatters: self.code_formatters.iter().cloned().collect(),
+            timeout: self.code_formatter_timeout,
+        }
+    }
+}
+
+fn parse_formatter(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((language, command)) if !language.trim().is_empty() && !command.trim().is_empty() => {
+            Ok((language.trim().to_string(), command.trim().to_string()))
+        }
+        _ => Err(format!(
+            "Invalid code formatter: {s}. Expected LANG=COMMAND"
+        )),
+    }
 }
@@ -0,0 +1,164 @@
+use crate::config::{LintConfig, ProjectConfig};
+use crate::diagnostic::SourceDiagnostic;
+use crate::input::{read_file, InputFormat};
+use crate::lint::{rules::all_rules, Diagnostic, Linter, RuleLevel, Severity};
+use anyhow::{anyhow, Result};
+use clap::Args;
+use core::str::FromStr;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Clone)]
+pub enum ReportFormat {
+    Human,
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "human" => Ok(ReportFormat::Human),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!(
+                "Invalid report format: {s}. Supported: human, json"
+            )),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct Lint {
+    /// Enable rules by id (comma-separated or repeated)
+    #[clap(long, value_delimiter = ',')]
+    pub enable: Vec<String>,
+
+    /// Disable rules by id (comma-separated or repeated)
+    #[clap(long, value_delimiter = ',')]
+    pub disable: Vec<String>,
+
+    /// Report format: human, json
+    #[clap(long, default_value = "human")]
+    pub report: ReportFormat,
+
+    /// List available rules and exit
+    #[clap(long)]
+    pub list_rules: bool,
+
+    /// Files to check
+    #[clap(required_unless_present = "list_rules")]
+    pub files: Vec<PathBuf>,
+}
+
+struct LintResult<'a> {
+    path: &'a PathBuf,
+    content: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Serialize)]
+struct FileDiagnostic<'a> {
+    file: String,
+    #[serde(flatten)]
+    diagnostic: &'a Diagnostic,
+}
+
+impl Lint {
+    pub fn run(&self, project: &ProjectConfig) -> Result<()> {
+        if self.list_rules {
+            for rule in all_rules() {
+                println!(
+                    "{:<22} {:<8} {}",
+                    rule.id(),
+                    rule.default_severity(),
+                    rule.description()
+                );
+            }
+            return Ok(());
+        }
+
+        let mut results = Vec::new();
+        for file_path in &self.files {
+            let content = read_file(file_path)?;
+            let input = InputFormat::Markdown.parse(&content, Some(file_path))?;
+
+            let settings = project.settings_for(Some(file_path))?;
+            let linter = Linter::new(&self.cli_config().merge(&settings.lint).rules)
+                .map_err(|err| anyhow!(err))?;
+            let diagnostics = linter.check(&input.document, &content);
+            results.push(LintResult {
+                path: file_path,
+                content,
+                diagnostics,
+            });
+        }
+
+        match self.report {
+            ReportFormat::Human => print_human(&results),
+            ReportFormat::Json => print_json(&results)?,
+        }
+
+        let has_errors = results
+            .iter()
+            .flat_map(|result| &result.diagnostics)
+            .any(|diagnostic| diagnostic.severity == Severity::Error);
+        if has_errors {
+            std::process::exit(1);
+        }
+
+        Ok(())
+    }
+
+    fn cli_config(&self) -> LintConfig {
+        let mut config = LintConfig::default();
+        for id in &self.enable {
+            let severity = all_rules()
+                .into_iter()
+                .find(|rule| rule.id() == id)
+                .map_or(RuleLevel::Warning, |rule| rule.default_severity().into());
+            config.rules.insert(id.clone(), severity);
+        }
+        for id in &self.disable {
+            config.rules.insert(id.clone(), RuleLevel::Off);
+        }
+        config
+    }
+}
+
+fn print_human(results: &[LintResult]) {
+    let mut counts = [0usize; 3];
+    for result in results {
+        for diagnostic in &result.diagnostics {
+            let mut report = SourceDiagnostic::error(&diagnostic.message)
+                .with_label(format!("{}[{}]", diagnostic.severity, diagnostic.rule))
+                .with_path(Some(result.path));
+            if let Some(position) = diagnostic.position {
+                report = report.at(&result.content, position);
+            }
+            println!("{report}\n");
+            counts[diagnostic.severity as usize] += 1;
+        }
+    }
+
+    let [infos, warnings, errors] = counts;
+    if infos + warnings + errors == 0 {
+        println!("No problems found");
+    } else {
+        println!("Found {errors} error(s), {warnings} warning(s), {infos} info message(s)");
+    }
+}
+
+fn print_json(results: &[LintResult]) -> Result<()> {
+    let report: Vec<_> = results
+        .iter()
+        .flat_map(|result| {
+            result.diagnostics.iter().map(|diagnostic| FileDiagnostic {
+                file: result.path.display().to_string(),
+                diagnostic,
+            })
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
@@ -1,5 +1,15 @@
+pub mod config;
 pub mod convert_to;
+pub mod extract_code;
 pub mod format;
+pub mod lint;
+pub mod query;
+pub mod toc;
 
+pub use config::Config;
 pub use convert_to::ConvertTo;
+pub use extract_code::ExtractCode;
 pub use format::Format;
+pub use lint::Lint;
+pub use query::Query;
+pub use toc::Toc;
@@ -0,0 +1,127 @@
+use crate::commands::convert_to::ast_json::to_json;
+use crate::config::{ProjectConfig, Settings};
+use crate::input::{expand_inputs, read_file, read_input, InputFormat, ParsedDocument};
+use crate::query::{select, Node, Selector};
+use anyhow::Result;
+use clap::Args;
+use core::str::FromStr;
+use markdown_ppp::ast::{Block, Document, List};
+
+#[derive(Clone)]
+pub enum MatchFormat {
+    Markdown,
+    Text,
+    AstJson,
+}
+
+impl FromStr for MatchFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" => Ok(MatchFormat::Markdown),
+            "text" => Ok(MatchFormat::Text),
+            "ast-json" => Ok(MatchFormat::AstJson),
+            _ => Err(format!(
+                "Invalid output format: {s}. Supported: markdown, text, ast-json"
+            )),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct Query {
+    /// Selector like `code-block[lang=sql]` or `section[title=Installation] link`
+    pub selector: Selector,
+
+    /// Input format: markdown, html, org, ast-json, ast-jsonl, ast-yaml
+    #[clap(long, short = 'f', default_value = "markdown")]
+    pub from: InputFormat,
+
+    /// Print the matches as: markdown, text, ast-json
+    #[clap(long, short = 't', default_value = "markdown")]
+    pub to: MatchFormat,
+
+    /// Input files or glob patterns (reads stdin if none are given)
+    pub inputs: Vec<String>,
+}
+
+impl Query {
+    pub fn run(&self, project: &ProjectConfig) -> Result<()> {
+        let mut documents = Vec::new();
+        if self.inputs.is_empty() {
+            let input = self.from.parse(&read_input()?, None)?;
+            documents.push((input, project.settings_for(None)?));
+        }
+        for file in expand_inputs(&self.inputs)? {
+            let input = self.from.parse(&read_file(&file.path)?, Some(&file.path))?;
+            documents.push((input, project.settings_for(Some(&file.path))?));
+        }
+
+        let output = match self.to {
+            MatchFormat::AstJson => {
+                let matches: Vec<Node> = documents
+                    .iter()
+                    .flat_map(|(input, _)| select(&input.document, &self.selector))
+                    .collect();
+                let indent = documents
+                    .first()
+                    .and_then(|(_, settings)| settings.ast_json.indent());
+                to_json(&matches, indent)?
+            }
+            MatchFormat::Markdown | MatchFormat::Text => {
+                let rendered: Vec<String> = documents
+                    .iter()
+                    .flat_map(|(input, settings)| self.render_matches(input, settings))
+                    .collect();
+                let separator = match self.to {
+                    MatchFormat::Markdown => "\n\n",
+                    _ => "\n",
+                };
+                rendered.join(separator)
+            }
+        };
+
+        if !output.is_empty() {
+            println!("{output}");
+        }
+        Ok(())
+    }
+
+    /// Matches of one document, each rendered on its own.
+    fn render_matches(&self, input: &ParsedDocument, settings: &Settings) -> Vec<String> {
+        select(&input.document, &self.selector)
+            .into_iter()
+            .map(|node| {
+                let document = Document {
+                    blocks: node_blocks(node),
+                };
+                let rendered = match self.to {
+                    MatchFormat::Text => crate::printer::text::render_text(
+                        &document,
+                        &settings.text.to_printer_config(),
+                    ),
+                    _ => markdown_ppp::printer::render_markdown(
+                        &document,
+                        settings.markdown.to_printer_config(),
+                    ),
+                };
+                rendered.trim_end().to_string()
+            })
+            .collect()
+    }
+}
+
+/// Blocks showing a matched node on its own: list items keep their list
+/// marker and inline nodes become a paragraph.
+fn node_blocks(node: Node) -> Vec<Block> {
+    match node {
+        Node::Section(_, blocks) => blocks.to_vec(),
+        Node::Block(block) => vec![block.clone()],
+        Node::ListItem(list, item) => vec![Block::List(List {
+            kind: list.kind.clone(),
+            items: vec![item.clone()],
+        })],
+        Node::Inline(inline) => vec![Block::Paragraph(vec![inline.clone()])],
+    }
+}
@@ -0,0 +1,91 @@
+use crate::config::{MarkdownConfig, ProjectConfig, TocConfig};
+use crate::input::{read_file, read_input, InputFormat};
+use crate::output::write_if_changed;
+use crate::toc::{generate, replace_in_text, TOC_START};
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use markdown_ppp::ast::Document;
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct Toc {
+    #[clap(flatten)]
+    pub toc: TocConfig,
+
+    #[clap(flatten)]
+    pub config: MarkdownConfig,
+
+    /// Prefix for anchor links
+    #[clap(long)]
+    pub anchor_prefix: Option<String>,
+
+    /// Rewrite the region between <!-- toc --> and <!-- tocstop --> markers in place
+    #[clap(long, short = 'i', requires = "files")]
+    pub insert: bool,
+
+    /// Files to build the table of contents for (reads stdin if none are given)
+    pub files: Vec<PathBuf>,
+}
+
+impl Toc {
+    pub fn run(&self, project: &ProjectConfig) -> Result<()> {
+        if self.files.is_empty() {
+            let content = read_input()?;
+            print!("{}", self.render(project, &content, None)?);
+            return Ok(());
+        }
+
+        for file_path in &self.files {
+            let content = read_file(file_path)?;
+            let toc = self.render(project, &content, Some(file_path))?;
+
+            if !self.insert {
+                print!("{toc}");
+                continue;
+            }
+
+            let Some(updated) = replace_in_text(&content, &toc) else {
+                bail!("No {TOC_START} marker found in {}", file_path.display());
+            };
+            if write_if_changed(file_path, &updated)
+                .with_context(|| format!("Failed to write file: {}", file_path.display()))?
+            {
+                println!("Updated table of contents: {}", file_path.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rendered table of contents of the content, empty if it has no headings.
+    fn render(
+        &self,
+        project: &ProjectConfig,
+        content: &str,
+        path: Option<&Path>,
+    ) -> Result<String> {
+        let input = InputFormat::Markdown.parse(content, path)?;
+        let settings = project.settings_for(path)?;
+
+        let anchor_prefix = self
+            .anchor_prefix
+            .as_ref()
+            .or(settings.html.anchor_prefix.as_ref())
+            .map_or("", String::as_str);
+        let Some(toc) = generate(
+            &input.document,
+            &self.toc.merge(&settings.toc),
+            anchor_prefix,
+        ) else {
+            return Ok(String::new());
+        };
+
+        let document = Document { blocks: vec![toc] };
+        let printer_config = self.config.merge(&settings.markdown).to_printer_config();
+        let mut rendered = markdown_ppp::printer::render_markdown(&document, printer_config);
+        if !rendered.ends_with('\n') {
+            rendered.push('\n');
+        }
+        Ok(rendered)
+    }
+}
@@ -0,0 +1,51 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_INDENT: usize = 2;
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AstJsonConfig {
+    /// Write indented JSON, one value per line
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub pretty: Option<bool>,
+
+    /// Spaces per indentation level, implies --pretty [default: 2]
+    #[clap(long)]
+    pub indent: Option<usize>,
+
+    /// Leave out fields that hold their default value, like empty lists and
+    /// missing titles; `--from ast-json` fills them in again
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub omit_defaults: Option<bool>,
+}
+
+impl AstJsonConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            pretty: self.pretty.or(fallback.pretty),
+            indent: self.indent.or(fallback.indent),
+            omit_defaults: self.omit_defaults.or(fallback.omit_defaults),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    /// The indentation stays unset, setting it turns on pretty printing.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            pretty: Some(false),
+            indent: None,
+            omit_defaults: Some(false),
+        })
+    }
+
+    /// Indentation of pretty-printed output, or `None` for a single line.
+    pub fn indent(&self) -> Option<usize> {
+        if self.pretty == Some(true) || self.indent.is_some() {
+            Some(self.indent.unwrap_or(DEFAULT_INDENT))
+        } else {
+            None
+        }
+    }
+}
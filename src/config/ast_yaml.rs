@@ -0,0 +1,27 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AstYamlConfig {
+    /// Leave out fields that hold their default value, like empty lists and
+    /// missing titles; `--from ast-yaml` fills them in again
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub omit_defaults: Option<bool>,
+}
+
+impl AstYamlConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            omit_defaults: self.omit_defaults.or(fallback.omit_defaults),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            omit_defaults: Some(false),
+        })
+    }
+}
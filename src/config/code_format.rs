@@ -0,0 +1,74 @@
+use crate::code_format::BUILTIN_JSON;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+const DEFAULT_TIMEOUT: u64 = 10;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CodeFormatConfig {
+    /// Command per code block language that reads the code on stdin and writes
+    /// the formatted code to stdout; `builtin:json` is the built-in JSON formatter
+    pub formatters: BTreeMap<String, String>,
+
+    /// Seconds a formatter may run before it is stopped [default: 10]
+    pub timeout: Option<u64>,
+}
+
+impl CodeFormatConfig {
+    /// Fill formatters and options that are not set here from `fallback`.
+    /// Languages are compared case-insensitively and stored in lowercase.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        let formatters = fallback
+            .formatters
+            .iter()
+            .chain(&self.formatters)
+            .map(|(language, command)| (language.to_lowercase(), command.clone()))
+            .collect();
+        Self {
+            formatters,
+            timeout: self.timeout.or(fallback.timeout),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            formatters: BTreeMap::new(),
+            timeout: Some(DEFAULT_TIMEOUT),
+        })
+    }
+
+    /// Copy of the configuration with only the built-in formatters, which run
+    /// no external command.
+    pub fn builtin_only(&self) -> Self {
+        Self {
+            formatters: self
+                .formatters
+                .iter()
+                .filter(|(_, command)| command.as_str() == BUILTIN_JSON)
+                .map(|(language, command)| (language.clone(), command.clone()))
+                .collect(),
+            timeout: self.timeout,
+        }
+    }
+
+    /// Whether a formatter runs an external command.
+    pub fn has_commands(&self) -> bool {
+        self.formatters
+            .values()
+            .any(|command| command != BUILTIN_JSON)
+    }
+
+    /// Formatter command of a language in a merged configuration.
+    pub fn formatter(&self, language: &str) -> Option<&str> {
+        self.formatters
+            .get(&language.to_lowercase())
+            .map(String::as_str)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+    }
+}
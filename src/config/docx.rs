@@ -0,0 +1,69 @@
+use crate::output::zip::read_entry;
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DocxConfig {
+    /// DOCX file whose styles are used instead of the default ones
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_docx: Option<PathBuf>,
+}
+
+impl DocxConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            reference_docx: self
+                .reference_docx
+                .clone()
+                .or_else(|| fallback.reference_docx.clone()),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.clone()
+    }
+
+    /// Resolve relative file paths against `dir`, the directory of the
+    /// configuration file they were read from.
+    pub fn resolve_paths(&mut self, dir: &Path) {
+        if let Some(reference) = &mut self.reference_docx {
+            *reference = dir.join(&*reference);
+        }
+    }
+
+    /// Printer configuration with the title and author from the front matter
+    /// `fields`; relative image paths are resolved against `base_dir`.
+    pub fn to_printer_config(
+        &self,
+        field: impl Fn(&str) -> Option<String>,
+        base_dir: &Path,
+    ) -> Result<crate::printer::docx::Config> {
+        let styles = match &self.reference_docx {
+            Some(path) => Some(reference_styles(path).with_context(|| {
+                format!("Failed to read reference document: {}", path.display())
+            })?),
+            None => None,
+        };
+
+        Ok(crate::printer::docx::Config {
+            title: field("title"),
+            author: field("author"),
+            styles,
+            base_dir: base_dir.to_path_buf(),
+        })
+    }
+}
+
+/// `word/styles.xml` of a DOCX file.
+fn reference_styles(path: &Path) -> Result<String> {
+    let archive = std::fs::read(path)?;
+    let styles =
+        read_entry(&archive, "word/styles.xml")?.context("The document has no word/styles.xml")?;
+    String::from_utf8(styles).context("word/styles.xml is not valid UTF-8")
+}
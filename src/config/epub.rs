@@ -0,0 +1,75 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_LANGUAGE: &str = "en";
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EpubConfig {
+    /// Book title [default: front matter title or first heading]
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Book author [default: front matter author]
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Language of the book [default: front matter lang or en]
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Stylesheet file embedded into the book instead of the default one (repeatable)
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub css: Vec<PathBuf>,
+
+    /// Start a new chapter at every level 1 heading, not only at every input file
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub split_chapters: Option<bool>,
+}
+
+impl EpubConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            title: self.title.clone().or_else(|| fallback.title.clone()),
+            author: self.author.clone().or_else(|| fallback.author.clone()),
+            language: self.language.clone().or_else(|| fallback.language.clone()),
+            css: if self.css.is_empty() {
+                fallback.css.clone()
+            } else {
+                self.css.clone()
+            },
+            split_chapters: self.split_chapters.or(fallback.split_chapters),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    /// Title, author and language stay unset, they default to front matter values.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            split_chapters: Some(false),
+            ..Self::default()
+        })
+    }
+
+    /// Resolve relative file paths against `dir`, the directory of the
+    /// configuration file they were read from.
+    pub fn resolve_paths(&mut self, dir: &Path) {
+        for stylesheet in &mut self.css {
+            *stylesheet = dir.join(&*stylesheet);
+        }
+    }
+
+    /// Language of the book, from the option or the front matter `field`.
+    pub fn language(&self, field: impl Fn(&str) -> Option<String>) -> String {
+        self.language
+            .clone()
+            .or_else(|| field("lang"))
+            .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+    }
+}
@@ -1,20 +1,90 @@
 use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[derive(Args)]
+const DEFAULT_WIDTH: usize = 80;
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct HtmlConfig {
-    /// Width for HTML output formatting
-    #[clap(long, default_value_t = 80)]
-    pub width: usize,
+    /// Width for HTML output formatting [default: 80]
+    #[clap(long)]
+    pub width: Option<usize>,
 
-    /// Prefix for anchor links
+    /// Prefix for heading and footnote anchors
     #[clap(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub anchor_prefix: Option<String>,
+
+    /// Make headings link targets for the anchors of `toc`; on by default with
+    /// a `--template` that uses `$toc$`
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub heading_ids: Option<bool>,
+
+    /// Start the output with the title, author and date from the front matter
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub title_block: Option<bool>,
+
+    /// Wrap the output in a complete HTML document with a <head>
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub standalone: Option<bool>,
+
+    /// Stylesheet URL linked from the <head> of a standalone document (repeatable)
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub css: Vec<String>,
+
+    /// Template file for the output document, see "Templates" in the README
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<PathBuf>,
 }
 
 impl HtmlConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            width: self.width.or(fallback.width),
+            anchor_prefix: self
+                .anchor_prefix
+                .clone()
+                .or_else(|| fallback.anchor_prefix.clone()),
+            heading_ids: self.heading_ids.or(fallback.heading_ids),
+            title_block: self.title_block.or(fallback.title_block),
+            standalone: self.standalone.or(fallback.standalone),
+            css: if self.css.is_empty() {
+                fallback.css.clone()
+            } else {
+                self.css.clone()
+            },
+            template: self.template.clone().or_else(|| fallback.template.clone()),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            width: Some(DEFAULT_WIDTH),
+            anchor_prefix: None,
+            heading_ids: Some(false),
+            title_block: Some(false),
+            standalone: Some(false),
+            css: Vec::new(),
+            template: None,
+        })
+    }
+
+    /// Resolve relative file paths against `dir`, the directory of the
+    /// configuration file they were read from.
+    pub fn resolve_paths(&mut self, dir: &Path) {
+        if let Some(template) = &mut self.template {
+            *template = dir.join(&*template);
+        }
+    }
+
     pub fn to_printer_config(&self) -> markdown_ppp::html_printer::config::Config {
-        let mut config =
-            markdown_ppp::html_printer::config::Config::default().with_width(self.width);
+        let mut config = markdown_ppp::html_printer::config::Config::default()
+            .with_width(self.width.unwrap_or(DEFAULT_WIDTH));
 
         if let Some(ref prefix) = self.anchor_prefix {
             config = config.with_anchor_prefix(prefix.clone());
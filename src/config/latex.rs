@@ -1,7 +1,13 @@
 use clap::Args;
 use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[derive(Clone)]
+const DEFAULT_WIDTH: usize = 80;
+pub const DEFAULT_DOCUMENT_CLASS: &str = "article";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TableStyle {
     Tabular,
     Longtabu,
@@ -23,7 +29,8 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CodeStyle {
     Verbatim,
     Listings,
@@ -45,37 +52,98 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
     }
 }
 
-#[derive(Args)]
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct LatexConfig {
-    /// Width for LaTeX output formatting
-    #[clap(long, default_value_t = 80)]
-    pub width: usize,
+    /// Width for LaTeX output formatting [default: 80]
+    #[clap(long)]
+    pub width: Option<usize>,
+
+    /// Table style: tabular, longtabu, booktabs [default: tabular]
+    #[clap(long)]
+    pub table_style: Option<TableStyle>,
+
+    /// Code block style: verbatim, listings, minted [default: verbatim]
+    #[clap(long)]
+    pub code_style: Option<CodeStyle>,
+
+    /// Start the output with the title, author and date from the front matter
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub title_block: Option<bool>,
 
-    /// Table style: tabular, longtabu, booktabs
-    #[clap(long, default_value = "tabular")]
-    pub table_style: TableStyle,
+    /// Wrap the output in a complete document with a preamble
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub standalone: Option<bool>,
 
-    /// Code block style: verbatim, listings, minted
-    #[clap(long, default_value = "verbatim")]
-    pub code_style: CodeStyle,
+    /// Document class of a standalone document [default: article]
+    #[clap(long)]
+    pub document_class: Option<String>,
+
+    /// Template file for the output document, see "Templates" in the README
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<PathBuf>,
 }
 
 impl LatexConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            width: self.width.or(fallback.width),
+            table_style: self
+                .table_style
+                .clone()
+                .or_else(|| fallback.table_style.clone()),
+            code_style: self
+                .code_style
+                .clone()
+                .or_else(|| fallback.code_style.clone()),
+            title_block: self.title_block.or(fallback.title_block),
+            standalone: self.standalone.or(fallback.standalone),
+            document_class: self
+                .document_class
+                .clone()
+                .or_else(|| fallback.document_class.clone()),
+            template: self.template.clone().or_else(|| fallback.template.clone()),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            width: Some(DEFAULT_WIDTH),
+            table_style: Some(TableStyle::Tabular),
+            code_style: Some(CodeStyle::Verbatim),
+            title_block: Some(false),
+            standalone: Some(false),
+            document_class: Some(DEFAULT_DOCUMENT_CLASS.to_string()),
+            template: None,
+        })
+    }
+
+    /// Resolve relative file paths against `dir`, the directory of the
+    /// configuration file they were read from.
+    pub fn resolve_paths(&mut self, dir: &Path) {
+        if let Some(template) = &mut self.template {
+            *template = dir.join(&*template);
+        }
+    }
+
     pub fn to_printer_config(&self) -> markdown_ppp::latex_printer::config::Config {
-        let table_style = match self.table_style {
+        let table_style = match self.table_style.as_ref().unwrap_or(&TableStyle::Tabular) {
             TableStyle::Tabular => markdown_ppp::latex_printer::config::TableStyle::Tabular,
             TableStyle::Longtabu => markdown_ppp::latex_printer::config::TableStyle::Longtabu,
             TableStyle::Booktabs => markdown_ppp::latex_printer::config::TableStyle::Booktabs,
         };
 
-        let code_style = match self.code_style {
+        let code_style = match self.code_style.as_ref().unwrap_or(&CodeStyle::Verbatim) {
             CodeStyle::Verbatim => markdown_ppp::latex_printer::config::CodeBlockStyle::Verbatim,
             CodeStyle::Listings => markdown_ppp::latex_printer::config::CodeBlockStyle::Listings,
             CodeStyle::Minted => markdown_ppp::latex_printer::config::CodeBlockStyle::Minted,
         };
 
         markdown_ppp::latex_printer::config::Config::default()
-            .with_width(self.width)
+            .with_width(self.width.unwrap_or(DEFAULT_WIDTH))
             .with_table_style(table_style)
             .with_code_block_style(code_style)
     }
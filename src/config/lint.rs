@@ -0,0 +1,28 @@
+use crate::lint::{rules::all_rules, RuleLevel};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Level per rule id: off, info, warning or error
+    pub rules: BTreeMap<String, RuleLevel>,
+}
+
+impl LintConfig {
+    /// Fill rules that are not configured here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        let mut rules = fallback.rules.clone();
+        rules.extend(self.rules.clone());
+        Self { rules }
+    }
+
+    /// Copy of the configuration with every rule set to its effective level.
+    pub fn resolved(&self) -> Self {
+        let defaults = all_rules()
+            .into_iter()
+            .map(|rule| (rule.id().to_string(), rule.default_severity().into()))
+            .collect();
+        self.merge(&Self { rules: defaults })
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::{bail, Result};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_WIDTH: usize = 80;
+pub const DEFAULT_SECTION: &str = "1";
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ManConfig {
+    /// Width for roff source lines [default: 80]
+    #[clap(long)]
+    pub width: Option<usize>,
+
+    /// Page name in the .TH header [default: front matter name or title]
+    #[clap(long)]
+    pub name: Option<String>,
+
+    /// Manual section in the .TH header [default: front matter section or 1]
+    #[clap(long)]
+    pub section: Option<String>,
+
+    /// Date in the .TH header [default: front matter date]
+    #[clap(long)]
+    pub date: Option<String>,
+
+    /// Source, e.g. the package and version, in the .TH header
+    #[clap(long)]
+    pub source: Option<String>,
+
+    /// Title of the manual in the .TH header
+    #[clap(long)]
+    pub manual: Option<String>,
+}
+
+impl ManConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            width: self.width.or(fallback.width),
+            name: self.name.clone().or_else(|| fallback.name.clone()),
+            section: self.section.clone().or_else(|| fallback.section.clone()),
+            date: self.date.clone().or_else(|| fallback.date.clone()),
+            source: self.source.clone().or_else(|| fallback.source.clone()),
+            manual: self.manual.clone().or_else(|| fallback.manual.clone()),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    /// Header fields stay unset, they default to front matter values.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            width: Some(DEFAULT_WIDTH),
+            ..Self::default()
+        })
+    }
+
+    /// Printer configuration; header fields that are not set here are taken
+    /// from the front matter `fields`.
+    pub fn to_printer_config(
+        &self,
+        field: impl Fn(&str) -> Option<String>,
+    ) -> Result<crate::printer::man::Config> {
+        // A title like `tool(1)` gives both the name and the section
+        let title = field("title");
+        let (title_name, title_section) = match title.as_deref().and_then(split_title) {
+            Some((name, section)) => (Some(name.to_string()), Some(section.to_string())),
+            None => (title, None),
+        };
+
+        let name = self
+            .name
+            .clone()
+            .or_else(|| field("name"))
+            .or(title_name)
+            .unwrap_or_default();
+        let section = self
+            .section
+            .clone()
+            .or_else(|| field("section"))
+            .or(title_section)
+            .unwrap_or_else(|| DEFAULT_SECTION.to_string());
+        // The section also names the file in `--output-dir`, e.g. `tool.3p`
+        if section.is_empty() || !section.chars().all(|c| c.is_ascii_alphanumeric()) {
+            bail!("Invalid man section {section:?}: expected something like 1 or 3p");
+        }
+
+        Ok(crate::printer::man::Config {
+            width: self.width.unwrap_or(DEFAULT_WIDTH),
+            name: name.to_uppercase(),
+            section,
+            date: self
+                .date
+                .clone()
+                .or_else(|| field("date"))
+                .unwrap_or_default(),
+            source: self
+                .source
+                .clone()
+                .or_else(|| field("source"))
+                .unwrap_or_default(),
+            manual: self
+                .manual
+                .clone()
+                .or_else(|| field("manual"))
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Name and section of a title like `tool(1)`.
+fn split_title(title: &str) -> Option<(&str, &str)> {
+    let (name, section) = title.trim().strip_suffix(')')?.split_once('(')?;
+    if !name.is_empty() && !section.is_empty() && !section.contains(' ') {
+        Some((name, section))
+    } else {
+        None
+    }
+}
@@ -1,25 +1,59 @@
 use clap::Args;
+use serde::{Deserialize, Serialize};
 
-#[derive(Args)]
+const DEFAULT_WIDTH: usize = 80;
+const DEFAULT_SPACES_BEFORE_LIST_ITEM: usize = 1;
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct MarkdownConfig {
-    /// Width for markdown output formatting
-    #[clap(long, default_value_t = 80)]
-    pub width: usize,
+    /// Width for markdown output formatting [default: 80]
+    #[clap(long)]
+    pub width: Option<usize>,
 
-    /// Number of spaces before list items (0-3)
-    #[clap(long, default_value_t = 1)]
-    pub spaces_before_list_item: usize,
+    /// Number of spaces before list items (0-3) [default: 1]
+    #[clap(long)]
+    pub spaces_before_list_item: Option<usize>,
 
     /// Disable empty line before lists
-    #[clap(long)]
-    pub no_empty_line_before_list: bool,
+    #[clap(
+        long = "no-empty-line-before-list",
+        num_args = 0,
+        default_missing_value = "false"
+    )]
+    pub empty_line_before_list: Option<bool>,
 }
 
 impl MarkdownConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            width: self.width.or(fallback.width),
+            spaces_before_list_item: self
+                .spaces_before_list_item
+                .or(fallback.spaces_before_list_item),
+            empty_line_before_list: self
+                .empty_line_before_list
+                .or(fallback.empty_line_before_list),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            width: Some(DEFAULT_WIDTH),
+            spaces_before_list_item: Some(DEFAULT_SPACES_BEFORE_LIST_ITEM),
+            empty_line_before_list: Some(true),
+        })
+    }
+
     pub fn to_printer_config(&self) -> markdown_ppp::printer::config::Config {
         markdown_ppp::printer::config::Config::default()
-            .with_width(self.width)
-            .with_spaces_before_list_item(self.spaces_before_list_item)
-            .with_empty_line_before_list(!self.no_empty_line_before_list)
+            .with_width(self.width.unwrap_or(DEFAULT_WIDTH))
+            .with_spaces_before_list_item(
+                self.spaces_before_list_item
+                    .unwrap_or(DEFAULT_SPACES_BEFORE_LIST_ITEM),
+            )
+            .with_empty_line_before_list(self.empty_line_before_list.unwrap_or(true))
     }
 }
@@ -1,7 +1,35 @@
+pub mod asciidoc;
+pub mod ast_json;
+pub mod ast_yaml;
+pub mod code_format;
+pub mod docx;
+pub mod epub;
 pub mod html;
 pub mod latex;
+pub mod lint;
+pub mod man;
 pub mod markdown;
+pub mod org;
+pub mod project;
+pub mod rst;
+pub mod text;
+pub mod toc;
+pub mod typst;
 
+pub use asciidoc::AsciidocConfig;
+pub use ast_json::AstJsonConfig;
+pub use ast_yaml::AstYamlConfig;
+pub use code_format::CodeFormatConfig;
+pub use docx::DocxConfig;
+pub use epub::EpubConfig;
 pub use html::HtmlConfig;
 pub use latex::LatexConfig;
+pub use lint::LintConfig;
+pub use man::ManConfig;
 pub use markdown::MarkdownConfig;
+pub use org::OrgConfig;
+pub use project::{ProjectConfig, Settings};
+pub use rst::RstConfig;
+pub use text::TextConfig;
+pub use toc::TocConfig;
+pub use typst::TypstConfig;
@@ -0,0 +1,34 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_WIDTH: usize = 80;
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrgConfig {
+    /// Width for Org output formatting [default: 80]
+    #[clap(long)]
+    pub width: Option<usize>,
+}
+
+impl OrgConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            width: self.width.or(fallback.width),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            width: Some(DEFAULT_WIDTH),
+        })
+    }
+
+    pub fn to_printer_config(&self) -> crate::printer::org::Config {
+        crate::printer::org::Config {
+            width: self.width.unwrap_or(DEFAULT_WIDTH),
+        }
+    }
+}
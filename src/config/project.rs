@@ -0,0 +1,240 @@
+use super::{
+    AsciidocConfig, AstJsonConfig, AstYamlConfig, CodeFormatConfig, DocxConfig, EpubConfig,
+    HtmlConfig, LatexConfig, LintConfig, ManConfig, MarkdownConfig, OrgConfig, RstConfig,
+    TextConfig, TocConfig, TypstConfig,
+};
+use crate::diagnostic::SourceDiagnostic;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// Name of the project configuration file looked up from the current directory upwards
+pub const CONFIG_FILE_NAME: &str = ".markdown-tool.toml";
+
+/// Printer settings for every output format.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub markdown: MarkdownConfig,
+    pub html: HtmlConfig,
+    pub latex: LatexConfig,
+    pub lint: LintConfig,
+    pub toc: TocConfig,
+    pub text: TextConfig,
+    pub typst: TypstConfig,
+    pub asciidoc: AsciidocConfig,
+    pub rst: RstConfig,
+    pub man: ManConfig,
+    pub org: OrgConfig,
+    pub docx: DocxConfig,
+    pub epub: EpubConfig,
+    pub ast_json: AstJsonConfig,
+    pub ast_yaml: AstYamlConfig,
+    pub code_format: CodeFormatConfig,
+}
+
+impl Settings {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            markdown: self.markdown.merge(&fallback.markdown),
+            html: self.html.merge(&fallback.html),
+            latex: self.latex.merge(&fallback.latex),
+            lint: self.lint.merge(&fallback.lint),
+            toc: self.toc.merge(&fallback.toc),
+            text: self.text.merge(&fallback.text),
+            typst: self.typst.merge(&fallback.typst),
+            asciidoc: self.asciidoc.merge(&fallback.asciidoc),
+            rst: self.rst.merge(&fallback.rst),
+            man: self.man.merge(&fallback.man),
+            org: self.org.merge(&fallback.org),
+            docx: self.docx.merge(&fallback.docx),
+            epub: self.epub.merge(&fallback.epub),
+            ast_json: self.ast_json.merge(&fallback.ast_json),
+            ast_yaml: self.ast_yaml.merge(&fallback.ast_yaml),
+            code_format: self.code_format.merge(&fallback.code_format),
+        }
+    }
+
+    /// Resolve the file paths of the settings against `dir`, the directory of
+    /// the configuration file.
+    fn resolve_paths(&mut self, dir: &Path) {
+        self.html.resolve_paths(dir);
+        self.latex.resolve_paths(dir);
+        self.docx.resolve_paths(dir);
+        self.epub.resolve_paths(dir);
+    }
+
+    /// Copy of the settings with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        Self {
+            markdown: self.markdown.resolved(),
+            html: self.html.resolved(),
+            latex: self.latex.resolved(),
+            lint: self.lint.resolved(),
+            toc: self.toc.resolved(),
+            text: self.text.resolved(),
+            typst: self.typst.resolved(),
+            asciidoc: self.asciidoc.resolved(),
+            rst: self.rst.resolved(),
+            man: self.man.resolved(),
+            org: self.org.resolved(),
+            docx: self.docx.resolved(),
+            epub: self.epub.resolved(),
+            ast_json: self.ast_json.resolved(),
+            ast_yaml: self.ast_yaml.resolved(),
+            code_format: self.code_format.resolved(),
+        }
+    }
+}
+
+/// Settings applied only to files matching one of the glob patterns.
+///
+/// Patterns containing `/` are matched against the path relative to the
+/// configuration file, other patterns against the file name.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Override {
+    pub files: Vec<String>,
+    #[serde(flatten)]
+    pub settings: Settings,
+}
+
+impl Override {
+    fn matches(&self, relative: &Path) -> Result<bool> {
+        for pattern in &self.files {
+            let glob = glob::Pattern::new(pattern)
+                .with_context(|| format!("Invalid override pattern: {pattern}"))?;
+            let matched = if pattern.contains('/') {
+                glob.matches_path(relative)
+            } else {
+                relative
+                    .file_name()
+                    .is_some_and(|name| glob.matches(&name.to_string_lossy()))
+            };
+            if matched {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Settings of the overrides, without their file patterns.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct OverrideSections {
+    #[serde(rename = "override")]
+    _overrides: Vec<Settings>,
+}
+
+/// Contents of a `.markdown-tool.toml` file.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    #[serde(flatten)]
+    pub settings: Settings,
+
+    #[serde(rename = "override")]
+    pub overrides: Vec<Override>,
+
+    /// Configuration file the settings were loaded from
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl ProjectConfig {
+    /// Load the given configuration file, or search for one from the current
+    /// directory upwards if no file is given.
+    pub fn load(explicit: Option<&Path>) -> Result<Self> {
+        match explicit {
+            Some(path) => Self::read(path),
+            None => match Self::discover()? {
+                Some(path) => Self::read(&path),
+                None => Ok(Self::default()),
+            },
+        }
+    }
+
+    fn discover() -> Result<Option<PathBuf>> {
+        let current_dir = std::env::current_dir().context("Failed to get current directory")?;
+        Ok(current_dir
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|path| path.is_file()))
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let parse_error = |err: toml::de::Error| {
+            let diagnostic =
+                SourceDiagnostic::error(format!("Failed to parse config file: {}", err.message()))
+                    .with_path(Some(path));
+            match err.span() {
+                Some(span) => diagnostic.at_offset(&content, span.start),
+                None => diagnostic,
+            }
+        };
+
+        // Errors in flattened settings have no position, so the sections are
+        // checked on their own first
+        toml::from_str::<Settings>(&content).map_err(parse_error)?;
+        toml::from_str::<OverrideSections>(&content).map_err(parse_error)?;
+        let mut config: Self = toml::from_str(&content).map_err(parse_error)?;
+        // Paths in the file are relative to it, not to the current directory
+        let dir = path.parent().unwrap_or(Path::new(""));
+        config.settings.resolve_paths(dir);
+        for item in &mut config.overrides {
+            item.settings.resolve_paths(dir);
+        }
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Whether the file, or one of its overrides, configures code formatters
+    /// that run external commands.
+    pub fn has_formatter_commands(&self) -> bool {
+        self.settings.code_format.has_commands()
+            || self
+                .overrides
+                .iter()
+                .any(|item| item.settings.code_format.has_commands())
+    }
+
+    /// Settings for the given input file, with matching overrides applied in order.
+    pub fn settings_for(&self, file: Option<&Path>) -> Result<Settings> {
+        let mut settings = self.settings.clone();
+
+        if let Some(relative) = file.map(|file| self.relative_path(file)).transpose()? {
+            for item in &self.overrides {
+                if item.matches(&relative)? {
+                    settings = item.settings.merge(&settings);
+                }
+            }
+        }
+
+        Ok(settings)
+    }
+
+    fn relative_path(&self, file: &Path) -> Result<PathBuf> {
+        let current_dir = std::env::current_dir().context("Failed to get current directory")?;
+        let absolute: PathBuf = current_dir
+            .join(file)
+            .components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect();
+
+        let root = self
+            .path
+            .as_ref()
+            .and_then(|path| path.parent())
+            .map(|dir| current_dir.join(dir))
+            .unwrap_or(current_dir);
+
+        Ok(absolute
+            .strip_prefix(&root)
+            .map(Path::to_path_buf)
+            .unwrap_or(absolute))
+    }
+}
@@ -0,0 +1,71 @@
+use clap::Args;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_WIDTH: usize = 80;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TableStyle {
+    Grid,
+    ListTable,
+}
+
+impl FromStr for TableStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "grid" => Ok(TableStyle::Grid),
+            "list-table" => Ok(TableStyle::ListTable),
+            _ => Err(format!(
+                "Invalid table style: {s}. Supported: grid, list-table"
+            )),
+        }
+    }
+}
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RstConfig {
+    /// Width for reStructuredText output formatting [default: 80]
+    #[clap(long)]
+    pub width: Option<usize>,
+
+    /// Table style: grid, list-table [default: grid]
+    #[clap(long)]
+    pub table_style: Option<TableStyle>,
+}
+
+impl RstConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            width: self.width.or(fallback.width),
+            table_style: self
+                .table_style
+                .clone()
+                .or_else(|| fallback.table_style.clone()),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            width: Some(DEFAULT_WIDTH),
+            table_style: Some(TableStyle::Grid),
+        })
+    }
+
+    pub fn to_printer_config(&self) -> crate::printer::rst::Config {
+        let table_style = match self.table_style.as_ref().unwrap_or(&TableStyle::Grid) {
+            TableStyle::Grid => crate::printer::rst::TableStyle::Grid,
+            TableStyle::ListTable => crate::printer::rst::TableStyle::ListTable,
+        };
+
+        crate::printer::rst::Config {
+            width: self.width.unwrap_or(DEFAULT_WIDTH),
+            table_style,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_WIDTH: usize = 80;
+const DEFAULT_BULLET: &str = "*";
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TextConfig {
+    /// Width for text output formatting [default: 80]
+    #[clap(long)]
+    pub width: Option<usize>,
+
+    /// Marker of bullet list items [default: *]
+    #[clap(long)]
+    pub bullet: Option<String>,
+
+    /// Number links and list their URLs after the text
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub link_footnotes: Option<bool>,
+}
+
+impl TextConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            width: self.width.or(fallback.width),
+            bullet: self.bullet.clone().or_else(|| fallback.bullet.clone()),
+            link_footnotes: self.link_footnotes.or(fallback.link_footnotes),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            width: Some(DEFAULT_WIDTH),
+            bullet: Some(DEFAULT_BULLET.to_string()),
+            link_footnotes: Some(false),
+        })
+    }
+
+    pub fn to_printer_config(&self) -> crate::printer::text::Config {
+        crate::printer::text::Config {
+            width: self.width.unwrap_or(DEFAULT_WIDTH),
+            bullet: self
+                .bullet
+                .clone()
+                .unwrap_or_else(|| DEFAULT_BULLET.to_string()),
+            link_footnotes: self.link_footnotes.unwrap_or(false),
+        }
+    }
+}
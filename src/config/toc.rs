@@ -0,0 +1,43 @@
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MIN_DEPTH: u8 = 1;
+const DEFAULT_MAX_DEPTH: u8 = 6;
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TocConfig {
+    /// Smallest heading level included in the table of contents [default: 1]
+    #[clap(long, value_parser = clap::value_parser!(u8).range(1..=6))]
+    pub min_depth: Option<u8>,
+
+    /// Largest heading level included in the table of contents [default: 6]
+    #[clap(long, value_parser = clap::value_parser!(u8).range(1..=6))]
+    pub max_depth: Option<u8>,
+}
+
+impl TocConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            min_depth: self.min_depth.or(fallback.min_depth),
+            max_depth: self.max_depth.or(fallback.max_depth),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            min_depth: Some(DEFAULT_MIN_DEPTH),
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+        })
+    }
+
+    /// Range of heading levels to include.
+    pub fn depth(&self) -> (u8, u8) {
+        (
+            self.min_depth.unwrap_or(DEFAULT_MIN_DEPTH),
+            self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+        )
+    }
+}
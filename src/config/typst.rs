@@ -0,0 +1,114 @@
+use clap::Args;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_WIDTH: usize = 80;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableStyle {
+    Grid,
+    Booktabs,
+    Plain,
+}
+
+impl FromStr for TableStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "grid" => Ok(TableStyle::Grid),
+            "booktabs" => Ok(TableStyle::Booktabs),
+            "plain" => Ok(TableStyle::Plain),
+            _ => Err(format!(
+                "Invalid table style: {s}. Supported: grid, booktabs, plain"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeStyle {
+    Raw,
+    Block,
+}
+
+impl FromStr for CodeStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "raw" => Ok(CodeStyle::Raw),
+            "block" => Ok(CodeStyle::Block),
+            _ => Err(format!("Invalid code style: {s}. Supported: raw, block")),
+        }
+    }
+}
+
+#[derive(Args, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TypstConfig {
+    /// Width for Typst output formatting [default: 80]
+    #[clap(long)]
+    pub width: Option<usize>,
+
+    /// Table style: grid, booktabs, plain [default: grid]
+    #[clap(long)]
+    pub table_style: Option<TableStyle>,
+
+    /// Code block style: raw, block [default: raw]
+    #[clap(long)]
+    pub code_style: Option<CodeStyle>,
+
+    /// Start the output with the title, author and date from the front matter
+    #[clap(long, num_args = 0, default_missing_value = "true")]
+    pub title_block: Option<bool>,
+}
+
+impl TypstConfig {
+    /// Fill options that are not set here from `fallback`.
+    pub fn merge(&self, fallback: &Self) -> Self {
+        Self {
+            width: self.width.or(fallback.width),
+            table_style: self
+                .table_style
+                .clone()
+                .or_else(|| fallback.table_style.clone()),
+            code_style: self
+                .code_style
+                .clone()
+                .or_else(|| fallback.code_style.clone()),
+            title_block: self.title_block.or(fallback.title_block),
+        }
+    }
+
+    /// Copy of the configuration with every unset option replaced by its default.
+    pub fn resolved(&self) -> Self {
+        self.merge(&Self {
+            width: Some(DEFAULT_WIDTH),
+            table_style: Some(TableStyle::Grid),
+            code_style: Some(CodeStyle::Raw),
+            title_block: Some(false),
+        })
+    }
+
+    pub fn to_printer_config(&self) -> crate::printer::typst::Config {
+        let table_style = match self.table_style.as_ref().unwrap_or(&TableStyle::Grid) {
+            TableStyle::Grid => crate::printer::typst::TableStyle::Grid,
+            TableStyle::Booktabs => crate::printer::typst::TableStyle::Booktabs,
+            TableStyle::Plain => crate::printer::typst::TableStyle::Plain,
+        };
+
+        let code_style = match self.code_style.as_ref().unwrap_or(&CodeStyle::Raw) {
+            CodeStyle::Raw => crate::printer::typst::CodeStyle::Raw,
+            CodeStyle::Block => crate::printer::typst::CodeStyle::Block,
+        };
+
+        crate::printer::typst::Config {
+            width: self.width.unwrap_or(DEFAULT_WIDTH),
+            table_style,
+            code_style,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Line and column in a source text, both starting at 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// Position of a byte offset; the column counts characters, not bytes.
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let offset = offset.min(source.len());
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+        }
+    }
+}
+
+/// Message tied to a location in a source file, printed with the offending
+/// line and a caret under the column.
+#[derive(Debug)]
+pub struct SourceDiagnostic {
+    /// Leading label, e.g. `error` or `warning[rule-id]`
+    pub label: String,
+    pub message: String,
+    /// Source file, `None` for standard input
+    pub path: Option<PathBuf>,
+    pub position: Option<Position>,
+    /// Text of the source line at `position`
+    pub snippet: Option<String>,
+}
+
+impl SourceDiagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            label: "error".to_string(),
+            message: message.into(),
+            path: None,
+            position: None,
+            snippet: None,
+        }
+    }
+
+    pub fn with_label(self, label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            ..self
+        }
+    }
+
+    pub fn with_path(self, path: Option<&Path>) -> Self {
+        Self {
+            path: path.map(Path::to_path_buf),
+            ..self
+        }
+    }
+
+    /// Attach a position and the corresponding line of `source`.
+    pub fn at(self, source: &str, position: Position) -> Self {
+        let snippet = source
+            .lines()
+            .nth(position.line - 1)
+            .map(|line| line.trim_end_matches('\r').to_string());
+        Self {
+            position: Some(position),
+            snippet,
+            ..self
+        }
+    }
+
+    /// Attach the position of a byte offset in `source`.
+    pub fn at_offset(self, source: &str, offset: usize) -> Self {
+        self.at(source, Position::from_offset(source, offset))
+    }
+
+    /// `path:line:column` as used in the location line.
+    pub fn location(&self) -> String {
+        let path = self
+            .path
+            .as_ref()
+            .map_or_else(|| "<stdin>".to_string(), |path| path.display().to_string());
+        match self.position {
+            Some(position) => format!("{path}:{}:{}", position.line, position.column),
+            None => path,
+        }
+    }
+}
+
+impl fmt::Display for SourceDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.label, self.message)?;
+
+        let (Some(position), Some(snippet)) = (self.position, &self.snippet) else {
+            return write!(f, " --> {}", self.location());
+        };
+
+        let number = position.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let caret_indent: String = snippet
+            .chars()
+            .take(position.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(
+            f,
+            "{gutter}--> {}\n{gutter} |\n{number} | {snippet}\n{gutter} | {caret_indent}^",
+            self.location()
+        )
+    }
+}
+
+impl std::error::Error for SourceDiagnostic {}
+
+/// Print an error to stderr, with source context if it is a [`SourceDiagnostic`].
+pub fn report_error(err: &anyhow::Error) {
+    match err.downcast_ref::<SourceDiagnostic>() {
+        Some(diagnostic) => eprintln!("{diagnostic}"),
+        None => eprintln!("Failed with error: {err:#}"),
+    }
+}
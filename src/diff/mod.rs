@@ -0,0 +1,300 @@
+//! Line-based differences between two texts, printed as unified diffs.
+
+use serde::Serialize;
+
+const BOLD: &str = "\x1b[1m";
+const CYAN: &str = "\x1b[36m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Line<'a> {
+    Context(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Lines of one text covered by a hunk, numbered like unified diff hunk
+/// headers: `start` is the first line, starting at 1, or the line before the
+/// hunk if `count` is 0.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct LineRange {
+    pub start: usize,
+    pub count: usize,
+}
+
+/// Group of changed lines with the unchanged lines around them.
+#[derive(Debug)]
+pub struct Hunk<'a> {
+    pub old: LineRange,
+    pub new: LineRange,
+    pub lines: Vec<Line<'a>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Edit {
+    Keep,
+    Remove,
+    Add,
+}
+
+/// Hunks turning `old` into `new`, each with up to `context` unchanged lines
+/// before and after its changes.
+pub fn hunks<'a>(old: &'a str, new: &'a str, context: usize) -> Vec<Hunk<'a>> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+    // Every line of the diff with the indexes of the next old and new line
+    let mut lines = Vec::new();
+    let (mut old_index, mut new_index) = (0, 0);
+    for edit in edit_script(&old_lines, &new_lines) {
+        let line = match edit {
+            Edit::Keep => Line::Context(old_lines[old_index]),
+            Edit::Remove => Line::Removed(old_lines[old_index]),
+            Edit::Add => Line::Added(new_lines[new_index]),
+        };
+        lines.push((line, old_index, new_index));
+        if edit != Edit::Add {
+            old_index += 1;
+        }
+        if edit != Edit::Remove {
+            new_index += 1;
+        }
+    }
+
+    let is_change = |index: usize| !matches!(lines[index].0, Line::Context(_));
+    let mut hunks = Vec::new();
+    let mut next = 0;
+    while let Some(first_change) = (next..lines.len()).find(|index| is_change(*index)) {
+        // Extend the hunk over changes separated by at most 2 * context lines
+        let mut end = first_change;
+        loop {
+            while end < lines.len() && is_change(end) {
+                end += 1;
+            }
+            let next_change = (end..lines.len()).find(|index| is_change(*index));
+            match next_change {
+                Some(index) if index - end <= 2 * context => end = index,
+                _ => break,
+            }
+        }
+
+        let start = first_change.saturating_sub(context).max(next);
+        let stop = (end + context).min(lines.len());
+        let hunk_lines: Vec<Line> = lines[start..stop].iter().map(|(line, ..)| *line).collect();
+        let old_count = hunk_lines
+            .iter()
+            .filter(|line| !matches!(line, Line::Added(_)))
+            .count();
+        let new_count = hunk_lines
+            .iter()
+            .filter(|line| !matches!(line, Line::Removed(_)))
+            .count();
+        let (_, old_index, new_index) = lines[start];
+        hunks.push(Hunk {
+            old: range(old_index, old_count),
+            new: range(new_index, new_count),
+            lines: hunk_lines,
+        });
+        next = stop;
+    }
+    hunks
+}
+
+fn range(index: usize, count: usize) -> LineRange {
+    LineRange {
+        start: if count == 0 { index } else { index + 1 },
+        count,
+    }
+}
+
+/// Unified diff from `old` to `new`, both named `path`, with ANSI colors if
+/// `color` is set. Empty if the texts are equal.
+pub fn unified_diff(path: &str, old: &str, new: &str, color: bool) -> String {
+    let hunks = hunks(old, new, 3);
+    if hunks.is_empty() {
+        return String::new();
+    }
+    let paint = |style: &str, text: &str| {
+        if color {
+            format!("{style}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    };
+
+    let mut diff = String::new();
+    diff.push_str(&paint(BOLD, &format!("--- {path}")));
+    diff.push('\n');
+    diff.push_str(&paint(BOLD, &format!("+++ {path}")));
+    diff.push('\n');
+    for hunk in &hunks {
+        let header = format!(
+            "@@ -{} +{} @@",
+            header_range(hunk.old),
+            header_range(hunk.new)
+        );
+        diff.push_str(&paint(CYAN, &header));
+        diff.push('\n');
+        for line in &hunk.lines {
+            let (marker, text, style) = match line {
+                Line::Context(text) => (' ', *text, None),
+                Line::Removed(text) => ('-', *text, Some(RED)),
+                Line::Added(text) => ('+', *text, Some(GREEN)),
+            };
+            let content = format!("{marker}{}", text.strip_suffix('\n').unwrap_or(text));
+            match style {
+                Some(style) => diff.push_str(&paint(style, &content)),
+                None => diff.push_str(&content),
+            }
+            diff.push('\n');
+            if !text.ends_with('\n') {
+                diff.push_str("\\ No newline at end of file\n");
+            }
+        }
+    }
+    diff
+}
+
+/// Range as written in a hunk header, where a count of 1 is left out.
+fn header_range(range: LineRange) -> String {
+    match range.count {
+        1 => range.start.to_string(),
+        count => format!("{},{count}", range.start),
+    }
+}
+
+/// Edit distance each search explores before it settles for a split that may
+/// not be optimal, which bounds the time spent on texts that were mostly
+/// rewritten.
+const MAX_SEARCH_D: usize = 1024;
+
+/// Edit script between the lines, using the linear space variant of Myers'
+/// algorithm: the middle of an optimal path splits the problem into two
+/// smaller ones. The script is the shortest unless a part of the texts needs
+/// more than `MAX_SEARCH_D` edits.
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    // Furthest reach on diagonals -d..=d of the forward and backward searches
+    let diagonals = 2 * (max_d(old.len(), new.len()) + 1);
+    let mut search = Search {
+        forward: vec![0; diagonals],
+        backward: vec![0; diagonals],
+        edits: Vec::with_capacity(old.len() + new.len()),
+    };
+    search.conquer(old, new);
+    // Removed lines come before the lines added in their place
+    for changes in search.edits.split_mut(|edit| *edit == Edit::Keep) {
+        changes.sort_by_key(|edit| *edit == Edit::Add);
+    }
+    search.edits
+}
+
+/// Edit distance after which the searches from both ends must have met, or
+/// stop.
+fn max_d(n: usize, m: usize) -> usize {
+    ((n + m + 1) / 2 + 1).min(MAX_SEARCH_D)
+}
+
+struct Search {
+    forward: Vec<usize>,
+    backward: Vec<usize>,
+    edits: Vec<Edit>,
+}
+
+impl Search {
+    fn conquer(&mut self, old: &[&str], new: &[&str]) {
+        // Common prefix and suffix need no search
+        let prefix = common_prefix(old, new);
+        let (old, new) = (&old[prefix..], &new[prefix..]);
+        let suffix = common_suffix(old, new);
+        let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+
+        self.edits
+            .extend(std::iter::repeat(Edit::Keep).take(prefix));
+        if old.is_empty() || new.is_empty() {
+            self.edits
+                .extend(std::iter::repeat(Edit::Remove).take(old.len()));
+            self.edits
+                .extend(std::iter::repeat(Edit::Add).take(new.len()));
+        } else {
+            let (x, y) = self.middle_snake(old, new);
+            self.conquer(&old[..x], &new[..y]);
+            self.conquer(&old[x..], &new[y..]);
+        }
+        self.edits
+            .extend(std::iter::repeat(Edit::Keep).take(suffix));
+    }
+
+    /// Point on an optimal path where the searches from the start and the
+    /// end of both texts meet. If they don't meet within `MAX_SEARCH_D` edits,
+    /// the point the search from the start got furthest to, like GNU diff.
+    fn middle_snake(&mut self, old: &[&str], new: &[&str]) -> (usize, usize) {
+        let (n, m) = (old.len(), new.len());
+        let delta = n as isize - m as isize;
+        let odd = delta & 1 == 1;
+        // Diagonal k = x - y is stored at k + offset
+        let offset = self.forward.len() as isize / 2;
+        let at = |k: isize| (k + offset) as usize;
+        self.forward[at(1)] = 0;
+        self.backward[at(1)] = 0;
+
+        let max_d = max_d(n, m) as isize;
+        for d in 0..max_d {
+            for k in (-d..=d).rev().step_by(2) {
+                let v = &self.forward;
+                let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                    v[at(k + 1)]
+                } else {
+                    v[at(k - 1)] + 1
+                };
+                let y = (x as isize - k) as usize;
+                let start = (x, y);
+                x += common_prefix(&old[x.min(n)..], &new[y.min(m)..]);
+                self.forward[at(k)] = x;
+                if odd && (k - delta).abs() < d && x + self.backward[at(-(k - delta))] >= n {
+                    return start;
+                }
+            }
+            for k in (-d..=d).rev().step_by(2) {
+                let v = &self.backward;
+                let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                    v[at(k + 1)]
+                } else {
+                    v[at(k - 1)] + 1
+                };
+                let mut y = (x as isize - k) as usize;
+                if x < n && y < m {
+                    let advance = common_suffix(&old[..n - x], &new[..m - y]);
+                    x += advance;
+                    y += advance;
+                }
+                self.backward[at(k)] = x;
+                if !odd && (k - delta).abs() <= d && x + self.forward[at(-(k - delta))] >= n {
+                    return (n - x, m - y);
+                }
+            }
+        }
+
+        let d = max_d - 1;
+        (-d..=d)
+            .step_by(2)
+            .map(|k| (self.forward[at(k)], self.forward[at(k)] as isize - k))
+            .filter(|&(x, y)| x <= n && (0..=m as isize).contains(&y))
+            .map(|(x, y)| (x, y as usize))
+            .max_by_key(|(x, y)| x + y)
+            .unwrap_or((n, 0))
+    }
+}
+
+fn common_prefix(a: &[&str], b: &[&str]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+fn common_suffix(a: &[&str], b: &[&str]) -> usize {
+    a.iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
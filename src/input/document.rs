@@ -0,0 +1,66 @@
+use super::front_matter::FrontMatter;
+use markdown_ppp::ast::{Block, Document};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Parsed input: the Markdown document together with its front matter.
+pub struct ParsedDocument {
+    pub front_matter: Option<FrontMatter>,
+    pub document: Document,
+}
+
+/// AST file layout: the document fields plus a `front_matter` field when
+/// present, so AST files without front matter keep their shape.
+///
+/// The fields are spelled out because `#[serde(flatten)]` loses error
+/// positions and YAML enum tags.
+#[derive(Serialize)]
+struct AstFileRef<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    front_matter: &'a Option<FrontMatter>,
+    blocks: &'a [Block],
+}
+
+#[derive(Deserialize)]
+struct AstFile {
+    #[serde(default)]
+    front_matter: Option<FrontMatter>,
+    blocks: Vec<Block>,
+}
+
+impl Serialize for ParsedDocument {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AstFileRef {
+            front_matter: &self.front_matter,
+            blocks: &self.document.blocks,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ParsedDocument {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let file = AstFile::deserialize(deserializer)?;
+        Ok(Self {
+            front_matter: file.front_matter,
+            document: Document {
+                blocks: file.blocks,
+            },
+        })
+    }
+}
+
+impl ParsedDocument {
+    /// Text value of a front matter field, see [`FrontMatter::text`].
+    pub fn metadata(&self, key: &str) -> Option<String> {
+        self.front_matter.as_ref()?.text(key)
+    }
+
+    /// Prepend the front matter, if any, to the rendered Markdown body.
+    pub fn with_front_matter(&self, body: String) -> anyhow::Result<String> {
+        match &self.front_matter {
+            Some(front_matter) if body.is_empty() => front_matter.to_markdown(),
+            Some(front_matter) => Ok(format!("{}\n{body}", front_matter.to_markdown()?)),
+            None => Ok(body),
+        }
+    }
+}
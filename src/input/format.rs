@@ -1,10 +1,23 @@
+use super::html::parse_html;
+use super::org::parse_org;
+use super::schema::{fill_block_defaults, fill_defaults};
+use super::{FrontMatter, ParsedDocument};
+use crate::diagnostic::{Position, SourceDiagnostic};
 use anyhow::Result;
 use core::str::FromStr;
+use markdown_ppp::ast::{Block, Document};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
 
 #[derive(Clone)]
 pub enum InputFormat {
     Markdown,
+    Html,
+    Org,
     AstJson,
+    AstJsonl,
     AstYaml,
 }
 
@@ -14,31 +27,160 @@ impl FromStr for InputFormat {
     fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "markdown" => Ok(InputFormat::Markdown),
+            "html" => Ok(InputFormat::Html),
+            "org" => Ok(InputFormat::Org),
             "ast-json" => Ok(InputFormat::AstJson),
+            "ast-jsonl" => Ok(InputFormat::AstJsonl),
             "ast-yaml" => Ok(InputFormat::AstYaml),
             _ => Err(format!(
-                "Invalid input format: {s}. Supported formats: markdown, ast-json, ast-yaml"
+                "Invalid input format: {s}. Supported formats: markdown, html, org, ast-json, ast-jsonl, ast-yaml"
             )),
         }
     }
 }
 
 impl InputFormat {
-    pub fn parse(&self, input: &str) -> Result<markdown_ppp::ast::Document> {
-        match self {
-            InputFormat::Markdown => {
-                let state = markdown_ppp::parser::MarkdownParserState::default();
-                markdown_ppp::parser::parse_markdown(state, input)
-                    .map_err(|err| anyhow::anyhow!("{err}"))
-            }
-            InputFormat::AstJson => {
-                let doc: markdown_ppp::ast::Document = serde_json::from_str(input)?;
-                Ok(doc)
-            }
-            InputFormat::AstYaml => {
-                let doc: markdown_ppp::ast::Document = serde_yaml::from_str(input)?;
-                Ok(doc)
+    /// Parse the input into a document; errors are reported as
+    /// [`SourceDiagnostic`]s pointing into `input`, which was read from `path`.
+    pub fn parse(&self, input: &str, path: Option<&Path>) -> Result<ParsedDocument> {
+        let result = match self {
+            InputFormat::Markdown => parse_markdown(input),
+            InputFormat::Html => Ok(parse_html(input)),
+            InputFormat::Org => Ok(parse_org(input)),
+            InputFormat::AstJson => parse_ast_json(input),
+            InputFormat::AstJsonl => parse_ast_jsonl(input),
+            InputFormat::AstYaml => from_yaml(input).map_err(|err| {
+                let diagnostic = SourceDiagnostic::error(strip_location(&err.to_string()));
+                match err.location() {
+                    Some(location) => diagnostic.at_offset(input, location.index()),
+                    None => diagnostic,
+                }
+            }),
+        };
+
+        result.map_err(|diagnostic| diagnostic.with_path(path).into())
+    }
+}
+
+/// Split off the front matter and parse the rest of the input as Markdown.
+fn parse_markdown(input: &str) -> Result<ParsedDocument, SourceDiagnostic> {
+    let (front_matter, body_start) = FrontMatter::split(input)?;
+
+    let body = &input[body_start..];
+    let state = markdown_ppp::parser::MarkdownParserState::default();
+    let document = markdown_ppp::parser::parse_markdown(state, body).map_err(|err| match err {
+        nom::Err::Error(err) | nom::Err::Failure(err) => {
+            SourceDiagnostic::error(format!("Failed to parse markdown: {:?}", err.code))
+                .at_offset(input, input.len() - err.input.len())
+        }
+        nom::Err::Incomplete(_) => SourceDiagnostic::error("Unexpected end of markdown input")
+            .at_offset(input, input.len()),
+    })?;
+
+    Ok(ParsedDocument {
+        front_matter,
+        document,
+    })
+}
+
+/// Parse an AST JSON document.
+fn parse_ast_json(input: &str) -> Result<ParsedDocument, SourceDiagnostic> {
+    from_json(input, fill_defaults).map_err(|err| {
+        let position = Position {
+            line: err.line(),
+            column: err.column().max(1),
+        };
+        SourceDiagnostic::error(strip_location(&err.to_string())).at(input, position)
+    })
+}
+
+/// Front matter line of an AST JSON Lines document.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FrontMatterLine {
+    front_matter: FrontMatter,
+}
+
+/// Parse AST JSON Lines: one top-level block per line, optionally preceded
+/// by a `{"front_matter": ...}` line. Blank lines are skipped.
+fn parse_ast_jsonl(input: &str) -> Result<ParsedDocument, SourceDiagnostic> {
+    let mut front_matter = None;
+    let mut blocks = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if blocks.is_empty() && front_matter.is_none() {
+            if let Ok(line) = serde_json::from_str::<FrontMatterLine>(line) {
+                front_matter = Some(line.front_matter);
+                continue;
             }
         }
+
+        let block = from_json::<Block>(line, fill_block_defaults).map_err(|err| {
+            let position = Position {
+                line: index + 1,
+                column: err.column().max(1),
+            };
+            SourceDiagnostic::error(strip_location(&err.to_string())).at(input, position)
+        })?;
+        blocks.push(block);
+    }
+
+    Ok(ParsedDocument {
+        front_matter,
+        document: Document { blocks },
+    })
+}
+
+/// Deserialize JSON that may leave out fields holding their default value,
+/// see `convert-to ast-json --omit-defaults`.
+fn from_json<T: DeserializeOwned>(json: &str, fill: fn(&mut Value)) -> serde_json::Result<T> {
+    serde_json::from_str(json).or_else(|err| {
+        // Errors of the filled in value have no position, so report the
+        // error of the JSON as written
+        let Ok(mut value) = serde_json::from_str::<Value>(json) else {
+            return Err(err);
+        };
+        fill(&mut value);
+        serde_json::from_value(value).map_err(|_| err)
+    })
+}
+
+/// Deserialize YAML that may leave out fields holding their default value and
+/// write enum variants as single-key maps, see `convert-to ast-yaml --omit-defaults`.
+fn from_yaml(yaml: &str) -> serde_yaml::Result<ParsedDocument> {
+    serde_yaml::from_str(yaml).or_else(|err| {
+        // Read through JSON, whose enums are single-key maps; errors are
+        // reported for the YAML as written
+        let Ok(mut value) = serde_yaml::from_str::<Value>(yaml) else {
+            return Err(err);
+        };
+        fill_defaults(&mut value);
+        serde_json::from_value(value).map_err(|_| err)
+    })
+}
+
+/// Remove the " at line X column Y" locations that serde errors put in their
+/// message, such as serde_yaml's "..., while scanning a quoted scalar at line X column Y".
+fn strip_location(message: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = message;
+    while let Some(index) = rest.find(" at line ") {
+        stripped.push_str(&rest[..index]);
+        let location = &rest[index + " at line ".len()..];
+        let line_end = location
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(location.len());
+        let after_line = &location[line_end..];
+        rest = match after_line.strip_prefix(" column ") {
+            Some(column) if line_end > 0 => column.trim_start_matches(|c: char| c.is_ascii_digit()),
+            _ => {
+                stripped.push_str(" at line ");
+                location
+            }
+        };
     }
+    stripped.push_str(rest);
+    stripped
 }
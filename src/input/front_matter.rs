@@ -0,0 +1,155 @@
+use crate::diagnostic::SourceDiagnostic;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Syntax of a front matter block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontMatterFormat {
+    /// Delimited by `---` lines
+    Yaml,
+    /// Delimited by `+++` lines
+    Toml,
+}
+
+impl FrontMatterFormat {
+    fn delimiter(self) -> &'static str {
+        match self {
+            FrontMatterFormat::Yaml => "---",
+            FrontMatterFormat::Toml => "+++",
+        }
+    }
+}
+
+/// Metadata block at the very beginning of a Markdown document.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FrontMatter {
+    pub format: FrontMatterFormat,
+
+    /// Parsed metadata
+    pub data: serde_json::Value,
+
+    /// Source text including the delimiters, kept to reproduce the block verbatim
+    #[serde(skip)]
+    pub raw: Option<String>,
+}
+
+impl FrontMatter {
+    /// Split the front matter off the beginning of `text`.
+    ///
+    /// Returns the front matter, if any, and the byte offset where the Markdown body starts.
+    pub fn split(text: &str) -> Result<(Option<Self>, usize), SourceDiagnostic> {
+        let Some((format, content_start, content_end, body_start)) = locate(text) else {
+            return Ok((None, 0));
+        };
+
+        let content = &text[content_start..content_end];
+        let data = match format {
+            FrontMatterFormat::Yaml => yaml_mapping(content).unwrap_or_default(),
+            FrontMatterFormat::Toml => {
+                let value: toml::Table = toml::from_str(content).map_err(|err| {
+                    let offset = err.span().map_or(0, |span| content_start + span.start);
+                    SourceDiagnostic::error(format!("Invalid TOML front matter: {}", err.message()))
+                        .at_offset(text, offset)
+                })?;
+                toml_to_json(toml::Value::Table(value))
+            }
+        };
+
+        let front_matter = FrontMatter {
+            format,
+            data,
+            raw: Some(text[..body_start].to_string()),
+        };
+        Ok((Some(front_matter), body_start))
+    }
+
+    /// Front matter as it should appear at the top of a Markdown document.
+    pub fn to_markdown(&self) -> Result<String> {
+        if let Some(raw) = &self.raw {
+            return Ok(raw.clone());
+        }
+
+        let content = match self.format {
+            FrontMatterFormat::Yaml => serde_yaml::to_string(&self.data)?,
+            FrontMatterFormat::Toml => toml::to_string(&self.data)?,
+        };
+        let delimiter = self.format.delimiter();
+        Ok(format!("{delimiter}\n{content}{delimiter}\n"))
+    }
+
+    /// Text value of a metadata field; lists of strings are joined with `, `.
+    pub fn text(&self, key: &str) -> Option<String> {
+        match self.data.get(key)? {
+            serde_json::Value::String(value) => Some(value.clone()),
+            serde_json::Value::Number(value) => Some(value.to_string()),
+            serde_json::Value::Array(values) => {
+                let values: Vec<_> = values.iter().filter_map(|value| value.as_str()).collect();
+                (!values.is_empty()).then(|| values.join(", "))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Byte offset where the Markdown body starts, after any front matter block.
+pub fn body_offset(text: &str) -> usize {
+    locate(text).map_or(0, |(_, _, _, body_start)| body_start)
+}
+
+/// Format, content range and body start of a front matter block.
+fn locate(text: &str) -> Option<(FrontMatterFormat, usize, usize, usize)> {
+    let first_line_end = text.find('\n')?;
+    let format = match text[..first_line_end].trim_end() {
+        "---" => FrontMatterFormat::Yaml,
+        "+++" => FrontMatterFormat::Toml,
+        _ => return None,
+    };
+
+    let content_start = first_line_end + 1;
+    let mut offset = content_start;
+    for line in text[content_start..].split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        let closes = trimmed == format.delimiter()
+            || (format == FrontMatterFormat::Yaml && trimmed == "...");
+        if closes {
+            // `---` is also a thematic break; text between two of them that
+            // isn't a YAML mapping belongs to the Markdown body
+            if format == FrontMatterFormat::Yaml
+                && yaml_mapping(&text[content_start..offset]).is_none()
+            {
+                return None;
+            }
+            return Some((format, content_start, offset, offset + line.len()));
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+/// Metadata of YAML front matter content, if it is a mapping or empty.
+fn yaml_mapping(content: &str) -> Option<serde_json::Value> {
+    match serde_yaml::from_str::<Option<serde_json::Value>>(content) {
+        Ok(None) => Some(serde_json::Value::Object(Default::default())),
+        Ok(Some(value)) if value.is_object() => Some(value),
+        _ => None,
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(value) => serde_json::Value::String(value),
+        toml::Value::Integer(value) => value.into(),
+        toml::Value::Float(value) => value.into(),
+        toml::Value::Boolean(value) => value.into(),
+        toml::Value::Datetime(value) => serde_json::Value::String(value.to_string()),
+        toml::Value::Array(values) => values.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => serde_json::Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
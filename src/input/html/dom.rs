@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Node of the element tree built from HTML source.
+pub enum Node {
+    Element(Element),
+    /// Text with entities decoded
+    Text(String),
+    /// Comment including its delimiters
+    Comment(String),
+}
+
+pub struct Element {
+    /// Tag name, lowercase unless the element is inside SVG or MathML
+    pub name: String,
+    /// Attributes with decoded values; names are lowercase unless the element
+    /// is SVG or MathML
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+    /// Byte range of the start tag in the source
+    pub start_tag: Range<usize>,
+    /// Byte range of the whole element in the source
+    pub source: Range<usize>,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Child elements, skipping text and comments.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Concatenated text of all descendants.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        collect_text(&self.children, &mut text);
+        text
+    }
+}
+
+fn collect_text(nodes: &[Node], text: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(value) => text.push_str(value),
+            Node::Element(element) if element.name == "br" => text.push('\n'),
+            Node::Element(element) => collect_text(&element.children, text),
+            Node::Comment(_) => {}
+        }
+    }
+}
+
+/// Elements that never have content or an end tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is not parsed as markup.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Phrasing elements, which do not end an open paragraph.
+const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "big", "br", "button", "cite", "code", "data", "del", "dfn",
+    "em", "font", "i", "img", "input", "ins", "kbd", "label", "mark", "math", "nobr", "q", "s",
+    "samp", "small", "span", "strike", "strong", "sub", "sup", "svg", "time", "tt", "u", "var",
+    "wbr",
+];
+
+/// Roots of foreign content, whose elements keep the case of their names and
+/// don't close open HTML elements.
+const FOREIGN_ELEMENTS: &[&str] = &["svg", "math"];
+
+/// Whether the element never has content or an end tag, like `<br>`.
+pub fn is_void(name: &str) -> bool {
+    VOID_ELEMENTS.contains(&name)
+}
+
+/// Whether the element is phrasing content, as opposed to a block.
+pub fn is_inline(name: &str) -> bool {
+    INLINE_ELEMENTS.contains(&name)
+}
+
+/// Whether the element starts SVG or MathML content.
+pub fn is_foreign(name: &str) -> bool {
+    FOREIGN_ELEMENTS.contains(&name)
+}
+
+/// Parse HTML into a list of top-level nodes.
+///
+/// The parser is tolerant like a browser: unknown markup is kept, unclosed
+/// elements are closed at the end of their parent and the usual optional end
+/// tags (`</p>`, `</li>`, `</td>`, ...) are inferred.
+pub fn parse(input: &str) -> Vec<Node> {
+    let mut builder = TreeBuilder {
+        input,
+        position: 0,
+        open: Vec::new(),
+        root: Vec::new(),
+    };
+    builder.run();
+    builder.root
+}
+
+struct TreeBuilder<'a> {
+    input: &'a str,
+    position: usize,
+    open: Vec<Element>,
+    root: Vec<Node>,
+}
+
+impl TreeBuilder<'_> {
+    fn run(&mut self) {
+        while self.position < self.input.len() {
+            let rest = &self.input[self.position..];
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                let end = comment.find("-->").map_or(rest.len(), |end| end + 7);
+                self.append(Node::Comment(rest[..end].to_string()));
+                self.position += end;
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                // Doctype and processing instructions carry no content
+                self.position += rest.find('>').map_or(rest.len(), |end| end + 1);
+            } else if rest.starts_with("</") && starts_with_letter(&rest[2..]) {
+                self.end_tag();
+            } else if rest.starts_with('<') && starts_with_letter(&rest[1..]) {
+                self.start_tag();
+            } else {
+                // A `<` that does not start a tag is text
+                let end = match rest.strip_prefix('<') {
+                    Some(after) => after.find('<').map_or(rest.len(), |end| end + 1),
+                    None => rest.find('<').unwrap_or(rest.len()),
+                };
+                self.text(&rest[..end]);
+                self.position += end;
+            }
+        }
+        while !self.open.is_empty() {
+            self.close(self.input.len());
+        }
+    }
+
+    fn text(&mut self, raw: &str) {
+        let text = decode_entities(raw);
+        if let Some(Node::Text(last)) = self.children().last_mut() {
+            last.push_str(&text);
+        } else {
+            self.append(Node::Text(text));
+        }
+    }
+
+    fn start_tag(&mut self) {
+        let start = self.position;
+        let bytes = self.input.as_bytes();
+        let mut position = start + 1;
+        let name_end = self.scan(position, |byte| {
+            byte.is_ascii_alphanumeric() || byte == b'-' || byte == b':'
+        });
+        let name = self.input[position..name_end].to_ascii_lowercase();
+        position = name_end;
+        // SVG and MathML names are case-sensitive, e.g. `viewBox`
+        let foreign = self.in_foreign_content() || is_foreign(&name);
+        let name = if foreign && !is_foreign(&name) {
+            self.input[start + 1..name_end].to_string()
+        } else {
+            name
+        };
+
+        let mut attributes = Vec::new();
+        let mut self_closing = false;
+        loop {
+            position = self.scan(position, |byte| byte.is_ascii_whitespace());
+            match bytes.get(position) {
+                None => break,
+                Some(b'>') => {
+                    position += 1;
+                    break;
+                }
+                Some(b'/') => {
+                    position += 1;
+                    self_closing = bytes.get(position) == Some(&b'>');
+                    continue;
+                }
+                Some(_) => {}
+            }
+
+            let key_end = self.scan(position + 1, |byte| {
+                !byte.is_ascii_whitespace() && !matches!(byte, b'=' | b'>' | b'/')
+            });
+            let key = if foreign {
+                self.input[position..key_end].to_string()
+            } else {
+                self.input[position..key_end].to_ascii_lowercase()
+            };
+            position = self.scan(key_end, |byte| byte.is_ascii_whitespace());
+            if bytes.get(position) != Some(&b'=') {
+                attributes.push((key, String::new()));
+                continue;
+            }
+            position = self.scan(position + 1, |byte| byte.is_ascii_whitespace());
+            let value = match bytes.get(position) {
+                Some(&quote) if quote == b'"' || quote == b'\'' => {
+                    let value_end = self.scan(position + 1, |byte| byte != quote);
+                    let value = &self.input[position + 1..value_end];
+                    position = (value_end + 1).min(self.input.len());
+                    value
+                }
+                _ => {
+                    let value_end =
+                        self.scan(position, |byte| !byte.is_ascii_whitespace() && byte != b'>');
+                    let value = &self.input[position..value_end];
+                    position = value_end;
+                    value
+                }
+            };
+            attributes.push((key, decode_entities(value)));
+        }
+        self.position = position;
+
+        if !self.in_foreign_content() {
+            self.close_implied(&name, start);
+        }
+        let element = Element {
+            name,
+            attributes,
+            children: Vec::new(),
+            start_tag: start..position,
+            source: start..position,
+        };
+
+        if self_closing || is_void(&element.name) {
+            self.append(Node::Element(element));
+        } else if !foreign && RAW_TEXT_ELEMENTS.contains(&element.name.as_str()) {
+            self.raw_text(element);
+        } else {
+            self.open.push(element);
+        }
+    }
+
+    /// Read the content of a raw text element up to its end tag.
+    fn raw_text(&mut self, mut element: Element) {
+        let rest = &self.input[self.position..];
+        let end_tag = format!("</{}", element.name);
+        let content_end = rest
+            .to_ascii_lowercase()
+            .find(&end_tag)
+            .unwrap_or(rest.len());
+        let content = &rest[..content_end];
+        if !content.is_empty() {
+            let text = match element.name.as_str() {
+                "script" | "style" => content.to_string(),
+                _ => decode_entities(content),
+            };
+            element.children.push(Node::Text(text));
+        }
+        let after = &rest[content_end..];
+        let end = content_end + after.find('>').map_or(after.len(), |end| end + 1);
+        self.position += end;
+        element.source.end = self.position;
+        self.append(Node::Element(element));
+    }
+
+    fn end_tag(&mut self) {
+        let rest = &self.input[self.position..];
+        let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+        let name = rest[2..end]
+            .trim_end_matches('>')
+            .split(|c: char| c.is_ascii_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        self.position += end;
+
+        // End tags without a matching open element are ignored
+        if let Some(index) = self
+            .open
+            .iter()
+            .rposition(|element| element.name.eq_ignore_ascii_case(name))
+        {
+            while self.open.len() > index + 1 {
+                self.close(self.position - end);
+            }
+            self.close(self.position);
+        }
+    }
+
+    /// Close elements whose end tag is implied by the start tag `name`.
+    fn close_implied(&mut self, name: &str, position: usize) {
+        let (closes, scope): (&[&str], &[&str]) = match name {
+            "li" => (&["li"], &["ul", "ol"]),
+            "dt" | "dd" => (&["dt", "dd"], &["dl"]),
+            "tr" => (&["tr", "td", "th"], &["table", "thead", "tbody", "tfoot"]),
+            "td" | "th" => (&["td", "th"], &["tr", "table"]),
+            "thead" | "tbody" | "tfoot" => {
+                (&["thead", "tbody", "tfoot", "tr", "td", "th"], &["table"])
+            }
+            "option" => (&["option"], &["select"]),
+            _ if !is_inline(name) => {
+                // Block elements end an open paragraph
+                if let Some(index) = self.open.iter().rposition(|element| element.name == "p") {
+                    let inline_above = self.open[index + 1..]
+                        .iter()
+                        .all(|element| is_inline(&element.name));
+                    if inline_above {
+                        while self.open.len() > index {
+                            self.close(position);
+                        }
+                    }
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        // Close from the outermost matching element inside the nearest scope
+        let scope_start = self
+            .open
+            .iter()
+            .rposition(|element| scope.contains(&element.name.as_str()))
+            .map_or(0, |index| index + 1);
+        if let Some(offset) = self.open[scope_start..]
+            .iter()
+            .position(|element| closes.contains(&element.name.as_str()))
+        {
+            while self.open.len() > scope_start + offset {
+                self.close(position);
+            }
+        }
+    }
+
+    /// Whether an open SVG or MathML element contains the current position.
+    fn in_foreign_content(&self) -> bool {
+        self.open.iter().any(|element| is_foreign(&element.name))
+    }
+
+    /// Close the innermost open element, which ends at `end`.
+    fn close(&mut self, end: usize) {
+        if let Some(mut element) = self.open.pop() {
+            element.source.end = end;
+            self.append(Node::Element(element));
+        }
+    }
+
+    fn children(&mut self) -> &mut Vec<Node> {
+        match self.open.last_mut() {
+            Some(element) => &mut element.children,
+            None => &mut self.root,
+        }
+    }
+
+    fn append(&mut self, node: Node) {
+        self.children().push(node);
+    }
+
+    /// Position of the first byte from `start` that does not match `predicate`.
+    fn scan(&self, start: usize, predicate: impl Fn(u8) -> bool) -> usize {
+        let bytes = self.input.as_bytes();
+        let mut position = start.min(bytes.len());
+        while position < bytes.len() && predicate(bytes[position]) {
+            position += 1;
+        }
+        position
+    }
+}
+
+fn starts_with_letter(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic())
+}
+
+/// Replace character references such as `&amp;`, `&#39;` and `&#x2014;`.
+/// Unknown references are kept as they are.
+pub fn decode_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match decode_reference(rest) {
+            Some((decoded, length)) => {
+                result.push_str(&decoded);
+                rest = &rest[length..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Decoded text and source length of the reference at the start of `text`.
+fn decode_reference(text: &str) -> Option<(String, usize)> {
+    let end = text.find(';').filter(|end| *end <= 32)?;
+    let reference = &text[..=end];
+    if let Some(number) = reference[1..end].strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        let character = char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+        return Some((character.to_string(), end + 1));
+    }
+    let characters = named_entities().get(reference)?;
+    Some((characters.to_string(), end + 1))
+}
+
+fn named_entities() -> &'static HashMap<&'static str, &'static str> {
+    static ENTITIES: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    ENTITIES.get_or_init(|| {
+        entities::ENTITIES
+            .iter()
+            .map(|entity| (entity.entity, entity.characters))
+            .collect()
+    })
+}
@@ -0,0 +1,568 @@
+//! HTML input: elements with a Markdown equivalent are mapped onto the AST,
+//! anything else is kept as raw HTML.
+
+pub mod dom;
+
+use self::dom::{is_foreign, is_inline, Element, Node};
+use super::front_matter::FrontMatterFormat;
+use super::{FrontMatter, ParsedDocument};
+use markdown_ppp::ast::{
+    Alignment, Block, CodeBlock, CodeBlockKind, Document, Heading, HeadingKind, Image, Inline,
+    Link, List, ListBulletKind, ListItem, ListKind, ListOrderedKindOptions, Table, TaskState,
+};
+use serde_json::{Map, Value};
+
+/// Parse an HTML page or fragment.
+///
+/// The `<title>` and the author, date, description and keywords `<meta>`
+/// tags become the front matter.
+pub fn parse_html(input: &str) -> ParsedDocument {
+    let nodes = dom::parse(input);
+    let mut converter = Converter {
+        input,
+        metadata: Map::new(),
+    };
+    let blocks = converter.blocks(&nodes);
+
+    let front_matter = (!converter.metadata.is_empty()).then_some(FrontMatter {
+        format: FrontMatterFormat::Yaml,
+        data: Value::Object(converter.metadata),
+        raw: None,
+    });
+    ParsedDocument {
+        front_matter,
+        document: Document { blocks },
+    }
+}
+
+/// Metadata fields taken from `<meta name="..." content="...">`.
+const META_FIELDS: &[&str] = &["author", "date", "description", "keywords"];
+
+struct Converter<'a> {
+    input: &'a str,
+    metadata: Map<String, Value>,
+}
+
+impl Converter<'_> {
+    /// Blocks of a node list; runs of inline content become paragraphs.
+    fn blocks(&mut self, nodes: &[Node]) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        let mut inline_nodes = Vec::new();
+        for node in nodes {
+            match node {
+                Node::Element(element) if !is_inline(&element.name) => {
+                    self.paragraph(&inline_nodes, &mut blocks);
+                    inline_nodes.clear();
+                    self.block(element, &mut blocks);
+                }
+                Node::Comment(comment) if inline_nodes.iter().all(is_blank) => {
+                    inline_nodes.clear();
+                    blocks.push(Block::HtmlBlock(comment.clone()));
+                }
+                _ => inline_nodes.push(node),
+            }
+        }
+        self.paragraph(&inline_nodes, &mut blocks);
+        blocks
+    }
+
+    fn block(&mut self, element: &Element, blocks: &mut Vec<Block>) {
+        match element.name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = element.name[1..].parse().unwrap_or(1);
+                let content = trimmed(self.inlines(&element.children));
+                if !content.is_empty() {
+                    blocks.push(Block::Heading(Heading {
+                        kind: HeadingKind::Atx(level),
+                        content,
+                    }));
+                }
+            }
+            "p" => {
+                let nodes: Vec<&Node> = element.children.iter().collect();
+                self.paragraph(&nodes, blocks);
+            }
+            "ul" | "ol" | "menu" => blocks.push(self.list(element)),
+            "pre" => blocks.push(code_block(element)),
+            "blockquote" => blocks.push(Block::BlockQuote(self.blocks(&element.children))),
+            "hr" => blocks.push(Block::ThematicBreak),
+            "table" => match self.table(element) {
+                Some(table) => blocks.push(table),
+                None => blocks.push(self.raw(element)),
+            },
+            // Containers without a Markdown equivalent only contribute their content
+            "html" | "body" | "main" | "article" | "section" | "div" | "header" | "footer"
+            | "center" | "li" | "tr" | "td" | "th" | "thead" | "tbody" | "tfoot" => {
+                blocks.extend(self.blocks(&element.children))
+            }
+            "head" => {
+                for child in element.elements() {
+                    self.metadata(child);
+                }
+            }
+            "title" | "meta" => self.metadata(element),
+            "link" | "base" => {}
+            _ => blocks.push(self.raw(element)),
+        }
+    }
+
+    /// Add a paragraph of the inline nodes unless they are blank.
+    fn paragraph(&mut self, nodes: &[&Node], blocks: &mut Vec<Block>) {
+        let mut inlines = trimmed(self.inlines(nodes.iter().copied()));
+        if inlines.is_empty() {
+            return;
+        }
+        if let Some(Inline::Text(text)) = inlines.first_mut() {
+            *text = escape_block_start(text);
+        }
+        blocks.push(Block::Paragraph(inlines));
+    }
+
+    fn list(&mut self, element: &Element) -> Block {
+        let kind = if element.name == "ol" {
+            let start = element
+                .attribute("start")
+                .and_then(|start| start.trim().parse().ok())
+                .unwrap_or(1);
+            ListKind::Ordered(ListOrderedKindOptions { start })
+        } else {
+            ListKind::Bullet(ListBulletKind::Dash)
+        };
+
+        let mut items: Vec<ListItem> = Vec::new();
+        for node in &element.children {
+            match node {
+                Node::Element(item) if item.name == "li" => items.push(self.list_item(item)),
+                _ if is_blank(&node) => {}
+                // Stray content, such as a nested list outside of an item,
+                // belongs to the previous item
+                _ => {
+                    let blocks = self.blocks(std::slice::from_ref(node));
+                    match items.last_mut() {
+                        Some(item) => item.blocks.extend(blocks),
+                        None => items.push(ListItem { task: None, blocks }),
+                    }
+                }
+            }
+        }
+        Block::List(List { kind, items })
+    }
+
+    fn list_item(&mut self, item: &Element) -> ListItem {
+        let mut children = item.children.as_slice();
+        let mut task = None;
+        // A leading checkbox makes a task list item
+        if let Some(index) = children.iter().position(|node| !is_blank(&node)) {
+            if let Node::Element(input) = &children[index] {
+                if input.name == "input" && input.attribute("type") == Some("checkbox") {
+                    task = Some(match input.attribute("checked") {
+                        Some(_) => TaskState::Complete,
+                        None => TaskState::Incomplete,
+                    });
+                    children = &children[index + 1..];
+                }
+            }
+        }
+        ListItem {
+            task,
+            blocks: self.blocks(children),
+        }
+    }
+
+    /// Table with one line per row; tables with merged cells or block
+    /// content cannot be written in Markdown.
+    fn table(&mut self, table: &Element) -> Option<Block> {
+        let mut rows = Vec::new();
+        for child in table.elements() {
+            match child.name.as_str() {
+                "thead" | "tbody" | "tfoot" => rows.extend(child.elements()),
+                "tr" => rows.push(child),
+                "caption" | "colgroup" => {}
+                _ => return None,
+            }
+        }
+
+        let mut alignments = Vec::new();
+        let mut table_rows = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            if row.name != "tr" {
+                return None;
+            }
+            let mut cells = Vec::new();
+            for cell in row.elements() {
+                let merged = ["colspan", "rowspan"]
+                    .iter()
+                    .any(|span| cell.attribute(span).is_some_and(|span| span.trim() != "1"));
+                let block_content = cell
+                    .elements()
+                    .any(|child| !is_inline(&child.name) && child.name != "p");
+                if !matches!(cell.name.as_str(), "td" | "th") || merged || block_content {
+                    return None;
+                }
+                if index == 0 {
+                    alignments.push(alignment(cell));
+                }
+                let mut inlines = trimmed(self.inlines(&cell.children));
+                escape_pipes(&mut inlines);
+                cells.push(inlines);
+            }
+            table_rows.push(cells);
+        }
+
+        let columns = table_rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return None;
+        }
+        for row in &mut table_rows {
+            row.resize_with(columns, Vec::new);
+        }
+        alignments.resize(columns, Alignment::None);
+        Some(Block::Table(Table {
+            rows: table_rows,
+            alignments,
+        }))
+    }
+
+    /// The element's source as a raw HTML block.
+    fn raw(&self, element: &Element) -> Block {
+        Block::HtmlBlock(self.source(element))
+    }
+
+    /// The element's source. Blank lines are dropped since they would end
+    /// the HTML in Markdown.
+    fn source(&self, element: &Element) -> String {
+        let source = &self.input[element.source.clone()];
+        let lines: Vec<&str> = source
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        lines.join("\n")
+    }
+
+    fn metadata(&mut self, element: &Element) {
+        let (key, value) = match element.name.as_str() {
+            "title" => ("title", element.text()),
+            "meta" => {
+                let Some(name) = element.attribute("name") else {
+                    return;
+                };
+                let name = name.to_ascii_lowercase();
+                let Some(key) = META_FIELDS.iter().find(|field| **field == name) else {
+                    return;
+                };
+                (
+                    *key,
+                    element.attribute("content").unwrap_or_default().to_string(),
+                )
+            }
+            _ => return,
+        };
+        let value = collapse_whitespace(&value).trim().to_string();
+        if !value.is_empty() && !self.metadata.contains_key(key) {
+            self.metadata.insert(key.to_string(), Value::String(value));
+        }
+    }
+
+    fn inlines<'n>(&mut self, nodes: impl IntoIterator<Item = &'n Node>) -> Vec<Inline> {
+        let mut inlines = Vec::new();
+        for node in nodes {
+            self.inline(node, &mut inlines);
+        }
+        merge_text(inlines)
+    }
+
+    fn inline(&mut self, node: &Node, inlines: &mut Vec<Inline>) {
+        let element = match node {
+            Node::Text(text) => {
+                inlines.push(Inline::Text(escape(&collapse_whitespace(text))));
+                return;
+            }
+            Node::Comment(comment) => {
+                inlines.push(Inline::Html(comment.clone()));
+                return;
+            }
+            Node::Element(element) => element,
+        };
+
+        match element.name.as_str() {
+            "em" | "i" => inlines.extend(styled(Inline::Emphasis, self.inlines(&element.children))),
+            "strong" | "b" => {
+                inlines.extend(styled(Inline::Strong, self.inlines(&element.children)))
+            }
+            "del" | "s" | "strike" => inlines.extend(styled(
+                Inline::Strikethrough,
+                self.inlines(&element.children),
+            )),
+            "code" | "kbd" | "samp" | "tt" => {
+                let code = collapse_whitespace(&element.text());
+                if !code.is_empty() {
+                    inlines.push(Inline::Code(code));
+                }
+            }
+            "a" => match element.attribute("href") {
+                Some(href) => inlines.push(Inline::Link(Link {
+                    destination: href.trim().replace(' ', "%20"),
+                    title: element.attribute("title").map(str::to_string),
+                    children: trimmed(self.inlines(&element.children)),
+                })),
+                // Anchors without a target only contribute their text
+                None => inlines.extend(self.inlines(&element.children)),
+            },
+            "img" => inlines.push(Inline::Image(Image {
+                destination: element
+                    .attribute("src")
+                    .unwrap_or_default()
+                    .trim()
+                    .replace(' ', "%20"),
+                title: element.attribute("title").map(str::to_string),
+                alt: escape(&collapse_whitespace(
+                    element.attribute("alt").unwrap_or_default(),
+                )),
+            })),
+            "br" => inlines.push(Inline::LineBreak),
+            // SVG and MathML have no Markdown equivalent and stay whole
+            name if is_foreign(name) => inlines.push(Inline::Html(self.source(element))),
+            name if is_inline(name) => {
+                // Phrasing elements without a Markdown equivalent keep their tags
+                inlines.push(Inline::Html(
+                    self.input[element.start_tag.clone()].to_string(),
+                ));
+                if element.source != element.start_tag {
+                    inlines.extend(self.inlines(&element.children));
+                    inlines.push(Inline::Html(format!("</{name}>")));
+                }
+            }
+            // Blocks inside inline content, such as paragraphs in table
+            // cells, are separated by spaces
+            _ => {
+                inlines.push(Inline::Text(" ".to_string()));
+                inlines.extend(self.inlines(&element.children));
+                inlines.push(Inline::Text(" ".to_string()));
+            }
+        }
+    }
+}
+
+fn is_blank(node: &&Node) -> bool {
+    matches!(node, Node::Text(text) if text.trim().is_empty())
+}
+
+/// Language of a code block from a `language-*` or `lang-*` class.
+fn language(element: &Element) -> Option<String> {
+    let class = element.attribute("class")?;
+    class
+        .split_whitespace()
+        .find_map(|class| {
+            class
+                .strip_prefix("language-")
+                .or_else(|| class.strip_prefix("lang-"))
+        })
+        .map(str::to_string)
+}
+
+fn code_block(pre: &Element) -> Block {
+    let mut elements = pre.children.iter().filter(|node| !is_blank(node));
+    let code = match (elements.next(), elements.next()) {
+        (Some(Node::Element(code)), None) if code.name == "code" => Some(code),
+        _ => None,
+    };
+
+    let text = code.unwrap_or(pre).text();
+    // A newline right after the start tag is not part of the content
+    let literal = text
+        .strip_prefix('\n')
+        .unwrap_or(&text)
+        .trim_end_matches('\n')
+        .to_string();
+    Block::CodeBlock(CodeBlock {
+        kind: CodeBlockKind::Fenced {
+            info: code.and_then(language).or_else(|| language(pre)),
+        },
+        literal,
+    })
+}
+
+/// Alignment of a table cell from its `align` attribute or `text-align` style.
+fn alignment(cell: &Element) -> Alignment {
+    let style = cell
+        .attribute("style")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let value = cell
+        .attribute("align")
+        .map(str::to_ascii_lowercase)
+        .or_else(|| {
+            style.split(';').find_map(|declaration| {
+                let (property, value) = declaration.split_once(':')?;
+                (property.trim() == "text-align").then(|| value.trim().to_string())
+            })
+        });
+    match value.as_deref() {
+        Some("left") => Alignment::Left,
+        Some("center") => Alignment::Center,
+        Some("right") => Alignment::Right,
+        _ => Alignment::None,
+    }
+}
+
+/// Wrap the inlines in a style, keeping surrounding spaces outside of the
+/// markers since Markdown does not allow them inside.
+fn styled(style: fn(Vec<Inline>) -> Inline, mut inlines: Vec<Inline>) -> Vec<Inline> {
+    let (leading, trailing) = trim(&mut inlines);
+    let mut result = Vec::new();
+    if leading {
+        result.push(Inline::Text(" ".to_string()));
+    }
+    if !inlines.is_empty() {
+        result.push(style(inlines));
+    }
+    if trailing {
+        result.push(Inline::Text(" ".to_string()));
+    }
+    result
+}
+
+fn trimmed(mut inlines: Vec<Inline>) -> Vec<Inline> {
+    trim(&mut inlines);
+    while matches!(inlines.first(), Some(Inline::LineBreak)) {
+        inlines.remove(0);
+        trim(&mut inlines);
+    }
+    while matches!(inlines.last(), Some(Inline::LineBreak)) {
+        inlines.pop();
+        trim(&mut inlines);
+    }
+    inlines
+}
+
+/// Remove whitespace at both ends; returns whether there was any.
+fn trim(inlines: &mut Vec<Inline>) -> (bool, bool) {
+    let mut leading = false;
+    if let Some(Inline::Text(text)) = inlines.first_mut() {
+        leading = text.starts_with(' ');
+        *text = text.trim_start_matches(' ').to_string();
+        if text.is_empty() {
+            inlines.remove(0);
+        }
+    }
+    let mut trailing = false;
+    if let Some(Inline::Text(text)) = inlines.last_mut() {
+        trailing = text.ends_with(' ');
+        *text = text.trim_end_matches(' ').to_string();
+        if text.is_empty() {
+            inlines.pop();
+        }
+    }
+    (leading, trailing)
+}
+
+/// Join adjacent text, collapse the spaces where they meet and drop spaces
+/// around hard line breaks.
+fn merge_text(inlines: Vec<Inline>) -> Vec<Inline> {
+    let mut merged: Vec<Inline> = Vec::new();
+    for inline in inlines {
+        match (merged.last_mut(), inline) {
+            (Some(Inline::Text(last)), Inline::Text(text)) => {
+                if last.ends_with(' ') {
+                    last.push_str(text.trim_start_matches(' '));
+                } else {
+                    last.push_str(&text);
+                }
+            }
+            (Some(Inline::Text(last)), Inline::LineBreak) => {
+                *last = last.trim_end_matches(' ').to_string();
+                merged.push(Inline::LineBreak);
+            }
+            (Some(Inline::LineBreak), Inline::Text(text)) => {
+                merged.push(Inline::Text(text.trim_start_matches(' ').to_string()));
+            }
+            (_, inline) => merged.push(inline),
+        }
+    }
+    merged.retain(|inline| !matches!(inline, Inline::Text(text) if text.is_empty()));
+    merged
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !space {
+                result.push(' ');
+            }
+            space = true;
+        } else {
+            result.push(c);
+            space = false;
+        }
+    }
+    result
+}
+
+/// Escape characters that Markdown would read as inline syntax.
+fn escape(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    for (index, &c) in chars.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|index| chars[index]);
+        let next = chars.get(index + 1).copied();
+        let special = match c {
+            '\\' | '`' | '*' | '[' | ']' | '~' => true,
+            // Underscores inside words are not emphasis
+            '_' => {
+                !previous.is_some_and(char::is_alphanumeric)
+                    || !next.is_some_and(char::is_alphanumeric)
+            }
+            '<' => next.is_some_and(|next| next.is_ascii_alphabetic() || "/!?".contains(next)),
+            '&' => is_reference(&chars[index + 1..]),
+            _ => false,
+        };
+        if special {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Whether the text after `&` would be read as a character reference.
+fn is_reference(chars: &[char]) -> bool {
+    let name: Vec<&char> = chars
+        .iter()
+        .take_while(|c| c.is_ascii_alphanumeric() || **c == '#')
+        .collect();
+    !name.is_empty() && chars.get(name.len()) == Some(&';')
+}
+
+/// Escape a leading word that Markdown would read as a block marker.
+fn escape_block_start(text: &str) -> String {
+    let word = text.split(' ').next().unwrap_or_default();
+    let digits = word.trim_start_matches(|c: char| c.is_ascii_digit());
+    let marker =
+        if word.chars().all(|c| c == '#') || matches!(word, "-" | "+") || word.starts_with('>') {
+            Some(0)
+        } else if digits.len() < word.len() && matches!(digits, "." | ")") {
+            Some(word.len() - 1)
+        } else {
+            None
+        };
+    match marker {
+        Some(position) => format!("{}\\{}", &text[..position], &text[position..]),
+        None => text.to_string(),
+    }
+}
+
+/// Escape `|` in table cells, where it would end the cell.
+fn escape_pipes(inlines: &mut [Inline]) {
+    for inline in inlines {
+        match inline {
+            Inline::Text(text) | Inline::Code(text) => *text = text.replace('|', "\\|"),
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children) => escape_pipes(children),
+            Inline::Link(link) => escape_pipes(&mut link.children),
+            _ => {}
+        }
+    }
+}
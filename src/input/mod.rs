@@ -1,5 +1,15 @@
+pub mod document;
 pub mod format;
+pub mod front_matter;
+pub mod html;
+pub mod org;
+pub mod paths;
 pub mod reader;
+pub mod schema;
+pub mod walk;
 
+pub use document::ParsedDocument;
 pub use format::InputFormat;
-pub use reader::read_input;
+pub use front_matter::FrontMatter;
+pub use paths::expand_inputs;
+pub use reader::{read_file, read_input};
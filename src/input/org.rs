@@ -0,0 +1,767 @@
+//! Org input: headlines, lists, blocks, tables, links and emphasis markers are
+//! mapped onto the Markdown AST, the keywords at the top become the front
+//! matter.
+
+use super::front_matter::FrontMatterFormat;
+use super::{FrontMatter, ParsedDocument};
+use crate::printer::org::{IMAGE_EXTENSIONS, ZERO_WIDTH_SPACE};
+use markdown_ppp::ast::{
+    Alignment, Block, CodeBlock, CodeBlockKind, Document, FootnoteDefinition, GitHubAlert,
+    GitHubAlertType, Heading, HeadingKind, Image, Inline, Link, List, ListBulletKind, ListItem,
+    ListKind, ListOrderedKindOptions, Table, TaskState,
+};
+use serde_json::{Map, Value};
+
+/// Parse an Org document.
+pub fn parse_org(input: &str) -> ParsedDocument {
+    let lines: Vec<String> = input.lines().map(str::to_string).collect();
+
+    // `#+KEY: value` lines before the first content are document metadata
+    let mut metadata = Map::new();
+    let mut start = 0;
+    for line in &lines {
+        let line = line.trim();
+        if let Some((key, value)) = keyword(line) {
+            if is_block_keyword(key) {
+                break;
+            }
+            metadata.insert(key.to_lowercase(), Value::String(value.to_string()));
+        } else if !line.is_empty() {
+            break;
+        }
+        start += 1;
+    }
+
+    let front_matter = (!metadata.is_empty()).then_some(FrontMatter {
+        format: FrontMatterFormat::Yaml,
+        data: Value::Object(metadata),
+        raw: None,
+    });
+    ParsedDocument {
+        front_matter,
+        document: Document {
+            blocks: parse_blocks(&lines[start..], true),
+        },
+    }
+}
+
+/// Key and value of a `#+KEY: value` line.
+fn keyword(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.strip_prefix("#+")?.split_once(':')?;
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    is_key.then(|| (key, value.trim()))
+}
+
+/// Keywords that belong to the following element rather than the document.
+fn is_block_keyword(key: &str) -> bool {
+    let key = key.to_ascii_uppercase();
+    key.starts_with("ATTR_") || matches!(key.as_str(), "CAPTION" | "NAME" | "RESULTS")
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+/// Level of a headline, which starts with stars at the beginning of the line.
+fn headline_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '*').count();
+    let rest = &line[level..];
+    (level > 0 && (rest.is_empty() || rest.starts_with(' '))).then_some(level)
+}
+
+/// Name and arguments of a `#+BEGIN_NAME args` line.
+fn block_start(line: &str) -> Option<(String, &str)> {
+    let rest = line
+        .get(..8)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("#+begin_"))
+        .map(|_| &line[8..])?;
+    let (name, arguments) = rest.split_once(' ').unwrap_or((rest, ""));
+    (!name.is_empty()).then(|| (name.to_ascii_uppercase(), arguments.trim()))
+}
+
+fn is_drawer_start(line: &str) -> bool {
+    line.len() > 2
+        && line.starts_with(':')
+        && line.ends_with(':')
+        && line[1..line.len() - 1]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn is_rule(line: &str) -> bool {
+    line.len() >= 5 && line.chars().all(|c| c == '-')
+}
+
+fn is_fixed_width(line: &str) -> bool {
+    line == ":" || line.starts_with(": ")
+}
+
+/// Label and text of a `[fn:label] text` line.
+fn footnote_definition(line: &str) -> Option<(&str, &str)> {
+    let (label, text) = line.strip_prefix("[fn:")?.split_once(']')?;
+    (!label.is_empty() && !label.contains(':')).then(|| (label, text.trim_start()))
+}
+
+struct Item<'a> {
+    indent: usize,
+    ordered: bool,
+    number: Option<u64>,
+    /// Column where the item text starts
+    content_column: usize,
+    content: &'a str,
+}
+
+/// List item starting on the line; `*` bullets must be indented at the top
+/// level, where they would be headlines otherwise.
+fn list_item(line: &str, top: bool) -> Option<Item<'_>> {
+    let indent = indentation(line);
+    let trimmed = &line[indent..];
+    let marker_length = match trimmed.chars().next()? {
+        '-' | '+' => 1,
+        '*' if indent > 0 || !top => 1,
+        c if c.is_ascii_digit() => {
+            let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+            match trimmed[digits..].chars().next() {
+                Some('.' | ')') => digits + 1,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let rest = &trimmed[marker_length..];
+    if !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    let content = rest.trim_start();
+    Some(Item {
+        indent,
+        ordered: trimmed.starts_with(|c: char| c.is_ascii_digit()),
+        number: trimmed[..marker_length - 1].parse().ok(),
+        content_column: indent + marker_length + (rest.len() - content.len()).max(1),
+        content,
+    })
+}
+
+/// Whether the line starts a block other than a paragraph.
+fn is_block_start(line: &str, top: bool) -> bool {
+    let trimmed = line.trim_start();
+    (top && headline_level(line).is_some())
+        || trimmed.starts_with("#+")
+        || trimmed == "#"
+        || trimmed.starts_with("# ")
+        || trimmed.starts_with('|')
+        || is_rule(trimmed)
+        || is_fixed_width(trimmed)
+        || is_drawer_start(trimmed)
+        || list_item(line, top).is_some()
+        || footnote_definition(line).is_some()
+}
+
+/// Remove up to `width` columns of indentation from every line.
+fn dedent(lines: &[String], width: usize) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| line[indentation(line).min(width)..].to_string())
+        .collect()
+}
+
+/// Remove the indentation common to all non-blank lines.
+fn dedent_common(lines: &[String]) -> Vec<String> {
+    let width = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indentation(line))
+        .min()
+        .unwrap_or(0);
+    dedent(lines, width)
+}
+
+/// Remove the comma that protects lines starting with `*` or `#+` in blocks.
+fn unescape_code_line(line: &str) -> String {
+    let indent = indentation(line);
+    let content = &line[indent..];
+    match content.strip_prefix(',') {
+        Some(rest) if rest.trim_start_matches(',').starts_with(['*']) => {
+            format!("{}{rest}", &line[..indent])
+        }
+        Some(rest) if rest.trim_start_matches(',').starts_with("#+") => {
+            format!("{}{rest}", &line[..indent])
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// Blocks of the lines; `top` is set at the document level, where
+/// headlines may appear.
+fn parse_blocks(lines: &[String], top: bool) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut image_alt: Option<String> = None;
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index].as_str();
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            index += 1;
+            continue;
+        }
+
+        if let Some(level) = headline_level(line).filter(|_| top) {
+            let content = line[level..].trim();
+            blocks.push(Block::Heading(Heading {
+                kind: HeadingKind::Atx(level.min(6) as u8),
+                content: parse_inlines(content),
+            }));
+            index += 1;
+            continue;
+        }
+
+        if let Some((name, arguments)) = block_start(trimmed) {
+            let end_line = format!("#+end_{}", name.to_ascii_lowercase());
+            let end = lines[index + 1..]
+                .iter()
+                .position(|line| line.trim().to_ascii_lowercase().starts_with(&end_line))
+                .map_or(lines.len(), |end| index + 1 + end);
+            let content = dedent_common(&lines[index + 1..end]);
+            blocks.extend(special_block(&name, arguments, &content));
+            index = end + 1;
+            continue;
+        }
+
+        if let Some((key, value)) = keyword(trimmed) {
+            if key.eq_ignore_ascii_case("ATTR_HTML") {
+                if let Some((_, alt)) = value.split_once(":alt ") {
+                    let alt = alt.split(" :").next().unwrap_or_default();
+                    image_alt = Some(alt.trim().to_string());
+                }
+            }
+            index += 1;
+            continue;
+        }
+
+        if trimmed == "#" || trimmed.starts_with("# ") {
+            index += 1;
+            continue;
+        }
+
+        if is_drawer_start(trimmed) {
+            let end = lines[index + 1..]
+                .iter()
+                .position(|line| line.trim().eq_ignore_ascii_case(":END:"));
+            index = end.map_or(index + 1, |end| index + end + 2);
+            continue;
+        }
+
+        if trimmed.starts_with('|') {
+            let end = lines[index..]
+                .iter()
+                .position(|line| !line.trim_start().starts_with('|'))
+                .map_or(lines.len(), |end| index + end);
+            blocks.extend(table(&lines[index..end]));
+            index = end;
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            blocks.push(Block::ThematicBreak);
+            index += 1;
+            continue;
+        }
+
+        if is_fixed_width(trimmed) {
+            let mut literal = Vec::new();
+            while let Some(line) = lines.get(index).map(|line| line.trim()) {
+                if !is_fixed_width(line) {
+                    break;
+                }
+                literal.push(line.strip_prefix(": ").unwrap_or_default());
+                index += 1;
+            }
+            blocks.push(Block::CodeBlock(CodeBlock {
+                kind: CodeBlockKind::Fenced { info: None },
+                literal: literal.join("\n"),
+            }));
+            continue;
+        }
+
+        if let Some(item) = list_item(line, top) {
+            let (list, end) = parse_list(lines, index, item.indent, item.ordered, top);
+            blocks.push(list);
+            index = end;
+            continue;
+        }
+
+        if let Some((label, text)) = footnote_definition(line) {
+            let mut content = vec![text.to_string()];
+            let mut end = index + 1;
+            let mut blank_lines = 0;
+            while let Some(line) = lines.get(end) {
+                // Two blank lines, a headline or another definition end it
+                if line.trim().is_empty() {
+                    blank_lines += 1;
+                    if blank_lines == 2 {
+                        break;
+                    }
+                } else if footnote_definition(line).is_some()
+                    || (top && headline_level(line).is_some())
+                {
+                    break;
+                } else {
+                    blank_lines = 0;
+                }
+                content.push(line.clone());
+                end += 1;
+            }
+            let marker_width = label.len() + 6;
+            blocks.push(Block::FootnoteDefinition(FootnoteDefinition {
+                label: label.to_string(),
+                blocks: parse_blocks(&dedent(&content, marker_width), false),
+            }));
+            index = end;
+            continue;
+        }
+
+        // Paragraph up to a blank line or the start of another block
+        let mut text = vec![trimmed];
+        index += 1;
+        while let Some(line) = lines.get(index) {
+            if line.trim().is_empty() || is_block_start(line, top) {
+                break;
+            }
+            text.push(line.trim());
+            index += 1;
+        }
+        let mut inlines = parse_inlines(&text.join("\n"));
+        if let ([Inline::Image(image)], Some(alt)) = (inlines.as_mut_slice(), image_alt.take()) {
+            image.alt = alt;
+        }
+        blocks.push(Block::Paragraph(inlines));
+    }
+    blocks
+}
+
+fn special_block(name: &str, arguments: &str, content: &[String]) -> Vec<Block> {
+    let literal = || {
+        content
+            .iter()
+            .map(|line| unescape_code_line(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let alert_type = match name {
+        "NOTE" => Some(GitHubAlertType::Note),
+        "TIP" => Some(GitHubAlertType::Tip),
+        "IMPORTANT" => Some(GitHubAlertType::Important),
+        "WARNING" => Some(GitHubAlertType::Warning),
+        "CAUTION" => Some(GitHubAlertType::Caution),
+        _ => None,
+    };
+
+    match name {
+        "SRC" | "EXAMPLE" => {
+            let language = arguments
+                .split_whitespace()
+                .next()
+                .filter(|_| name == "SRC");
+            vec![Block::CodeBlock(CodeBlock {
+                kind: CodeBlockKind::Fenced {
+                    info: language.map(str::to_string),
+                },
+                literal: literal(),
+            })]
+        }
+        "QUOTE" => vec![Block::BlockQuote(parse_blocks(content, false))],
+        "EXPORT" if arguments.eq_ignore_ascii_case("html") => vec![Block::HtmlBlock(literal())],
+        "EXPORT" | "COMMENT" => Vec::new(),
+        _ => match alert_type {
+            Some(alert_type) => vec![Block::GitHubAlert(GitHubAlert {
+                alert_type,
+                blocks: parse_blocks(content, false),
+            })],
+            // Other blocks, e.g. `CENTER` or `VERSE`, only contribute their content
+            None => parse_blocks(content, false),
+        },
+    }
+}
+
+/// List starting at line `start`; returns the list and the line after it.
+fn parse_list(
+    lines: &[String],
+    start: usize,
+    indent: usize,
+    ordered: bool,
+    top: bool,
+) -> (Block, usize) {
+    let mut items = Vec::new();
+    let mut first_number = None;
+    let mut index = start;
+
+    while let Some(item) = lines
+        .get(index)
+        .and_then(|line| list_item(line, top))
+        .filter(|item| item.indent == indent && item.ordered == ordered)
+    {
+        // The item continues with lines indented further than its bullet;
+        // a single blank line is allowed between them
+        let mut end = index + 1;
+        let mut next = end;
+        while let Some(line) = lines.get(next) {
+            if line.trim().is_empty() {
+                next += 1;
+                continue;
+            }
+            if indentation(line) <= indent || next - end > 1 {
+                break;
+            }
+            next += 1;
+            end = next;
+        }
+
+        let mut content = item.content;
+        let mut task = None;
+        if let Some(rest) = content.strip_prefix("[@") {
+            if let Some((number, rest)) = rest.split_once(']') {
+                first_number = first_number.or(number.parse().ok());
+                content = rest.trim_start();
+            }
+        }
+        for (cookie, state) in [
+            ("[X]", TaskState::Complete),
+            ("[x]", TaskState::Complete),
+            ("[ ]", TaskState::Incomplete),
+            ("[-]", TaskState::Incomplete),
+        ] {
+            if let Some(rest) = content.strip_prefix(cookie) {
+                task = Some(state);
+                content = rest.trim_start();
+                break;
+            }
+        }
+        if items.is_empty() {
+            first_number = first_number.or(item.number);
+        }
+
+        // Description items `term :: text` start with the term in bold
+        let mut term = None;
+        if !ordered {
+            if let Some((name, text)) = content.split_once(" ::") {
+                if text.is_empty() || text.starts_with(' ') {
+                    term = Some(name.trim());
+                    content = text.trim_start();
+                }
+            }
+        }
+
+        let mut item_lines = vec![content.to_string()];
+        item_lines.extend(dedent(&lines[index + 1..end], item.content_column));
+        let mut blocks = parse_blocks(&item_lines, false);
+        if let Some(term) = term {
+            let mut prefix = vec![Inline::Strong(parse_inlines(term))];
+            match blocks.first_mut() {
+                Some(Block::Paragraph(inlines)) => {
+                    prefix.push(Inline::Text(": ".to_string()));
+                    inlines.splice(0..0, prefix);
+                }
+                _ => blocks.insert(0, Block::Paragraph(prefix)),
+            }
+        }
+        items.push(ListItem { task, blocks });
+
+        // Items may be separated by a single blank line
+        index = end;
+        if lines.get(index).is_some_and(|line| line.trim().is_empty())
+            && lines
+                .get(index + 1)
+                .is_some_and(|line| !line.trim().is_empty())
+        {
+            index += 1;
+        }
+    }
+
+    let kind = if ordered {
+        ListKind::Ordered(ListOrderedKindOptions {
+            start: first_number.unwrap_or(1),
+        })
+    } else {
+        ListKind::Bullet(ListBulletKind::Dash)
+    };
+    // Do not swallow a blank line that does not lead to another item
+    if index > start
+        && lines
+            .get(index - 1)
+            .is_some_and(|line| line.trim().is_empty())
+    {
+        index -= 1;
+    }
+    (Block::List(List { kind, items }), index)
+}
+
+/// Table of `|` lines; the first row is the header and a row of `<l>`,
+/// `<c>` and `<r>` cookies sets the column alignments.
+fn table(lines: &[String]) -> Option<Block> {
+    let mut rows: Vec<Vec<Vec<Inline>>> = Vec::new();
+    let mut alignments = Vec::new();
+
+    for line in lines {
+        let line = line.trim();
+        if line.starts_with("|-") {
+            continue;
+        }
+        let line = line.strip_prefix('|').unwrap_or(line);
+        let line = line.strip_suffix('|').unwrap_or(line);
+        let cells: Vec<&str> = line.split('|').map(str::trim).collect();
+
+        let cookies: Option<Vec<Alignment>> =
+            cells.iter().map(|cell| alignment_cookie(cell)).collect();
+        if let Some(cookies) = cookies.filter(|_| cells.iter().any(|cell| !cell.is_empty())) {
+            if alignments.is_empty() {
+                alignments = cookies;
+            }
+            continue;
+        }
+
+        rows.push(
+            cells
+                .iter()
+                .map(|cell| {
+                    let mut inlines = parse_inlines(cell);
+                    escape_pipes(&mut inlines);
+                    inlines
+                })
+                .collect(),
+        );
+    }
+
+    let columns = rows.iter().map(Vec::len).max()?;
+    for row in &mut rows {
+        row.resize_with(columns, Vec::new);
+    }
+    alignments.resize(columns, Alignment::None);
+    Some(Block::Table(Table { rows, alignments }))
+}
+
+/// Alignment of a table cookie such as `<l>` or `<r10>`; `None` for other
+/// cells, an empty cell has no alignment.
+fn alignment_cookie(cell: &str) -> Option<Alignment> {
+    if cell.is_empty() {
+        return Some(Alignment::None);
+    }
+    let cookie = cell.strip_prefix('<')?.strip_suffix('>')?;
+    let (alignment, width) = cookie.split_at(cookie.chars().next()?.len_utf8());
+    if !width.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match alignment {
+        "l" => Some(Alignment::Left),
+        "c" => Some(Alignment::Center),
+        "r" => Some(Alignment::Right),
+        _ if cookie.chars().all(|c| c.is_ascii_digit()) => Some(Alignment::None),
+        _ => None,
+    }
+}
+
+/// Escape `|` in table cells as Markdown would.
+fn escape_pipes(inlines: &mut [Inline]) {
+    for inline in inlines {
+        match inline {
+            Inline::Text(text) | Inline::Code(text) => *text = text.replace('|', "\\|"),
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children) => escape_pipes(children),
+            Inline::Link(link) => escape_pipes(&mut link.children),
+            _ => {}
+        }
+    }
+}
+
+/// Characters allowed before an opening and after a closing emphasis marker.
+const MARKER_PRE: &str = "-('\"{\u{200B}";
+const MARKER_POST: &str = "-.,:!?;'\")}[\u{200B}";
+
+fn parse_inlines(text: &str) -> Vec<Inline> {
+    let chars: Vec<char> = text.chars().collect();
+    inlines(&chars)
+}
+
+fn inlines(chars: &[char]) -> Vec<Inline> {
+    let mut result = Vec::new();
+    let mut text = String::new();
+    let mut index = 0;
+    let flush = |text: &mut String, result: &mut Vec<Inline>| {
+        if !text.is_empty() {
+            result.push(Inline::Text(std::mem::take(text)));
+        }
+    };
+
+    while index < chars.len() {
+        let c = chars[index];
+        let rest = &chars[index..];
+        let previous = index.checked_sub(1).map(|index| chars[index]);
+
+        if c == ZERO_WIDTH_SPACE {
+            index += 1;
+            continue;
+        }
+
+        if starts_with(rest, "[[") {
+            if let Some((inline, length)) = link(rest) {
+                flush(&mut text, &mut result);
+                result.push(inline);
+                index += length;
+                continue;
+            }
+        }
+
+        if starts_with(rest, "[fn:") {
+            if let Some(end) = rest.iter().position(|c| *c == ']') {
+                let label: String = rest[4..end].iter().collect();
+                if !label.is_empty() && !label.contains(':') {
+                    flush(&mut text, &mut result);
+                    result.push(Inline::FootnoteReference(label));
+                    index += end + 1;
+                    continue;
+                }
+            }
+        }
+
+        if starts_with(rest, "@@html:") {
+            if let Some(end) = find(&rest[7..], "@@") {
+                flush(&mut text, &mut result);
+                result.push(Inline::Html(rest[7..7 + end].iter().collect()));
+                index += end + 9;
+                continue;
+            }
+        }
+
+        if starts_with(rest, "\\\\") && matches!(rest.get(2), None | Some('\n')) {
+            flush(&mut text, &mut result);
+            result.push(Inline::LineBreak);
+            index += 3;
+            continue;
+        }
+
+        if starts_with(rest, "\\vert{}") {
+            text.push('|');
+            index += 7;
+            continue;
+        }
+
+        if "*/_+=~".contains(c)
+            && previous.map_or(true, |c| c.is_whitespace() || MARKER_PRE.contains(c))
+        {
+            if let Some(end) = closing_marker(rest) {
+                flush(&mut text, &mut result);
+                let content = &rest[1..end];
+                result.push(match c {
+                    '=' | '~' => {
+                        Inline::Code(content.iter().collect::<String>().replace('\n', " "))
+                    }
+                    '*' => Inline::Strong(inlines(content)),
+                    '+' => Inline::Strikethrough(inlines(content)),
+                    _ => Inline::Emphasis(inlines(content)),
+                });
+                index += end + 1;
+                continue;
+            }
+        }
+
+        if previous.map_or(true, |c| c.is_whitespace() || c == '(')
+            && ["https://", "http://", "ftp://"]
+                .iter()
+                .any(|scheme| starts_with(rest, scheme))
+        {
+            let mut end = rest
+                .iter()
+                .position(|c| c.is_whitespace())
+                .unwrap_or(rest.len());
+            while end > 0 && ".,;:!?)'\"".contains(rest[end - 1]) {
+                end -= 1;
+            }
+            flush(&mut text, &mut result);
+            result.push(Inline::Autolink(rest[..end].iter().collect()));
+            index += end;
+            continue;
+        }
+
+        text.push(c);
+        index += 1;
+    }
+    flush(&mut text, &mut result);
+    result
+}
+
+fn starts_with(chars: &[char], prefix: &str) -> bool {
+    let mut chars = chars.iter();
+    prefix.chars().all(|c| chars.next() == Some(&c))
+}
+
+fn find(chars: &[char], needle: &str) -> Option<usize> {
+    (0..chars.len()).find(|index| starts_with(&chars[*index..], needle))
+}
+
+/// Position of the marker that closes the one at the start of `chars`.
+/// The content may not start or end with whitespace nor span more than two
+/// lines.
+fn closing_marker(chars: &[char]) -> Option<usize> {
+    let marker = chars[0];
+    if chars.get(1).map_or(true, |c| c.is_whitespace()) {
+        return None;
+    }
+    let mut newlines = 0;
+    for (index, &c) in chars.iter().enumerate().skip(2) {
+        if c == '\n' {
+            newlines += 1;
+            if newlines > 1 {
+                return None;
+            }
+        }
+        let after = chars.get(index + 1);
+        if c == marker
+            && !chars[index - 1].is_whitespace()
+            && after.map_or(true, |c| c.is_whitespace() || MARKER_POST.contains(*c))
+        {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// A `[[target][description]]` or `[[target]]` link and its length.
+fn link(chars: &[char]) -> Option<(Inline, usize)> {
+    let end = find(chars, "]]")?;
+    let inner: String = chars[2..end].iter().collect();
+    let (target, description) = match inner.split_once("][") {
+        Some((target, description)) => (target, Some(description)),
+        None => (inner.as_str(), None),
+    };
+    let destination = target.strip_prefix("file:").unwrap_or(target).to_string();
+
+    let inline = match description {
+        Some(description) => Inline::Link(Link {
+            destination,
+            title: None,
+            children: parse_inlines(description),
+        }),
+        None if is_image(&destination) => Inline::Image(Image {
+            destination,
+            title: None,
+            alt: String::new(),
+        }),
+        None if destination.contains("://") => Inline::Autolink(destination),
+        None => Inline::Link(Link {
+            children: vec![Inline::Text(target.to_string())],
+            destination,
+            title: None,
+        }),
+    };
+    Some((inline, end + 2))
+}
+
+fn is_image(destination: &str) -> bool {
+    destination.rsplit_once('.').is_some_and(|(_, extension)| {
+        IMAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+    })
+}
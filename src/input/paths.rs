@@ -0,0 +1,85 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// A single input file resolved from a command-line path or glob pattern.
+pub struct InputFile {
+    /// Path to read the input from
+    pub path: PathBuf,
+    /// Path relative to the pattern base, used to lay out generated outputs
+    pub relative: PathBuf,
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Directory prefix of a glob pattern that contains no wildcards, without
+/// `.` components.
+fn glob_base(pattern: &str) -> PathBuf {
+    let mut base = PathBuf::new();
+    for component in Path::new(pattern).components() {
+        if is_glob(&component.as_os_str().to_string_lossy()) {
+            break;
+        }
+        base.push(component);
+    }
+    without_cur_dir(&base)
+}
+
+fn without_cur_dir(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+fn file_name(path: &Path) -> Result<PathBuf> {
+    path.file_name()
+        .map(PathBuf::from)
+        .with_context(|| format!("Input path has no file name: {}", path.display()))
+}
+
+/// Expand input paths and glob patterns into the list of files to process.
+///
+/// Files given explicitly are laid out by their file name, files matched by a
+/// glob keep their path relative to the non-wildcard prefix of the pattern.
+pub fn expand_inputs(patterns: &[String]) -> Result<Vec<InputFile>> {
+    let mut files = Vec::new();
+
+    for pattern in patterns {
+        if !is_glob(pattern) {
+            let path = PathBuf::from(pattern);
+            let relative = file_name(&path)?;
+            files.push(InputFile { path, relative });
+            continue;
+        }
+
+        let base = glob_base(pattern);
+        let mut matched = false;
+        for entry in
+            glob::glob(pattern).with_context(|| format!("Invalid glob pattern: {pattern}"))?
+        {
+            let path = entry.with_context(|| format!("Failed to expand pattern: {pattern}"))?;
+            if !path.is_file() {
+                continue;
+            }
+            let relative = match without_cur_dir(&path).strip_prefix(&base) {
+                Ok(relative)
+                    if relative
+                        .components()
+                        .all(|component| matches!(component, Component::Normal(_))) =>
+                {
+                    relative.to_path_buf()
+                }
+                _ => file_name(&path)?,
+            };
+            files.push(InputFile { path, relative });
+            matched = true;
+        }
+
+        if !matched {
+            bail!("No files match pattern: {pattern}");
+        }
+    }
+
+    Ok(files)
+}
@@ -1,8 +1,14 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::io::Read;
+use std::path::Path;
 
 pub fn read_input() -> Result<String> {
     let mut input = String::new();
     std::io::stdin().read_to_string(&mut input)?;
     Ok(input)
 }
+
+pub fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))
+}
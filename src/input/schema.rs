@@ -0,0 +1,125 @@
+//! JSON Schema of the AST file layout and the field defaults it declares.
+
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/// JSON Schema of the documents read by `--from ast-json` and written by
+/// `convert-to ast-json`.
+pub const AST_SCHEMA: &str = include_str!("ast.schema.json");
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Omit,
+    Fill,
+}
+
+/// Remove object fields that hold the default value declared by the schema.
+pub fn omit_defaults(value: &mut Value) {
+    apply("", value, Mode::Omit);
+}
+
+/// Insert the default value of every field the schema declares one for and
+/// `value` leaves out; the reverse of [`omit_defaults`].
+pub fn fill_defaults(value: &mut Value) {
+    apply("", value, Mode::Fill);
+}
+
+/// [`fill_defaults`] for a single top-level block.
+pub fn fill_block_defaults(value: &mut Value) {
+    apply("/$defs/Block", value, Mode::Fill);
+}
+
+/// Walk `value` along the schema definition at the JSON `pointer`.
+fn apply(pointer: &str, value: &mut Value, mode: Mode) {
+    let root = schema_root();
+    let schema = root
+        .pointer(pointer)
+        .expect("AST schema has the definition");
+    walk(root, schema, value, mode);
+}
+
+/// [`AST_SCHEMA`] parsed once, as `--from ast-jsonl` walks it for every line.
+fn schema_root() -> &'static Value {
+    static ROOT: OnceLock<Value> = OnceLock::new();
+    ROOT.get_or_init(|| serde_json::from_str(AST_SCHEMA).expect("AST schema is valid JSON"))
+}
+
+fn walk(root: &Value, schema: &Value, value: &mut Value, mode: Mode) {
+    let schema = resolve(root, schema);
+    if let Some(branches) = schema.get("oneOf").and_then(Value::as_array) {
+        let branch = branches
+            .iter()
+            .map(|branch| resolve(root, branch))
+            .find(|branch| is_variant(branch, value));
+        if let Some(branch) = branch {
+            walk(root, branch, value, mode);
+        }
+        return;
+    }
+
+    match value {
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for item in items {
+                    walk(root, item_schema, item, mode);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+                return;
+            };
+            for (name, property) in properties {
+                let default = resolve_default(root, property);
+                let omit = match fields.get_mut(name) {
+                    Some(field) => {
+                        walk(root, property, field, mode);
+                        mode == Mode::Omit && default == Some(&*field)
+                    }
+                    None => {
+                        if let (Mode::Fill, Some(default)) = (mode, default) {
+                            fields.insert(name.clone(), default.clone());
+                        }
+                        false
+                    }
+                };
+                if omit {
+                    fields.remove(name);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Schema that a local `$ref` points to, or `schema` itself.
+fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+        .unwrap_or(schema)
+}
+
+fn resolve_default<'a>(root: &'a Value, schema: &'a Value) -> Option<&'a Value> {
+    schema
+        .get("default")
+        .or_else(|| resolve(root, schema).get("default"))
+}
+
+/// Whether `value` is the enum variant described by `branch`: a unit variant
+/// is a string constant, other variants are objects keyed by the variant name.
+fn is_variant(branch: &Value, value: &Value) -> bool {
+    match value {
+        Value::String(_) => branch.get("const") == Some(value),
+        Value::Object(fields) => {
+            let properties = branch.get("properties").and_then(Value::as_object);
+            !fields.is_empty()
+                && properties.is_some_and(|properties| {
+                    fields.keys().all(|name| properties.contains_key(name))
+                })
+        }
+        _ => false,
+    }
+}
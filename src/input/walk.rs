@@ -0,0 +1,258 @@
+//! Finding files in directory trees, skipping paths listed in ignore files.
+
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Ignore files read in every directory; later files take precedence.
+const IGNORE_FILES: &[&str] = &[".gitignore", ".markdown-toolignore"];
+
+/// Files searched for when no include patterns are given.
+const DEFAULT_INCLUDE: &[&str] = &["*.md", "*.markdown"];
+
+/// `*` and `?` don't match `/`, only `**` crosses directories.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Glob pattern matched against the file name, or against the whole relative
+/// path if the pattern contains `/`.
+struct PathPattern {
+    pattern: Pattern,
+    whole_path: bool,
+}
+
+impl PathPattern {
+    fn new(pattern: &str) -> Result<Self> {
+        Ok(Self {
+            pattern: Pattern::new(pattern)
+                .with_context(|| format!("Invalid glob pattern: {pattern}"))?,
+            whole_path: pattern.contains('/'),
+        })
+    }
+
+    fn matches(&self, relative: &Path) -> bool {
+        if self.whole_path {
+            self.pattern.matches_path_with(relative, MATCH_OPTIONS)
+        } else {
+            relative.file_name().is_some_and(|name| {
+                self.pattern
+                    .matches_with(&name.to_string_lossy(), MATCH_OPTIONS)
+            })
+        }
+    }
+}
+
+/// Include and exclude patterns of a directory search. Paths are matched
+/// relative to the searched directory.
+pub struct FileFilter {
+    include: Vec<PathPattern>,
+    exclude: Vec<PathPattern>,
+}
+
+impl FileFilter {
+    /// Filter selecting files that match an include pattern (by default
+    /// `*.md` and `*.markdown`) and no exclude pattern.
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let patterns = |patterns: &[&str]| {
+            patterns
+                .iter()
+                .map(|pattern| PathPattern::new(pattern))
+                .collect::<Result<Vec<_>>>()
+        };
+        let include: Vec<&str> = if include.is_empty() {
+            DEFAULT_INCLUDE.to_vec()
+        } else {
+            include.iter().map(String::as_str).collect()
+        };
+        let exclude: Vec<&str> = exclude.iter().map(String::as_str).collect();
+        Ok(Self {
+            include: patterns(&include)?,
+            exclude: patterns(&exclude)?,
+        })
+    }
+
+    fn is_excluded(&self, relative: &Path) -> bool {
+        self.exclude.iter().any(|pattern| pattern.matches(relative))
+    }
+
+    fn is_included(&self, relative: &Path) -> bool {
+        self.include.iter().any(|pattern| pattern.matches(relative))
+    }
+}
+
+/// Line of a `.gitignore` style file.
+struct IgnoreRule {
+    /// Absolute path of the directory holding the ignore file
+    base: PathBuf,
+    pattern: Pattern,
+    /// Pattern contains a `/` and is matched against the path below `base`
+    anchored: bool,
+    /// `!pattern`: re-include paths an earlier rule ignored
+    negated: bool,
+    /// `pattern/`: only match directories
+    directory_only: bool,
+}
+
+impl IgnoreRule {
+    fn parse(line: &str, base: &Path) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        // `\#` and `\!` stand for a literal first character
+        let line = line.strip_prefix('\\').unwrap_or(line);
+        let (directory_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+
+        Some(Self {
+            base: base.to_path_buf(),
+            pattern: Pattern::new(line).ok()?,
+            anchored,
+            negated,
+            directory_only,
+        })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.directory_only && !is_dir {
+            return false;
+        }
+        let Ok(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+        if self.anchored {
+            self.pattern.matches_path_with(relative, MATCH_OPTIONS)
+        } else {
+            relative.file_name().is_some_and(|name| {
+                self.pattern
+                    .matches_with(&name.to_string_lossy(), MATCH_OPTIONS)
+            })
+        }
+    }
+}
+
+/// Whether the last rule matching the absolute `path` ignores it.
+fn is_ignored(rules: &[IgnoreRule], path: &Path, is_dir: bool) -> bool {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(path, is_dir))
+        .is_some_and(|rule| !rule.negated)
+}
+
+fn read_ignore_files(dir: &Path, rules: &mut Vec<IgnoreRule>) -> Result<()> {
+    for name in IGNORE_FILES {
+        let path = dir.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read ignore file: {}", path.display()))?;
+        rules.extend(
+            content
+                .lines()
+                .filter_map(|line| IgnoreRule::parse(line, dir)),
+        );
+    }
+    Ok(())
+}
+
+/// Files below `root` selected by the filter, in a stable order. Ignore files
+/// in `root`, its subdirectories and the directories up to the enclosing Git
+/// repository root are honored, and `.git` directories are skipped.
+pub fn find_files(root: &Path, filter: &FileFilter) -> Result<Vec<PathBuf>> {
+    let absolute = fs::canonicalize(root)
+        .with_context(|| format!("Failed to read directory: {}", root.display()))?;
+
+    let mut rules = Vec::new();
+    let repository = absolute
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join(".git").exists());
+    if let Some(repository) = repository {
+        let mut parents: Vec<&Path> = absolute
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(repository))
+            .collect();
+        parents.reverse();
+        for dir in parents {
+            read_ignore_files(dir, &mut rules)?;
+        }
+    }
+
+    let mut files = Vec::new();
+    walk(
+        root,
+        &absolute,
+        Path::new(""),
+        filter,
+        &mut rules,
+        &mut files,
+    )?;
+    Ok(files)
+}
+
+fn walk(
+    dir: &Path,
+    absolute: &Path,
+    relative: &Path,
+    filter: &FileFilter,
+    rules: &mut Vec<IgnoreRule>,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let inherited = rules.len();
+    read_ignore_files(absolute, rules)?;
+
+    let mut entries = fs::read_dir(dir)
+        .and_then(|entries| entries.collect::<std::io::Result<Vec<_>>>())
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name();
+        // Symbolic links to directories are not followed, which rules out cycles
+        let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+        let entry_absolute = absolute.join(&name);
+        let entry_relative = relative.join(&name);
+        if name == ".git"
+            || is_ignored(rules, &entry_absolute, is_dir)
+            || filter.is_excluded(&entry_relative)
+        {
+            continue;
+        }
+
+        let path = if dir == Path::new(".") {
+            PathBuf::from(&name)
+        } else {
+            dir.join(&name)
+        };
+        if is_dir {
+            walk(
+                &path,
+                &entry_absolute,
+                &entry_relative,
+                filter,
+                rules,
+                files,
+            )?;
+        } else if path.is_file() && filter.is_included(&entry_relative) {
+            files.push(path);
+        }
+    }
+
+    rules.truncate(inherited);
+    Ok(())
+}
@@ -0,0 +1,177 @@
+pub mod rules;
+mod source_map;
+
+use crate::diagnostic::Position;
+use markdown_ppp::ast::Document;
+use serde::{Deserialize, Serialize};
+use source_map::SourceMap;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Severity of a lint finding.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.pad(name)
+    }
+}
+
+/// Configured level of a rule: disabled or enabled with a severity.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl RuleLevel {
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            RuleLevel::Off => None,
+            RuleLevel::Info => Some(Severity::Info),
+            RuleLevel::Warning => Some(Severity::Warning),
+            RuleLevel::Error => Some(Severity::Error),
+        }
+    }
+}
+
+impl From<Severity> for RuleLevel {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Info => RuleLevel::Info,
+            Severity::Warning => RuleLevel::Warning,
+            Severity::Error => RuleLevel::Error,
+        }
+    }
+}
+
+/// How a finding is located in the source text, since the AST carries no positions.
+pub enum Anchor {
+    /// The n-th heading of the document, counting from zero
+    Heading(usize),
+    /// The n-th fenced code block of the document, counting from zero
+    CodeBlock(usize),
+    /// Inline link with the given destination, located at its opening bracket;
+    /// findings of one rule are searched for in document order
+    Link(String),
+    /// Text that appears verbatim in the source; findings of one rule are
+    /// searched for in document order
+    Text(String),
+}
+
+/// Problem reported by a rule, before the rule id and severity are attached.
+pub struct Finding {
+    pub message: String,
+    /// Where the problem is located in the source text
+    pub anchor: Anchor,
+}
+
+impl Finding {
+    pub fn new(message: impl Into<String>, anchor: Anchor) -> Self {
+        Self {
+            message: message.into(),
+            anchor,
+        }
+    }
+}
+
+/// Finding of an enabled rule, ready to be reported.
+#[derive(Serialize)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(flatten)]
+    pub position: Option<Position>,
+}
+
+/// A single check over a parsed document.
+pub trait Rule {
+    /// Stable identifier used to enable, disable or configure the rule
+    fn id(&self) -> &'static str;
+
+    /// One-line description shown by `lint --list-rules`
+    fn description(&self) -> &'static str;
+
+    /// Severity used when the configuration does not set one
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, document: &Document) -> Vec<Finding>;
+}
+
+/// Set of rules with their effective severities.
+pub struct Linter {
+    rules: Vec<(Box<dyn Rule>, Severity)>,
+}
+
+impl Linter {
+    /// Build a linter from all known rules and the per-rule levels from the configuration.
+    pub fn new(levels: &BTreeMap<String, RuleLevel>) -> Result<Self, String> {
+        let all_rules = rules::all_rules();
+
+        if let Some(unknown) = levels
+            .keys()
+            .find(|id| !all_rules.iter().any(|rule| rule.id() == id.as_str()))
+        {
+            return Err(format!("Unknown lint rule: {unknown}"));
+        }
+
+        let rules = all_rules
+            .into_iter()
+            .filter_map(|rule| {
+                let severity = match levels.get(rule.id()) {
+                    Some(level) => level.severity()?,
+                    None => rule.default_severity(),
+                };
+                Some((rule, severity))
+            })
+            .collect();
+
+        Ok(Self { rules })
+    }
+
+    /// Run all enabled rules over the document parsed from `source`.
+    ///
+    /// Diagnostics are ordered by their position in the source.
+    pub fn check(&self, document: &Document, source: &str) -> Vec<Diagnostic> {
+        let source_map = SourceMap::new(document, source);
+
+        let mut diagnostics: Vec<_> = self
+            .rules
+            .iter()
+            .flat_map(|(rule, severity)| {
+                let mut cursor = 0;
+                rule.check(document)
+                    .into_iter()
+                    .map(|finding| Diagnostic {
+                        rule: rule.id(),
+                        severity: *severity,
+                        message: finding.message,
+                        position: source_map
+                            .locate(&finding.anchor, &mut cursor)
+                            .map(|offset| Position::from_offset(source, offset)),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        diagnostics.sort_by_key(|diagnostic| diagnostic.position);
+        diagnostics
+    }
+}
@@ -0,0 +1,46 @@
+use crate::lint::{Anchor, Finding, Rule};
+use markdown_ppp::ast::{Document, Link};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+
+const URL_SCHEMES: &[&str] = &["https://", "http://"];
+
+/// URLs in text should be written as links or autolinks.
+pub struct BareUrl;
+
+#[derive(Default)]
+struct Collector {
+    findings: Vec<Finding>,
+}
+
+impl Visitor for Collector {
+    fn visit_text(&mut self, text: &str) {
+        for word in text.split_whitespace() {
+            if URL_SCHEMES.iter().any(|scheme| word.starts_with(scheme)) {
+                self.findings.push(Finding::new(
+                    format!("Bare URL used, wrap it in <> or a link: {word}"),
+                    Anchor::Text(word.to_string()),
+                ));
+            }
+        }
+    }
+
+    fn visit_link(&mut self, _link: &Link) {
+        // Text inside a link is allowed to look like its URL
+    }
+}
+
+impl Rule for BareUrl {
+    fn id(&self) -> &'static str {
+        "bare-url"
+    }
+
+    fn description(&self) -> &'static str {
+        "URLs should not appear as plain text"
+    }
+
+    fn check(&self, document: &Document) -> Vec<Finding> {
+        let mut collector = Collector::default();
+        document.visit_with(&mut collector);
+        collector.findings
+    }
+}
@@ -0,0 +1,45 @@
+use crate::lint::{Anchor, Finding, Rule};
+use markdown_ppp::ast::{CodeBlock, CodeBlockKind, Document};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+
+/// Fenced code blocks should name their language for highlighting.
+pub struct CodeBlockLanguage;
+
+#[derive(Default)]
+struct Collector {
+    index: usize,
+    findings: Vec<Finding>,
+}
+
+impl Visitor for Collector {
+    fn visit_code_block(&mut self, code_block: &CodeBlock) {
+        if let CodeBlockKind::Fenced { info } = &code_block.kind {
+            let anchor = Anchor::CodeBlock(self.index);
+            self.index += 1;
+
+            if info.as_deref().map_or(true, |info| info.trim().is_empty()) {
+                let first_line = code_block.literal.lines().next().unwrap_or_default();
+                self.findings.push(Finding::new(
+                    format!("Fenced code block has no language: \"{first_line}\""),
+                    anchor,
+                ));
+            }
+        }
+    }
+}
+
+impl Rule for CodeBlockLanguage {
+    fn id(&self) -> &'static str {
+        "code-block-language"
+    }
+
+    fn description(&self) -> &'static str {
+        "Fenced code blocks should have a language specified"
+    }
+
+    fn check(&self, document: &Document) -> Vec<Finding> {
+        let mut collector = Collector::default();
+        document.visit_with(&mut collector);
+        collector.findings
+    }
+}
@@ -0,0 +1,47 @@
+use crate::ast::inline_text;
+use crate::lint::{Anchor, Finding, Rule};
+use markdown_ppp::ast::{Document, Heading};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+use std::collections::HashSet;
+
+/// Headings should have unique text so that their anchors are unique.
+pub struct DuplicateHeading;
+
+#[derive(Default)]
+struct Collector {
+    index: usize,
+    seen: HashSet<String>,
+    findings: Vec<Finding>,
+}
+
+impl Visitor for Collector {
+    fn visit_heading(&mut self, heading: &Heading) {
+        let anchor = Anchor::Heading(self.index);
+        self.index += 1;
+
+        let text = inline_text(&heading.content);
+        let key = text.trim().to_lowercase();
+        if !self.seen.insert(key) {
+            self.findings.push(Finding::new(
+                format!("Duplicate heading: \"{text}\""),
+                anchor,
+            ));
+        }
+    }
+}
+
+impl Rule for DuplicateHeading {
+    fn id(&self) -> &'static str {
+        "duplicate-heading"
+    }
+
+    fn description(&self) -> &'static str {
+        "Multiple headings should not have the same text"
+    }
+
+    fn check(&self, document: &Document) -> Vec<Finding> {
+        let mut collector = Collector::default();
+        document.visit_with(&mut collector);
+        collector.findings
+    }
+}
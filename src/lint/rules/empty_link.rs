@@ -0,0 +1,52 @@
+use crate::ast::inline_text;
+use crate::lint::{Anchor, Finding, Rule, Severity};
+use markdown_ppp::ast::{Document, Link};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+
+/// Links need both a destination and visible text.
+pub struct EmptyLink;
+
+#[derive(Default)]
+struct Collector {
+    findings: Vec<Finding>,
+}
+
+impl Visitor for Collector {
+    fn visit_link(&mut self, link: &Link) {
+        let text = inline_text(&link.children);
+        let destination = link.destination.trim();
+        let anchor = Anchor::Link(link.destination.clone());
+        if destination.is_empty() || destination == "#" {
+            self.findings.push(Finding::new(
+                format!("Link has an empty destination: \"{text}\""),
+                anchor,
+            ));
+        } else if text.trim().is_empty() {
+            self.findings.push(Finding::new(
+                format!("Link has no text: {destination}"),
+                anchor,
+            ));
+        }
+        self.walk_link(link);
+    }
+}
+
+impl Rule for EmptyLink {
+    fn id(&self) -> &'static str {
+        "empty-link"
+    }
+
+    fn description(&self) -> &'static str {
+        "Links should have a destination and text"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, document: &Document) -> Vec<Finding> {
+        let mut collector = Collector::default();
+        document.visit_with(&mut collector);
+        collector.findings
+    }
+}
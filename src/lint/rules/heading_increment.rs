@@ -0,0 +1,51 @@
+use crate::ast::{heading_level, inline_text};
+use crate::lint::{Anchor, Finding, Rule};
+use markdown_ppp::ast::{Document, Heading};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+
+/// Heading levels should only increase by one level at a time.
+pub struct HeadingIncrement;
+
+#[derive(Default)]
+struct Collector {
+    index: usize,
+    previous: Option<u8>,
+    findings: Vec<Finding>,
+}
+
+impl Visitor for Collector {
+    fn visit_heading(&mut self, heading: &Heading) {
+        let anchor = Anchor::Heading(self.index);
+        self.index += 1;
+
+        let level = heading_level(heading);
+        if let Some(previous) = self.previous {
+            if level > previous + 1 {
+                self.findings.push(Finding::new(
+                    format!(
+                        "Heading level jumps from h{previous} to h{level}: \"{}\"",
+                        inline_text(&heading.content)
+                    ),
+                    anchor,
+                ));
+            }
+        }
+        self.previous = Some(level);
+    }
+}
+
+impl Rule for HeadingIncrement {
+    fn id(&self) -> &'static str {
+        "heading-increment"
+    }
+
+    fn description(&self) -> &'static str {
+        "Heading levels should only increment by one level at a time"
+    }
+
+    fn check(&self, document: &Document) -> Vec<Finding> {
+        let mut collector = Collector::default();
+        document.visit_with(&mut collector);
+        collector.findings
+    }
+}
@@ -0,0 +1,51 @@
+use crate::ast::inline_text;
+use crate::lint::{Anchor, Finding, Rule};
+use markdown_ppp::ast::{Document, Heading};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!'];
+
+/// Headings should not end with punctuation.
+pub struct HeadingPunctuation;
+
+#[derive(Default)]
+struct Collector {
+    index: usize,
+    findings: Vec<Finding>,
+}
+
+impl Visitor for Collector {
+    fn visit_heading(&mut self, heading: &Heading) {
+        let anchor = Anchor::Heading(self.index);
+        self.index += 1;
+
+        let text = inline_text(&heading.content);
+        if let Some(last) = text.trim_end().chars().last() {
+            if TRAILING_PUNCTUATION.contains(&last) {
+                self.findings.push(Finding::new(
+                    format!(
+                        "Heading ends with punctuation '{last}': \"{}\"",
+                        text.trim()
+                    ),
+                    anchor,
+                ));
+            }
+        }
+    }
+}
+
+impl Rule for HeadingPunctuation {
+    fn id(&self) -> &'static str {
+        "heading-punctuation"
+    }
+
+    fn description(&self) -> &'static str {
+        "Headings should not end with punctuation"
+    }
+
+    fn check(&self, document: &Document) -> Vec<Finding> {
+        let mut collector = Collector::default();
+        document.visit_with(&mut collector);
+        collector.findings
+    }
+}
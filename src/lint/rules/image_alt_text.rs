@@ -0,0 +1,38 @@
+use crate::lint::{Anchor, Finding, Rule};
+use markdown_ppp::ast::{Document, Image};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+
+/// Images should describe their content for screen readers.
+pub struct ImageAltText;
+
+#[derive(Default)]
+struct Collector {
+    findings: Vec<Finding>,
+}
+
+impl Visitor for Collector {
+    fn visit_image(&mut self, image: &Image) {
+        if image.alt.trim().is_empty() {
+            self.findings.push(Finding::new(
+                format!("Image has no alternative text: {}", image.destination),
+                Anchor::Text(format!("]({}", image.destination)),
+            ));
+        }
+    }
+}
+
+impl Rule for ImageAltText {
+    fn id(&self) -> &'static str {
+        "image-alt-text"
+    }
+
+    fn description(&self) -> &'static str {
+        "Images should have alternative text"
+    }
+
+    fn check(&self, document: &Document) -> Vec<Finding> {
+        let mut collector = Collector::default();
+        document.visit_with(&mut collector);
+        collector.findings
+    }
+}
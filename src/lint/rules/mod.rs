@@ -0,0 +1,22 @@
+mod bare_url;
+mod code_block_language;
+mod duplicate_heading;
+mod empty_link;
+mod heading_increment;
+mod heading_punctuation;
+mod image_alt_text;
+
+use super::Rule;
+
+/// Every available rule, in the order they are reported.
+pub fn all_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(heading_increment::HeadingIncrement),
+        Box::new(duplicate_heading::DuplicateHeading),
+        Box::new(heading_punctuation::HeadingPunctuation),
+        Box::new(empty_link::EmptyLink),
+        Box::new(image_alt_text::ImageAltText),
+        Box::new(bare_url::BareUrl),
+        Box::new(code_block_language::CodeBlockLanguage),
+    ]
+}
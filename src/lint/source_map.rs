@@ -0,0 +1,233 @@
+use super::Anchor;
+use crate::input::front_matter;
+use markdown_ppp::ast::{CodeBlock, CodeBlockKind, Document, Heading};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+
+/// Byte offsets of headings and fenced code blocks found by scanning the source lines.
+///
+/// The offsets are only kept when the scan finds as many headings and fenced
+/// code blocks as the parser did, so findings are never pinned to the wrong block.
+pub struct SourceMap<'a> {
+    source: &'a str,
+    /// Start of the Markdown body after the front matter
+    body_start: usize,
+    headings: Option<Vec<usize>>,
+    code_blocks: Option<Vec<usize>>,
+}
+
+/// Number of headings and fenced code blocks in the parsed document.
+#[derive(Default)]
+struct BlockCounter {
+    headings: usize,
+    code_blocks: usize,
+}
+
+impl Visitor for BlockCounter {
+    fn visit_heading(&mut self, heading: &Heading) {
+        self.headings += 1;
+        self.walk_heading(heading);
+    }
+
+    fn visit_code_block(&mut self, code_block: &CodeBlock) {
+        if let CodeBlockKind::Fenced { .. } = code_block.kind {
+            self.code_blocks += 1;
+        }
+    }
+}
+
+/// Length of the block quote markers and indentation in front of the line content.
+fn container_prefix(line: &str) -> usize {
+    let mut prefix = 0;
+    loop {
+        let rest = &line[prefix..];
+        let trimmed = rest.trim_start_matches(' ');
+        match trimmed.strip_prefix('>') {
+            Some(_) if rest.len() - trimmed.len() < 4 => prefix += rest.len() - trimmed.len() + 1,
+            _ => return prefix,
+        }
+    }
+}
+
+/// Width of the list item marker at the start of `line` together with the
+/// spaces that separate it from the item content.
+fn list_marker(line: &str) -> Option<usize> {
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    let marker = match line.as_bytes().get(digits)? {
+        b'-' | b'*' | b'+' if digits == 0 => 1,
+        b'.' | b')' if (1..=9).contains(&digits) => digits + 1,
+        _ => return None,
+    };
+    let rest = &line[marker..];
+    let content = rest.trim_start_matches(' ');
+    if content.trim().is_empty() {
+        return Some((marker + 1).min(line.len()));
+    }
+    match rest.len() - content.len() {
+        0 => None,
+        spaces @ 1..=4 => Some(marker + spaces),
+        _ => Some(marker + 1),
+    }
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    let mut marks = line.chars().filter(|c| !c.is_whitespace());
+    let Some(marker) = marks.next().filter(|c| matches!(c, '-' | '*' | '_')) else {
+        return false;
+    };
+    let count = marks.try_fold(1, |count, c| (c == marker).then_some(count + 1));
+    count.is_some_and(|count| count >= 3)
+}
+
+fn fence(line: &str) -> Option<(char, usize)> {
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = line.chars().take_while(|c| *c == marker).count();
+    (length >= 3).then_some((marker, length))
+}
+
+fn is_atx_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (1..=6).contains(&hashes)
+        && line[hashes..]
+            .chars()
+            .next()
+            .map_or(true, char::is_whitespace)
+}
+
+fn is_setext_underline(line: &str) -> bool {
+    let line = line.trim_end();
+    !line.is_empty() && (line.chars().all(|c| c == '=') || line.chars().all(|c| c == '-'))
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn new(document: &Document, source: &'a str) -> Self {
+        let mut headings = Vec::new();
+        let mut code_blocks = Vec::new();
+        let mut open_fence: Option<(char, usize)> = None;
+        let mut paragraph_start: Option<usize> = None;
+        // Content columns of the open list items, innermost last
+        let mut list_indents: Vec<usize> = Vec::new();
+        let body_start = front_matter::body_offset(source);
+        let mut offset = body_start;
+
+        for line in source[body_start..].split_inclusive('\n') {
+            let line_offset = offset;
+            offset += line.len();
+
+            let prefix = container_prefix(line);
+            let rest = &line[prefix..];
+
+            // A non-blank line indented less than an item's content closes the item
+            if !rest.trim().is_empty() {
+                let leading = rest.len() - rest.trim_start_matches(' ').len();
+                while list_indents.last().is_some_and(|indent| leading < *indent) {
+                    list_indents.pop();
+                    open_fence = None;
+                    paragraph_start = None;
+                }
+            }
+            let mut column = list_indents.last().copied().unwrap_or(0);
+
+            if let Some((marker, length)) = open_fence {
+                let content = rest
+                    .get(column..)
+                    .unwrap_or_default()
+                    .trim_start_matches(' ');
+                if fence(content).is_some_and(|(m, l)| m == marker && l >= length)
+                    && content.trim_start_matches(marker).trim().is_empty()
+                {
+                    open_fence = None;
+                }
+                continue;
+            }
+
+            // Step over the markers of list items that start on this line
+            loop {
+                let inner = rest.get(column..).unwrap_or_default();
+                let content = inner.trim_start_matches(' ');
+                let indent = inner.len() - content.len();
+                if indent >= 4
+                    || is_thematic_break(content)
+                    || paragraph_start.is_some() && is_setext_underline(content)
+                {
+                    break;
+                }
+                let Some(width) = list_marker(content) else {
+                    break;
+                };
+                column += indent + width;
+                list_indents.push(column);
+                paragraph_start = None;
+            }
+
+            let inner = rest.get(column..).unwrap_or_default();
+            let content = inner.trim_start_matches(' ');
+            let indent = inner.len() - content.len();
+            let start = line_offset + prefix + column + indent;
+
+            if content.trim().is_empty() {
+                paragraph_start = None;
+            } else if indent < 4 && fence(content).is_some() {
+                open_fence = fence(content);
+                code_blocks.push(start);
+                paragraph_start = None;
+            } else if indent < 4 && is_atx_heading(content) {
+                headings.push(start);
+                paragraph_start = None;
+            } else if let Some(heading_start) =
+                paragraph_start.filter(|_| indent < 4 && is_setext_underline(content))
+            {
+                headings.push(heading_start);
+                paragraph_start = None;
+            } else if indent < 4 && is_thematic_break(content) {
+                paragraph_start = None;
+            } else {
+                paragraph_start.get_or_insert(start);
+            }
+        }
+
+        let mut counter = BlockCounter::default();
+        document.visit_with(&mut counter);
+
+        Self {
+            source,
+            body_start,
+            headings: (headings.len() == counter.headings).then_some(headings),
+            code_blocks: (code_blocks.len() == counter.code_blocks).then_some(code_blocks),
+        }
+    }
+
+    /// Byte offset of the anchor; text and link anchors are searched from `cursor`,
+    /// which is moved past the match.
+    pub fn locate(&self, anchor: &Anchor, cursor: &mut usize) -> Option<usize> {
+        match anchor {
+            Anchor::Heading(index) => self.headings.as_ref()?.get(*index).copied(),
+            Anchor::CodeBlock(index) => self.code_blocks.as_ref()?.get(*index).copied(),
+            Anchor::Link(destination) => {
+                let from = (*cursor).max(self.body_start);
+                let target = format!("]({destination}");
+                let close = from + self.source.get(from..)?.find(&target)?;
+                *cursor = close + target.len();
+                // Walk back to the bracket that opens the link text
+                let mut depth = 0;
+                self.source[..close].rfind(|c| match c {
+                    ']' => {
+                        depth += 1;
+                        false
+                    }
+                    '[' if depth == 0 => true,
+                    '[' => {
+                        depth -= 1;
+                        false
+                    }
+                    _ => false,
+                })
+            }
+            Anchor::Text(text) => {
+                let from = (*cursor).max(self.body_start);
+                let offset = from + self.source.get(from..)?.find(text.as_str())?;
+                *cursor = offset + text.len();
+                Some(offset)
+            }
+        }
+    }
+}
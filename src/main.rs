@@ -1,9 +1,23 @@
+mod ast;
+mod code_format;
 mod commands;
 mod config;
+mod diagnostic;
+mod diff;
 mod input;
+mod lint;
+mod output;
+mod printer;
+mod query;
+mod template;
+mod toc;
+mod watch;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use config::ProjectConfig;
+use diagnostic::report_error;
+use std::path::PathBuf;
 
 /// Subcommand for the application
 #[derive(Subcommand)]
@@ -13,12 +27,27 @@ enum CommandLine {
     ConvertTo(crate::commands::ConvertTo),
     /// Format markdown files
     Format(crate::commands::Format),
+    /// Check markdown files for common problems
+    Lint(crate::commands::Lint),
+    /// Generate or update a table of contents
+    Toc(crate::commands::Toc),
+    /// Print the parts of documents matched by a selector
+    Query(crate::commands::Query),
+    /// Write code blocks to files ("tangle")
+    #[clap(name = "extract-code")]
+    ExtractCode(crate::commands::ExtractCode),
+    /// Inspect project configuration
+    Config(crate::commands::Config),
 }
 
 /// markdown-tool - a tool for converting markdown files to other formats
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Application {
+    /// Configuration file (default: nearest .markdown-tool.toml)
+    #[clap(long, global = true)]
+    config: Option<PathBuf>,
+
     /// Subcommand
     #[clap(subcommand)]
     command: CommandLine,
@@ -26,15 +55,22 @@ struct Application {
 
 impl Application {
     fn run_command(&self) -> Result<()> {
+        let project = ProjectConfig::load(self.config.as_deref())?;
+
         match &self.command {
-            CommandLine::ConvertTo(convert_to) => convert_to.run(),
-            CommandLine::Format(format) => format.run(),
+            CommandLine::ConvertTo(convert_to) => convert_to.run(&project),
+            CommandLine::Format(format) => format.run(&project),
+            CommandLine::Lint(lint) => lint.run(&project),
+            CommandLine::Toc(toc) => toc.run(&project),
+            CommandLine::Query(query) => query.run(&project),
+            CommandLine::ExtractCode(extract_code) => extract_code.run(),
+            CommandLine::Config(config) => config.run(&project),
         }
     }
 
     pub fn run(&self) {
         if let Err(err) = self.run_command() {
-            eprintln!("Failed with error: {err:#}");
+            report_error(&err);
             std::process::exit(1);
         }
     }
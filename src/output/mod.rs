@@ -0,0 +1,93 @@
+pub mod zip;
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Destination of a rendered document: standard output or a file.
+pub enum OutputSink {
+    Stdout,
+    File(PathBuf),
+}
+
+impl OutputSink {
+    pub fn new(path: Option<&Path>) -> Self {
+        match path {
+            Some(path) => OutputSink::File(path.to_path_buf()),
+            None => OutputSink::Stdout,
+        }
+    }
+
+    /// Write the content terminated by a single newline.
+    ///
+    /// Returns `false` if the target file already had the same content and was left untouched.
+    pub fn write(&self, content: &str) -> Result<bool> {
+        let mut content = content.to_string();
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        self.write_bytes(content.as_bytes())
+    }
+
+    /// Write the content as is, e.g. a binary document.
+    ///
+    /// Returns `false` if the target file already had the same content and was left untouched.
+    pub fn write_bytes(&self, content: &[u8]) -> Result<bool> {
+        match self {
+            OutputSink::Stdout => {
+                let mut stdout = std::io::stdout().lock();
+                stdout
+                    .write_all(content)
+                    .and_then(|()| stdout.flush())
+                    .context("Failed to write to stdout")?;
+                Ok(true)
+            }
+            OutputSink::File(path) => {
+                if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    fs::create_dir_all(parent).with_context(|| {
+                        format!("Failed to create directory: {}", parent.display())
+                    })?;
+                }
+                write_if_changed(path, content)
+                    .with_context(|| format!("Failed to write file: {}", path.display()))
+            }
+        }
+    }
+}
+
+/// Atomically replace the file unless it already has exactly this content.
+///
+/// Returns `true` if the file was written.
+pub fn write_if_changed(file_path: &Path, content: impl AsRef<[u8]>) -> Result<bool> {
+    let content = content.as_ref();
+    if fs::read(file_path).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+    write_file_atomically(file_path, content)?;
+    Ok(true)
+}
+
+/// Write to a temporary file next to the target and rename it into place.
+pub fn write_file_atomically(file_path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+    let temp_file_path = file_path.with_extension(format!(
+        "{}.tmp",
+        file_path.extension().unwrap_or_default().to_string_lossy()
+    ));
+
+    fs::write(&temp_file_path, content).with_context(|| {
+        format!(
+            "Failed to write temporary file: {}",
+            temp_file_path.display()
+        )
+    })?;
+
+    fs::rename(&temp_file_path, file_path).with_context(|| {
+        format!(
+            "Failed to rename temporary file to: {}",
+            file_path.display()
+        )
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+//! Minimal ZIP archives for package formats like DOCX and EPUB.
+//!
+//! Entries are deflated unless that would not make them smaller. Sizes and
+//! offsets must fit the 32-bit fields of the format; ZIP64 is not supported.
+
+use anyhow::{anyhow, bail, Context, Result};
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+/// Version 2.0, the minimum for entries in directories and deflated entries
+const VERSION: u16 = 20;
+/// General purpose flag for UTF-8 entry names
+const UTF8_NAMES: u16 = 1 << 11;
+/// 1980-01-01, the earliest date that ZIP can store
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+/// Compression level of deflated entries, the default of zlib
+const LEVEL: u8 = 6;
+
+struct CentralEntry {
+    name: String,
+    name_length: u16,
+    method: u16,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Archive built in memory.
+#[derive(Default)]
+pub struct ZipWriter {
+    data: Vec<u8>,
+    entries: Vec<CentralEntry>,
+}
+
+impl ZipWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an entry, deflated if that makes it smaller; entries keep their order.
+    pub fn add(&mut self, name: &str, content: &[u8]) -> Result<()> {
+        let compressed = compress_to_vec(content, LEVEL);
+        if compressed.len() < content.len() {
+            self.add_entry(name, content, DEFLATED, &compressed)
+        } else {
+            self.add_entry(name, content, STORED, content)
+        }
+    }
+
+    /// Append an uncompressed entry, as EPUB requires for the `mimetype` entry.
+    pub fn add_stored(&mut self, name: &str, content: &[u8]) -> Result<()> {
+        self.add_entry(name, content, STORED, content)
+    }
+
+    fn add_entry(&mut self, name: &str, content: &[u8], method: u16, data: &[u8]) -> Result<()> {
+        let too_large = || format!("ZIP entry is too large: {name}");
+        let entry = CentralEntry {
+            name: name.to_string(),
+            name_length: u16::try_from(name.len())
+                .with_context(|| format!("ZIP entry name is too long: {name}"))?,
+            method,
+            crc: crc32(content),
+            compressed_size: u32::try_from(data.len()).with_context(too_large)?,
+            size: u32::try_from(content.len()).with_context(too_large)?,
+            offset: u32::try_from(self.data.len()).context("ZIP archive is too large")?,
+        };
+
+        push_u32(&mut self.data, LOCAL_HEADER);
+        push_u16(&mut self.data, VERSION);
+        push_u16(&mut self.data, UTF8_NAMES);
+        push_u16(&mut self.data, method);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, DOS_DATE);
+        push_u32(&mut self.data, entry.crc);
+        push_u32(&mut self.data, entry.compressed_size);
+        push_u32(&mut self.data, entry.size);
+        push_u16(&mut self.data, entry.name_length);
+        push_u16(&mut self.data, 0);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(data);
+
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Bytes of the archive with its central directory.
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        let too_large = "ZIP archive is too large";
+        let directory_offset = u32::try_from(self.data.len()).context(too_large)?;
+        let entry_count = u16::try_from(self.entries.len()).context("Too many ZIP entries")?;
+        for entry in &self.entries {
+            push_u32(&mut self.data, CENTRAL_HEADER);
+            push_u16(&mut self.data, VERSION);
+            push_u16(&mut self.data, VERSION);
+            push_u16(&mut self.data, UTF8_NAMES);
+            push_u16(&mut self.data, entry.method);
+            push_u16(&mut self.data, 0);
+            push_u16(&mut self.data, DOS_DATE);
+            push_u32(&mut self.data, entry.crc);
+            push_u32(&mut self.data, entry.compressed_size);
+            push_u32(&mut self.data, entry.size);
+            push_u16(&mut self.data, entry.name_length);
+            // Extra field, comment, disk number, internal and external attributes
+            push_u16(&mut self.data, 0);
+            push_u16(&mut self.data, 0);
+            push_u16(&mut self.data, 0);
+            push_u16(&mut self.data, 0);
+            push_u32(&mut self.data, 0);
+            push_u32(&mut self.data, entry.offset);
+            self.data.extend_from_slice(entry.name.as_bytes());
+        }
+        let directory_size = u32::try_from(self.data.len()).context(too_large)? - directory_offset;
+
+        push_u32(&mut self.data, END_OF_CENTRAL_DIRECTORY);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, entry_count);
+        push_u16(&mut self.data, entry_count);
+        push_u32(&mut self.data, directory_size);
+        push_u32(&mut self.data, directory_offset);
+        push_u16(&mut self.data, 0);
+        Ok(self.data)
+    }
+}
+
+/// Content of the entry `name` of an archive, or `None` if there is no such entry.
+pub fn read_entry(archive: &[u8], name: &str) -> Result<Option<Vec<u8>>> {
+    // The end record is followed by a comment of at most 64 KiB
+    let end = (0..archive.len().saturating_sub(21))
+        .rev()
+        .take(0x10000 + 22)
+        .find(|&offset| read_u32(archive, offset) == Some(END_OF_CENTRAL_DIRECTORY))
+        .context("Not a ZIP archive")?;
+    let count = read_u16(archive, end + 10).context("Truncated ZIP archive")?;
+    let mut offset = read_u32(archive, end + 16).context("Truncated ZIP archive")? as usize;
+
+    for _ in 0..count {
+        if read_u32(archive, offset) != Some(CENTRAL_HEADER) {
+            bail!("Corrupt ZIP central directory");
+        }
+        let field = |at: usize| read_u16(archive, offset + at).context("Truncated ZIP archive");
+        let method = field(10)?;
+        let crc = read_u32(archive, offset + 16).context("Truncated ZIP archive")?;
+        let compressed_size = read_u32(archive, offset + 20).context("Truncated ZIP archive")?;
+        let size = read_u32(archive, offset + 24).context("Truncated ZIP archive")?;
+        let name_length = field(28)? as usize;
+        let extra_length = field(30)? as usize;
+        let comment_length = field(32)? as usize;
+        let local_offset = read_u32(archive, offset + 42).context("Truncated ZIP archive")?;
+        let entry_name = archive
+            .get(offset + 46..offset + 46 + name_length)
+            .context("Truncated ZIP archive")?;
+        offset += 46 + name_length + extra_length + comment_length;
+
+        if entry_name != name.as_bytes() {
+            continue;
+        }
+
+        let local = local_offset as usize;
+        if read_u32(archive, local) != Some(LOCAL_HEADER) {
+            bail!("Corrupt ZIP entry: {name}");
+        }
+        let local_name_length = read_u16(archive, local + 26).context("Truncated ZIP archive")?;
+        let local_extra_length = read_u16(archive, local + 28).context("Truncated ZIP archive")?;
+        let start = local + 30 + local_name_length as usize + local_extra_length as usize;
+        let data = archive
+            .get(start..start + compressed_size as usize)
+            .context("Truncated ZIP archive")?;
+
+        let content = match method {
+            STORED => data.to_vec(),
+            DEFLATED => decompress_to_vec_with_limit(data, size as usize)
+                .map_err(|err| anyhow!("Failed to decompress ZIP entry {name}: {err}"))?,
+            _ => bail!("Unsupported compression method {method} of ZIP entry: {name}"),
+        };
+        if crc32(&content) != crc {
+            bail!("Corrupt ZIP entry: {name}");
+        }
+        return Ok(Some(content));
+    }
+    Ok(None)
+}
+
+/// CRC-32 checksum (IEEE) of ZIP entries.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+fn push_u16(data: &mut Vec<u8>, value: u16) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(data: &mut Vec<u8>, value: u32) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
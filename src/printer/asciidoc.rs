@@ -0,0 +1,511 @@
+use super::{wrap_markup, BREAK};
+use crate::ast::{alert_title, heading_level, Definitions};
+use markdown_ppp::ast::{
+    Alignment, Block, CodeBlock, CodeBlockKind, Document, Heading, Inline, List, ListKind, Table,
+    TaskState,
+};
+use std::collections::HashSet;
+
+/// Options of the AsciiDoc printer.
+pub struct Config {
+    /// Maximum line width of paragraphs
+    pub width: usize,
+}
+
+struct Printer<'a> {
+    config: &'a Config,
+    definitions: Definitions<'a>,
+    /// Footnotes already printed, later references only repeat their id
+    printed_footnotes: HashSet<String>,
+    /// Level of the last section title; levels may not be skipped
+    section_level: usize,
+    /// Number of enclosing lists, which selects the list marker
+    list_depth: usize,
+    /// Number of enclosing delimited blocks; nested delimiters get longer
+    block_depth: usize,
+    /// Rendering the text of a macro, where `]` must be escaped
+    in_macro: bool,
+}
+
+/// Render the document as AsciiDoc.
+pub fn render_asciidoc(document: &Document, config: &Config) -> String {
+    let mut printer = Printer {
+        config,
+        definitions: Definitions::collect(document),
+        printed_footnotes: HashSet::new(),
+        section_level: 0,
+        list_depth: 0,
+        block_depth: 0,
+        in_macro: false,
+    };
+    printer.blocks(&document.blocks).join("\n")
+}
+
+/// Escape text so that AsciiDoc shows it literally.
+pub fn escape_text(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let in_word = |index: Option<usize>| {
+        index
+            .and_then(|index| chars.get(index))
+            .is_some_and(|c| c.is_alphanumeric())
+    };
+
+    let mut escaped = String::with_capacity(text.len());
+    for (index, &c) in chars.iter().enumerate() {
+        let before = index.checked_sub(1).map(|index| chars[index]);
+        let after = chars.get(index + 1).copied();
+        match c {
+            '*' => escaped.push_str("{asterisk}"),
+            '`' => escaped.push_str("{backtick}"),
+            '^' => escaped.push_str("{caret}"),
+            '~' => escaped.push_str("{tilde}"),
+            // Constrained marks only take effect at word boundaries
+            '_' | '#' if !(in_word(index.checked_sub(1)) && in_word(Some(index + 1))) => {
+                escaped.push_str(&format!("pass:[{c}]"))
+            }
+            '+' if before == Some('+') || after == Some('+') => escaped.push_str("{plus}"),
+            '{' if is_attribute_reference(&chars[index + 1..]) => escaped.push_str("\\{"),
+            // Cross references `<<id>>` and anchors `[[id]]`
+            '<' | '[' if after == Some(c) && before != Some(c) => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Whether the characters after a `{` form an attribute reference `{name}`.
+fn is_attribute_reference(chars: &[char]) -> bool {
+    let name_length = chars
+        .iter()
+        .take_while(|c| c.is_alphanumeric() || **c == '_' || **c == '-')
+        .count();
+    name_length > 0 && chars.get(name_length) == Some(&'}')
+}
+
+/// Guard a line that AsciiDoc would read as block markup, e.g. a list item,
+/// section title, block delimiter or admonition label.
+fn escape_line_start(line: String) -> String {
+    let first_word = line.split(' ').next().unwrap_or_default();
+    let is_enumerator = first_word
+        .strip_suffix(['.', ')'])
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+    let is_markup = line.starts_with([
+        '=', '*', '.', '-', '+', ':', '|', '/', '[', '<', '>', '\'', '_',
+    ]) || is_enumerator
+        || line.ends_with("::")
+        || line.contains(":: ")
+        || line.ends_with(";;")
+        || line.contains(";; ")
+        || matches!(
+            first_word,
+            "NOTE:" | "TIP:" | "IMPORTANT:" | "WARNING:" | "CAUTION:"
+        );
+
+    if is_markup {
+        format!("{{empty}}{line}")
+    } else {
+        line
+    }
+}
+
+/// Id usable in `footnote:id[]`.
+fn footnote_id(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Attribute text of a macro; commas would separate further attributes.
+fn attribute_text(text: &str) -> String {
+    if text.contains([',', '"', ']', '=']) {
+        format!("\"{}\"", text.replace('"', "\\\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Whether AsciiDoc recognizes the URL without the `link:` prefix.
+fn has_scheme(url: &str) -> bool {
+    ["http://", "https://", "ftp://", "irc://", "mailto:"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme))
+}
+
+fn link_macro(destination: &str, text: &str) -> String {
+    if destination.contains([' ', '[', ']']) {
+        return format!("link:++{destination}++[{text}]");
+    }
+    if has_scheme(destination) {
+        format!("{destination}[{text}]")
+    } else {
+        format!("link:{destination}[{text}]")
+    }
+}
+
+/// Whether a mark at the end of `markup` and before `next` may be constrained,
+/// i.e. single, because it does not touch a word.
+fn is_constrained(markup: &str, next: Option<&Inline>) -> bool {
+    let before = markup.chars().last().map_or(true, |c| {
+        !(c.is_alphanumeric() || matches!(c, '_' | ';' | ':' | '}'))
+    });
+    let after = match next {
+        None | Some(Inline::LineBreak) => true,
+        Some(Inline::Text(text)) => text
+            .chars()
+            .next()
+            .map_or(true, |c| !(c.is_alphanumeric() || c == '_')),
+        Some(_) => false,
+    };
+    before && after
+}
+
+/// Shortest delimiter of at least four `c` that matches no line of the content.
+fn delimiter(c: char, content: &str) -> String {
+    let mut delimiter = c.to_string().repeat(4);
+    while content.lines().any(|line| line.trim_end() == delimiter) {
+        delimiter.push(c);
+    }
+    delimiter
+}
+
+fn code_block(code: &CodeBlock) -> Vec<String> {
+    let language = match &code.kind {
+        CodeBlockKind::Fenced { info: Some(info) } => {
+            info.split_whitespace().next().unwrap_or_default()
+        }
+        _ => "",
+    };
+    let literal = code.literal.trim_end_matches('\n');
+    let delimiter = delimiter('-', literal);
+
+    let mut lines = Vec::new();
+    if !language.is_empty() {
+        lines.push(format!("[source,{language}]"));
+    }
+    lines.push(delimiter.clone());
+    lines.extend(literal.lines().map(str::to_string));
+    lines.push(delimiter);
+    lines
+}
+
+impl<'a> Printer<'a> {
+    /// Lines of a sequence of blocks separated by blank lines.
+    fn blocks(&mut self, blocks: &'a [Block]) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut previous: Option<&Block> = None;
+        for block in blocks {
+            let block_lines = self.block(block);
+            if block_lines.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+                // Adjacent lists would be merged into one
+                if matches!((previous, block), (Some(Block::List(_)), Block::List(_))) {
+                    lines.push("//-".to_string());
+                    lines.push(String::new());
+                }
+            }
+            lines.extend(block_lines);
+            previous = Some(block);
+        }
+        lines
+    }
+
+    fn block(&mut self, block: &'a Block) -> Vec<String> {
+        match block {
+            Block::Paragraph(inlines) => self.paragraph(inlines),
+            Block::Heading(heading) => self.heading(heading),
+            Block::ThematicBreak => vec!["'''".to_string()],
+            Block::BlockQuote(blocks) => self.delimited(None, '_', blocks),
+            Block::List(list) => self.list(list),
+            Block::CodeBlock(code) => code_block(code),
+            Block::Table(table) => self.table(table),
+            Block::GitHubAlert(alert) => {
+                let style = format!("[{}]", alert_title(&alert.alert_type).to_uppercase());
+                self.delimited(Some(style), '=', &alert.blocks)
+            }
+            Block::HtmlBlock(html) => {
+                let html = html.trim_end_matches('\n');
+                let delimiter = delimiter('+', html);
+                let mut lines = vec![delimiter.clone()];
+                lines.extend(html.lines().map(str::to_string));
+                lines.push(delimiter);
+                lines
+            }
+            // Link references are resolved, footnotes are printed where they
+            // are referenced
+            Block::Definition(_) | Block::FootnoteDefinition(_) | Block::Empty => Vec::new(),
+        }
+    }
+
+    fn paragraph(&mut self, inlines: &'a [Inline]) -> Vec<String> {
+        // A paragraph of just an image is a block image, except as list item text
+        if let [Inline::Image(image)] = inlines {
+            if self.list_depth == 0 {
+                return vec![format!(
+                    "image::{}[{}]",
+                    image.destination,
+                    attribute_text(&image.alt)
+                )];
+            }
+        }
+        wrap_markup(&self.inlines(inlines), self.config.width)
+            .into_iter()
+            .map(escape_line_start)
+            .collect()
+    }
+
+    fn heading(&mut self, heading: &'a Heading) -> Vec<String> {
+        let level = heading_level(heading) as usize;
+        let content = self
+            .inlines(&heading.content)
+            .replace(" +\n", " ")
+            .replace(['\n', BREAK], " ");
+        let content = content.trim();
+
+        // Sections can't be nested in lists or delimited blocks
+        if self.list_depth > 0 || self.block_depth > 0 {
+            let marks = "=".repeat(level.min(5) + 1);
+            return vec!["[discrete]".to_string(), format!("{marks} {content}")];
+        }
+        let level = level.min(self.section_level + 1).min(5);
+        self.section_level = level;
+        vec![format!("{} {content}", "=".repeat(level + 1))]
+    }
+
+    /// Delimited block with an optional style line, e.g. a quote or admonition.
+    fn delimited(&mut self, style: Option<String>, c: char, blocks: &'a [Block]) -> Vec<String> {
+        let delimiter = c.to_string().repeat(4 + self.block_depth);
+        let list_depth = std::mem::take(&mut self.list_depth);
+        self.block_depth += 1;
+        let content = self.blocks(blocks);
+        self.block_depth -= 1;
+        self.list_depth = list_depth;
+
+        let mut lines: Vec<String> = style.into_iter().collect();
+        lines.push(delimiter.clone());
+        lines.extend(content);
+        lines.push(delimiter);
+        lines
+    }
+
+    fn list(&mut self, list: &'a List) -> Vec<String> {
+        self.list_depth += 1;
+        let marker = match &list.kind {
+            ListKind::Bullet(_) => "*".repeat(self.list_depth),
+            ListKind::Ordered(_) => ".".repeat(self.list_depth),
+        };
+
+        let mut lines = Vec::new();
+        if let ListKind::Ordered(options) = &list.kind {
+            if options.start != 1 {
+                lines.push(format!("[start={}]", options.start));
+            }
+        }
+        for item in &list.items {
+            let mut item_marker = format!("{marker} ");
+            match item.task {
+                Some(TaskState::Complete) => item_marker.push_str("[x] "),
+                Some(TaskState::Incomplete) => item_marker.push_str("[ ] "),
+                None => {}
+            }
+
+            let mut item_lines: Vec<String> = Vec::new();
+            for block in &item.blocks {
+                let block_lines = self.block(block);
+                if block_lines.is_empty() {
+                    continue;
+                }
+                if item_lines.is_empty() && matches!(block, Block::Paragraph(_)) {
+                    let mut block_lines = block_lines.into_iter();
+                    let first = block_lines.next().unwrap_or_default();
+                    item_lines.push(format!("{item_marker}{first}"));
+                    item_lines.extend(block_lines);
+                    continue;
+                }
+                // Items must start with text
+                if item_lines.is_empty() {
+                    item_lines.push(format!("{item_marker}{{empty}}"));
+                }
+                // Nested lists attach to the item by themselves, other blocks
+                // need a list continuation
+                if !matches!(block, Block::List(_)) {
+                    item_lines.push("+".to_string());
+                }
+                item_lines.extend(block_lines);
+            }
+            if item_lines.is_empty() {
+                item_lines.push(format!("{item_marker}{{empty}}"));
+            }
+            lines.extend(item_lines);
+        }
+
+        self.list_depth -= 1;
+        lines
+    }
+
+    fn table(&mut self, table: &'a Table) -> Vec<String> {
+        let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let specifiers: Vec<&str> = (0..columns)
+            .map(|column| match table.alignments.get(column) {
+                Some(Alignment::Left) => "<",
+                Some(Alignment::Center) => "^",
+                Some(Alignment::Right) => ">",
+                Some(Alignment::None) | None => "1",
+            })
+            .collect();
+
+        let mut lines = vec![
+            format!("[cols=\"{}\",options=\"header\"]", specifiers.join(",")),
+            "|===".to_string(),
+        ];
+        for (index, row) in table.rows.iter().enumerate() {
+            let cells: Vec<String> = (0..columns)
+                .map(|column| {
+                    let content = row
+                        .get(column)
+                        .map(|cell| self.inlines(cell))
+                        .unwrap_or_default()
+                        .replace(" +\n", " ")
+                        .replace(['\n', BREAK], " ");
+                    format!("|{}", content.trim().replace('|', "\\|"))
+                })
+                .collect();
+            lines.push(cells.join(" "));
+            // The blank line ends the header row
+            if index == 0 {
+                lines.push(String::new());
+            }
+        }
+        lines.push("|===".to_string());
+        lines
+    }
+
+    /// Markup of inline content; spaces that may be broken are [`BREAK`] and
+    /// hard line breaks are ` +\n`.
+    fn inlines(&mut self, inlines: &'a [Inline]) -> String {
+        let mut markup = String::new();
+        for (index, inline) in inlines.iter().enumerate() {
+            let constrained = is_constrained(&markup, inlines.get(index + 1));
+            let quote = |mark: &str, content: String| {
+                if constrained {
+                    format!("{mark}{content}{mark}")
+                } else {
+                    format!("{mark}{mark}{content}{mark}{mark}")
+                }
+            };
+
+            match inline {
+                Inline::Text(text) => {
+                    let mut text = escape_text(text);
+                    if self.in_macro {
+                        text = text.replace(']', "\\]");
+                    }
+                    markup.extend(
+                        text.chars()
+                            .map(|c| if c.is_whitespace() { BREAK } else { c }),
+                    );
+                }
+                Inline::LineBreak => markup.push_str(" +\n"),
+                Inline::Code(code) => {
+                    let code = code.replace('\n', " ");
+                    let literal = if !code
+                        .contains(['*', '_', '`', '#', '^', '~', '+', '{', '[', '<', '\\'])
+                    {
+                        code
+                    } else if !code.contains('+') {
+                        format!("+{code}+")
+                    } else {
+                        format!("pass:c[{}]", code.replace(']', "\\]"))
+                    };
+                    markup.push_str(&quote("`", literal));
+                }
+                Inline::Emphasis(children) => {
+                    let content = self.inlines(children);
+                    markup.push_str(&quote("_", content));
+                }
+                Inline::Strong(children) => {
+                    let content = self.inlines(children);
+                    markup.push_str(&quote("*", content));
+                }
+                Inline::Strikethrough(children) => {
+                    let content = self.inlines(children);
+                    markup.push_str(&format!("[.line-through]{}", quote("#", content)));
+                }
+                Inline::Link(link) => {
+                    let text = self.macro_text(&link.children);
+                    markup.push_str(&link_macro(&link.destination, &text));
+                }
+                Inline::LinkReference(reference) => {
+                    let text = self.macro_text(&reference.text);
+                    match self.definitions.link_destination(&reference.label) {
+                        Some(destination) => markup.push_str(&link_macro(destination, &text)),
+                        None => markup.push_str(&self.inlines(&reference.text)),
+                    }
+                }
+                Inline::Image(image) => markup.push_str(&format!(
+                    "image:{}[{}]",
+                    image.destination,
+                    attribute_text(&image.alt)
+                )),
+                Inline::Autolink(url) => {
+                    if has_scheme(url) || url.contains('@') {
+                        markup.push_str(url);
+                    } else {
+                        markup.push_str(&link_macro(url, ""));
+                    }
+                }
+                Inline::FootnoteReference(label) => markup.push_str(&self.footnote(label)),
+                Inline::Html(html) => markup.push_str(&format!("+++{html}+++")),
+                Inline::Empty => {}
+            }
+        }
+        markup
+    }
+
+    /// Markup of the text of a link macro.
+    fn macro_text(&mut self, inlines: &'a [Inline]) -> String {
+        let in_macro = std::mem::replace(&mut self.in_macro, true);
+        let text = self.inlines(inlines);
+        self.in_macro = in_macro;
+        text
+    }
+
+    /// Footnote with its text at the first reference, a reference to its id
+    /// afterwards.
+    fn footnote(&mut self, label: &str) -> String {
+        let id = footnote_id(label);
+        if self.printed_footnotes.contains(label) {
+            return format!("footnote:{id}[]");
+        }
+        let Some(definition) = self.definitions.footnotes.get(label) else {
+            return String::new();
+        };
+        self.printed_footnotes.insert(label.to_string());
+
+        // Footnotes are inline, so only the text of their paragraphs is kept
+        let paragraphs: Vec<String> = definition
+            .blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Paragraph(inlines) => Some(self.macro_text(inlines)),
+                _ => None,
+            })
+            .collect();
+        format!(
+            "footnote:{id}[{}]",
+            paragraphs.join(&BREAK.to_string()).trim()
+        )
+    }
+}
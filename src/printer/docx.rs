@@ -0,0 +1,1043 @@
+use crate::ast::{alert_title, heading_level, Definitions};
+use crate::output::zip::ZipWriter;
+use crate::toc::collect_headings;
+use anyhow::Result;
+use markdown_ppp::ast::{
+    Alignment, Block, CodeBlock, Document, GitHubAlert, Heading, Image, Inline, List, ListKind,
+    Table, TaskState,
+};
+use markdown_ppp::ast_transform::Visitor;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Options of the DOCX printer.
+pub struct Config {
+    /// Document title stored in the package properties
+    pub title: Option<String>,
+    /// Document author stored in the package properties
+    pub author: Option<String>,
+    /// `word/styles.xml` of a reference document used instead of the default styles
+    pub styles: Option<String>,
+    /// Directory that relative image paths are resolved against
+    pub base_dir: PathBuf,
+}
+
+const NAMESPACES: &str = concat!(
+    r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" "#,
+    r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" "#,
+    r#"xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" "#,
+    r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" "#,
+    r#"xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture""#,
+);
+const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+const RELATIONSHIP_TYPES: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// Letter page with one inch margins, in twentieths of a point
+const SECTION: &str = concat!(
+    r#"<w:sectPr><w:pgSz w:w="12240" w:h="15840"/>"#,
+    r#"<w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" "#,
+    r#"w:header="720" w:footer="720" w:gutter="0"/></w:sectPr>"#,
+);
+/// Width of the text between the margins, in twentieths of a point
+const TEXT_WIDTH: usize = 9360;
+/// Widest embedded image: the text width in EMU
+const MAX_IMAGE_WIDTH: u64 = 5_943_600;
+const EMU_PER_PIXEL: u64 = 9525;
+/// Indentation of each list level, in twentieths of a point
+const LIST_INDENT: usize = 720;
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+const NUMBER_FORMATS: [&str; 3] = ["decimal", "lowerLetter", "lowerRoman"];
+/// Numbering instance shared by all bullet lists
+const BULLET_NUMBERING: usize = 1;
+
+/// Style IDs used by the document with their default definitions; styles of
+/// a reference document replace these, and the ones it lacks are added.
+const STYLES: &[(&str, &str)] = &[
+    (
+        "Normal",
+        r#"<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/><w:qFormat/><w:pPr><w:spacing w:after="120"/></w:pPr></w:style>"#,
+    ),
+    (
+        "DefaultParagraphFont",
+        r#"<w:style w:type="character" w:default="1" w:styleId="DefaultParagraphFont"><w:name w:val="Default Paragraph Font"/><w:uiPriority w:val="1"/><w:semiHidden/></w:style>"#,
+    ),
+    (
+        "TableNormal",
+        r#"<w:style w:type="table" w:default="1" w:styleId="TableNormal"><w:name w:val="Normal Table"/><w:semiHidden/><w:tblPr><w:tblInd w:w="0" w:type="dxa"/><w:tblCellMar><w:top w:w="0" w:type="dxa"/><w:left w:w="108" w:type="dxa"/><w:bottom w:w="0" w:type="dxa"/><w:right w:w="108" w:type="dxa"/></w:tblCellMar></w:tblPr></w:style>"#,
+    ),
+    (
+        "Heading1",
+        r#"<w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="480" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:color w:val="1F3864"/><w:sz w:val="36"/></w:rPr></w:style>"#,
+    ),
+    (
+        "Heading2",
+        r#"<w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="360" w:after="120"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/><w:color w:val="1F3864"/><w:sz w:val="30"/></w:rPr></w:style>"#,
+    ),
+    (
+        "Heading3",
+        r#"<w:style w:type="paragraph" w:styleId="Heading3"><w:name w:val="heading 3"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="80"/><w:outlineLvl w:val="2"/></w:pPr><w:rPr><w:b/><w:color w:val="1F3864"/><w:sz w:val="26"/></w:rPr></w:style>"#,
+    ),
+    (
+        "Heading4",
+        r#"<w:style w:type="paragraph" w:styleId="Heading4"><w:name w:val="heading 4"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="80"/><w:outlineLvl w:val="3"/></w:pPr><w:rPr><w:b/><w:i/><w:color w:val="1F3864"/><w:sz w:val="24"/></w:rPr></w:style>"#,
+    ),
+    (
+        "Heading5",
+        r#"<w:style w:type="paragraph" w:styleId="Heading5"><w:name w:val="heading 5"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="200" w:after="40"/><w:outlineLvl w:val="4"/></w:pPr><w:rPr><w:b/><w:color w:val="404040"/></w:rPr></w:style>"#,
+    ),
+    (
+        "Heading6",
+        r#"<w:style w:type="paragraph" w:styleId="Heading6"><w:name w:val="heading 6"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="200" w:after="40"/><w:outlineLvl w:val="5"/></w:pPr><w:rPr><w:i/><w:color w:val="404040"/></w:rPr></w:style>"#,
+    ),
+    (
+        "Quote",
+        r#"<w:style w:type="paragraph" w:styleId="Quote"><w:name w:val="Quote"/><w:basedOn w:val="Normal"/><w:qFormat/><w:pPr><w:pBdr><w:left w:val="single" w:sz="18" w:space="8" w:color="BFBFBF"/></w:pBdr><w:ind w:left="720"/></w:pPr><w:rPr><w:i/><w:color w:val="404040"/></w:rPr></w:style>"#,
+    ),
+    (
+        "ListParagraph",
+        r#"<w:style w:type="paragraph" w:styleId="ListParagraph"><w:name w:val="List Paragraph"/><w:basedOn w:val="Normal"/><w:qFormat/><w:pPr><w:spacing w:after="60"/><w:ind w:left="720"/></w:pPr></w:style>"#,
+    ),
+    (
+        "SourceCode",
+        r#"<w:style w:type="paragraph" w:styleId="SourceCode"><w:name w:val="Source Code"/><w:basedOn w:val="Normal"/><w:qFormat/><w:pPr><w:shd w:val="clear" w:color="auto" w:fill="F5F5F5"/><w:spacing w:after="120" w:line="240" w:lineRule="auto"/></w:pPr><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Courier New"/><w:sz w:val="20"/></w:rPr></w:style>"#,
+    ),
+    (
+        "VerbatimChar",
+        r#"<w:style w:type="character" w:styleId="VerbatimChar"><w:name w:val="Verbatim Char"/><w:basedOn w:val="DefaultParagraphFont"/><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Courier New"/><w:sz w:val="20"/></w:rPr></w:style>"#,
+    ),
+    (
+        "HorizontalRule",
+        r#"<w:style w:type="paragraph" w:styleId="HorizontalRule"><w:name w:val="Horizontal Rule"/><w:basedOn w:val="Normal"/><w:pPr><w:pBdr><w:bottom w:val="single" w:sz="6" w:space="1" w:color="auto"/></w:pBdr></w:pPr></w:style>"#,
+    ),
+    (
+        "Hyperlink",
+        r#"<w:style w:type="character" w:styleId="Hyperlink"><w:name w:val="Hyperlink"/><w:basedOn w:val="DefaultParagraphFont"/><w:rPr><w:color w:val="0563C1"/><w:u w:val="single"/></w:rPr></w:style>"#,
+    ),
+    (
+        "FootnoteText",
+        r#"<w:style w:type="paragraph" w:styleId="FootnoteText"><w:name w:val="footnote text"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="0"/></w:pPr><w:rPr><w:sz w:val="20"/></w:rPr></w:style>"#,
+    ),
+    (
+        "FootnoteReference",
+        r#"<w:style w:type="character" w:styleId="FootnoteReference"><w:name w:val="footnote reference"/><w:basedOn w:val="DefaultParagraphFont"/><w:rPr><w:vertAlign w:val="superscript"/></w:rPr></w:style>"#,
+    ),
+    (
+        "Table",
+        r#"<w:style w:type="table" w:styleId="Table"><w:name w:val="Table"/><w:basedOn w:val="TableNormal"/><w:tblPr><w:tblBorders><w:top w:val="single" w:sz="4" w:space="0" w:color="A6A6A6"/><w:left w:val="single" w:sz="4" w:space="0" w:color="A6A6A6"/><w:bottom w:val="single" w:sz="4" w:space="0" w:color="A6A6A6"/><w:right w:val="single" w:sz="4" w:space="0" w:color="A6A6A6"/><w:insideH w:val="single" w:sz="4" w:space="0" w:color="A6A6A6"/><w:insideV w:val="single" w:sz="4" w:space="0" w:color="A6A6A6"/></w:tblBorders></w:tblPr><w:tblStylePr w:type="firstRow"><w:rPr><w:b/></w:rPr><w:tcPr><w:shd w:val="clear" w:color="auto" w:fill="F2F2F2"/></w:tcPr></w:tblStylePr></w:style>"#,
+    ),
+];
+
+const DEFAULT_STYLES_HEADER: &str = concat!(
+    r#"<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">"#,
+    r#"<w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" "#,
+    r#"w:eastAsia="Calibri" w:cs="Calibri"/><w:sz w:val="22"/><w:szCs w:val="22"/>"#,
+    r#"<w:lang w:val="en-US"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr>"#,
+    r#"<w:spacing w:after="120" w:line="264" w:lineRule="auto"/></w:pPr></w:pPrDefault>"#,
+    r#"</w:docDefaults>"#,
+);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Part {
+    Document,
+    Footnotes,
+}
+
+impl Part {
+    fn file_name(self) -> &'static str {
+        match self {
+            Part::Document => "document.xml",
+            Part::Footnotes => "footnotes.xml",
+        }
+    }
+}
+
+struct Relationship {
+    part: Part,
+    id: String,
+    kind: &'static str,
+    target: String,
+    external: bool,
+}
+
+/// Embedded image file.
+struct Media {
+    name: String,
+    width: u64,
+    height: u64,
+}
+
+/// Character formatting of a run.
+#[derive(Clone, Copy, Default)]
+struct Format {
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    code: bool,
+    link: bool,
+}
+
+struct Printer<'a> {
+    config: &'a Config,
+    definitions: Definitions<'a>,
+    /// Part whose content is being rendered, which owns new relationships
+    part: Part,
+    relationships: Vec<Relationship>,
+    /// Embedded images by destination; `None` if one couldn't be embedded
+    media: HashMap<&'a str, Option<Media>>,
+    media_files: Vec<(String, Vec<u8>)>,
+    footnotes: Vec<String>,
+    /// Level and start number of each numbering instance of ordered lists
+    ordered_lists: Vec<(usize, u64)>,
+    /// Bookmark name of each heading in document order, see [`collect_headings`]
+    anchors: Vec<String>,
+    /// Index in `anchors` of the next heading
+    next_heading: usize,
+    /// Index in `anchors` of the first heading of each footnote
+    footnote_headings: HashMap<String, usize>,
+    bookmarks: usize,
+    drawings: usize,
+    /// Style of paragraphs inside a block quote or footnote
+    container_style: Option<&'static str>,
+    /// Level of the innermost enclosing list
+    list_level: Option<usize>,
+    /// Numbering instance and level for the first paragraph of a list item
+    pending_number: Option<(usize, usize)>,
+    /// Runs that start the next paragraph, e.g. a task checkbox
+    pending_prefix: Option<String>,
+}
+
+/// Render the document as a DOCX package.
+pub fn render_docx(document: &Document, config: &Config) -> Result<Vec<u8>> {
+    let mut printer = Printer {
+        config,
+        definitions: Definitions::collect(document),
+        part: Part::Document,
+        relationships: Vec::new(),
+        media: HashMap::new(),
+        media_files: Vec::new(),
+        footnotes: Vec::new(),
+        ordered_lists: Vec::new(),
+        anchors: collect_headings(document, "")
+            .into_iter()
+            .map(|entry| entry.anchor)
+            .collect(),
+        next_heading: 0,
+        footnote_headings: HeadingCounter::count(&document.blocks).footnotes,
+        bookmarks: 0,
+        drawings: 0,
+        container_style: None,
+        list_level: None,
+        pending_number: None,
+        pending_prefix: None,
+    };
+    let body = printer.blocks(&document.blocks);
+    printer.package(&body)
+}
+
+/// Counts headings in document order, like [`collect_headings`].
+#[derive(Default)]
+struct HeadingCounter {
+    headings: usize,
+    /// Number of headings before each footnote definition
+    footnotes: HashMap<String, usize>,
+}
+
+impl HeadingCounter {
+    fn count(blocks: &[Block]) -> Self {
+        let mut counter = Self::default();
+        for block in blocks {
+            counter.visit_block(block);
+        }
+        counter
+    }
+}
+
+impl Visitor for HeadingCounter {
+    fn visit_block(&mut self, block: &Block) {
+        match block {
+            Block::Heading(_) => self.headings += 1,
+            Block::FootnoteDefinition(footnote) => {
+                // The first definition of a label wins, as in `Definitions`
+                self.footnotes
+                    .entry(footnote.label.clone())
+                    .or_insert(self.headings);
+            }
+            _ => {}
+        }
+        self.walk_block(block);
+    }
+}
+
+/// Whether the destination is a URL rather than a local path.
+fn has_scheme(destination: &str) -> bool {
+    destination.contains("://") || destination.starts_with("mailto:")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            // Other control characters are not allowed in XML
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Run of text; tabs become tab elements.
+fn run(format: Format, text: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+    let mut properties = String::new();
+    if format.link {
+        properties.push_str(r#"<w:rStyle w:val="Hyperlink"/>"#);
+    } else if format.code {
+        properties.push_str(r#"<w:rStyle w:val="VerbatimChar"/>"#);
+    }
+    if format.bold {
+        properties.push_str("<w:b/>");
+    }
+    if format.italic {
+        properties.push_str("<w:i/>");
+    }
+    if format.strike {
+        properties.push_str("<w:strike/>");
+    }
+
+    let content: Vec<String> = text
+        .split('\t')
+        .map(|part| {
+            if part.is_empty() {
+                String::new()
+            } else {
+                format!(r#"<w:t xml:space="preserve">{}</w:t>"#, escape(part))
+            }
+        })
+        .collect();
+    let content = content.join("<w:tab/>");
+    if properties.is_empty() {
+        format!("<w:r>{content}</w:r>")
+    } else {
+        format!("<w:r><w:rPr>{properties}</w:rPr>{content}</w:r>")
+    }
+}
+
+/// Pixel size of a PNG, JPEG or GIF image, with the file extension for its format.
+fn image_size(data: &[u8]) -> Option<(&'static str, u64, u64)> {
+    let be16 = |at: usize| {
+        Some(u64::from(u16::from_be_bytes([
+            *data.get(at)?,
+            *data.get(at + 1)?,
+        ])))
+    };
+    let be32 = |at: usize| {
+        let bytes = data.get(at..at + 4)?;
+        Some(u64::from(u32::from_be_bytes([
+            bytes[0], bytes[1], bytes[2], bytes[3],
+        ])))
+    };
+
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(("png", be32(16)?, be32(20)?));
+    }
+    if data.starts_with(b"GIF8") {
+        let le16 = |at: usize| u64::from(u16::from_le_bytes([data[at], data[at + 1]]));
+        return (data.len() >= 10).then(|| ("gif", le16(6), le16(8)));
+    }
+    if data.starts_with(&[0xFF, 0xD8]) {
+        // The frame header (SOFn) holds the size
+        let mut offset = 2;
+        while offset + 4 <= data.len() {
+            if data[offset] != 0xFF {
+                return None;
+            }
+            let marker = data[offset + 1];
+            let length = be16(offset + 2)? as usize;
+            if matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+                return Some(("jpeg", be16(offset + 7)?, be16(offset + 5)?));
+            }
+            offset += 2 + length;
+        }
+    }
+    None
+}
+
+impl<'a> Printer<'a> {
+    fn blocks(&mut self, blocks: &'a [Block]) -> String {
+        let mut xml = String::new();
+        let mut previous_table = false;
+        for block in blocks {
+            let is_table = matches!(block, Block::Table(_));
+            // Adjacent tables would be merged into one
+            if previous_table && is_table {
+                xml.push_str("<w:p/>");
+            }
+            xml.push_str(&self.block(block));
+            previous_table = is_table;
+        }
+        xml
+    }
+
+    fn block(&mut self, block: &'a Block) -> String {
+        match block {
+            Block::Paragraph(inlines) => {
+                let runs = self.inlines(inlines, Format::default());
+                self.paragraph(None, "", &runs)
+            }
+            Block::Heading(heading) => self.heading(heading),
+            Block::ThematicBreak => self.paragraph(Some("HorizontalRule"), "", ""),
+            Block::BlockQuote(blocks) => {
+                let outer = self.container_style.replace("Quote");
+                let xml = self.blocks(blocks);
+                self.container_style = outer;
+                xml
+            }
+            Block::List(list) => self.list(list),
+            Block::CodeBlock(code) => self.code_block(code),
+            Block::Table(table) => self.table(table),
+            Block::GitHubAlert(alert) => self.alert(alert),
+            // Footnotes are rendered where they are referenced, raw HTML is dropped
+            Block::FootnoteDefinition(footnote) => {
+                self.next_heading += HeadingCounter::count(&footnote.blocks).headings;
+                String::new()
+            }
+            Block::HtmlBlock(_) | Block::Definition(_) | Block::Empty => String::new(),
+        }
+    }
+
+    /// Paragraph with the given style, or the style of the enclosing block;
+    /// the first paragraph of a list item carries its number.
+    fn paragraph(&mut self, style: Option<&str>, properties: &str, runs: &str) -> String {
+        let style = style
+            .or(self.container_style)
+            .or(self.list_level.map(|_| "ListParagraph"));
+        let mut all_properties = String::new();
+        if let Some(style) = style {
+            all_properties.push_str(&format!(r#"<w:pStyle w:val="{style}"/>"#));
+        }
+        match self.pending_number.take() {
+            Some((numbering, level)) => all_properties.push_str(&format!(
+                r#"<w:numPr><w:ilvl w:val="{level}"/><w:numId w:val="{numbering}"/></w:numPr>"#
+            )),
+            // Later paragraphs of an item line up with its text
+            None => {
+                if let Some(level) = self.list_level {
+                    let indent = LIST_INDENT * (level + 1);
+                    all_properties.push_str(&format!(r#"<w:ind w:left="{indent}"/>"#));
+                }
+            }
+        }
+        all_properties.push_str(properties);
+
+        let prefix = self.pending_prefix.take().unwrap_or_default();
+        if all_properties.is_empty() {
+            format!("<w:p>{prefix}{runs}</w:p>")
+        } else {
+            format!("<w:p><w:pPr>{all_properties}</w:pPr>{prefix}{runs}</w:p>")
+        }
+    }
+
+    /// Render content outside of the enclosing lists and blocks, e.g. a footnote.
+    fn isolated(
+        &mut self,
+        container_style: Option<&'static str>,
+        render: impl FnOnce(&mut Self) -> String,
+    ) -> String {
+        let saved = (
+            std::mem::replace(&mut self.container_style, container_style),
+            self.list_level.take(),
+            self.pending_number.take(),
+            self.pending_prefix.take(),
+        );
+        let xml = render(self);
+        (
+            self.container_style,
+            self.list_level,
+            self.pending_number,
+            self.pending_prefix,
+        ) = saved;
+        xml
+    }
+
+    fn heading(&mut self, heading: &'a Heading) -> String {
+        let level = heading_level(heading).clamp(1, 6);
+
+        // Bookmarks are link targets for `#anchor` links, named like on GitHub
+        let anchor = self
+            .anchors
+            .get(self.next_heading)
+            .cloned()
+            .unwrap_or_default();
+        self.next_heading += 1;
+        let id = self.bookmarks;
+        self.bookmarks += 1;
+
+        let runs = format!(
+            r#"<w:bookmarkStart w:id="{id}" w:name="{}"/>{}<w:bookmarkEnd w:id="{id}"/>"#,
+            escape(&anchor),
+            self.inlines(&heading.content, Format::default())
+        );
+        self.paragraph(Some(&format!("Heading{level}")), "", &runs)
+    }
+
+    fn code_block(&mut self, code: &'a CodeBlock) -> String {
+        let lines: Vec<String> = code
+            .literal
+            .trim_end_matches('\n')
+            .split('\n')
+            .map(|line| run(Format::default(), line))
+            .collect();
+        self.paragraph(Some("SourceCode"), "", &lines.join("<w:r><w:br/></w:r>"))
+    }
+
+    fn alert(&mut self, alert: &'a GitHubAlert) -> String {
+        let outer = self.container_style.replace("Quote");
+        let title = run(
+            Format {
+                bold: true,
+                ..Format::default()
+            },
+            alert_title(&alert.alert_type),
+        );
+        let mut xml = self.paragraph(None, "", &title);
+        xml.push_str(&self.blocks(&alert.blocks));
+        self.container_style = outer;
+        xml
+    }
+
+    fn list(&mut self, list: &'a List) -> String {
+        let level = self.list_level.map_or(0, |level| (level + 1).min(8));
+        let numbering = match &list.kind {
+            ListKind::Bullet(_) => BULLET_NUMBERING,
+            // Each ordered list restarts its numbering
+            ListKind::Ordered(options) => {
+                self.ordered_lists.push((level, options.start));
+                BULLET_NUMBERING + self.ordered_lists.len()
+            }
+        };
+
+        let outer = self.list_level.replace(level);
+        let mut xml = String::new();
+        for item in &list.items {
+            self.pending_number = Some((numbering, level));
+            self.pending_prefix = item.task.as_ref().map(|task| {
+                let checkbox = match task {
+                    TaskState::Complete => "☒ ",
+                    TaskState::Incomplete => "☐ ",
+                };
+                run(Format::default(), checkbox)
+            });
+            xml.push_str(&self.blocks(&item.blocks));
+            // An empty item still shows its number
+            if self.pending_number.is_some() {
+                xml.push_str(&self.paragraph(None, "", ""));
+            }
+        }
+        self.list_level = outer;
+        xml
+    }
+
+    fn table(&mut self, table: &'a Table) -> String {
+        self.isolated(None, |printer| {
+            let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0).max(1);
+            let width = TEXT_WIDTH / columns;
+
+            let mut xml = String::from(concat!(
+                r#"<w:tbl><w:tblPr><w:tblStyle w:val="Table"/><w:tblW w:w="0" w:type="auto"/>"#,
+                r#"<w:tblLook w:val="04A0" w:firstRow="1" w:lastRow="0" w:firstColumn="0" "#,
+                r#"w:lastColumn="0" w:noHBand="0" w:noVBand="1"/></w:tblPr><w:tblGrid>"#
+            ));
+            for _ in 0..columns {
+                xml.push_str(&format!(r#"<w:gridCol w:w="{width}"/>"#));
+            }
+            xml.push_str("</w:tblGrid>");
+
+            for (index, row) in table.rows.iter().enumerate() {
+                xml.push_str("<w:tr>");
+                if index == 0 {
+                    // The header row repeats on every page
+                    xml.push_str("<w:trPr><w:tblHeader/></w:trPr>");
+                }
+                for column in 0..columns {
+                    let runs = match row.get(column) {
+                        Some(cell) => printer.inlines(cell, Format::default()),
+                        None => String::new(),
+                    };
+                    let justification = match table.alignments.get(column) {
+                        Some(Alignment::Center) => r#"<w:jc w:val="center"/>"#,
+                        Some(Alignment::Right) => r#"<w:jc w:val="right"/>"#,
+                        _ => "",
+                    };
+                    xml.push_str(&format!(
+                        r#"<w:tc><w:tcPr><w:tcW w:w="{width}" w:type="dxa"/></w:tcPr>{}</w:tc>"#,
+                        printer.paragraph(None, justification, &runs)
+                    ));
+                }
+                xml.push_str("</w:tr>");
+            }
+            xml.push_str("</w:tbl>");
+            xml
+        })
+    }
+
+    fn inlines(&mut self, inlines: &'a [Inline], format: Format) -> String {
+        let mut runs = String::new();
+        for inline in inlines {
+            let xml = match inline {
+                Inline::Text(text) => run(format, &text.replace('\n', " ")),
+                Inline::LineBreak => "<w:r><w:br/></w:r>".to_string(),
+                Inline::Code(code) => run(
+                    Format {
+                        code: true,
+                        ..format
+                    },
+                    &code.replace('\n', " "),
+                ),
+                Inline::Emphasis(children) => self.inlines(
+                    children,
+                    Format {
+                        italic: true,
+                        ..format
+                    },
+                ),
+                Inline::Strong(children) => self.inlines(
+                    children,
+                    Format {
+                        bold: true,
+                        ..format
+                    },
+                ),
+                Inline::Strikethrough(children) => self.inlines(
+                    children,
+                    Format {
+                        strike: true,
+                        ..format
+                    },
+                ),
+                Inline::Link(link) => self.hyperlink(&link.destination, &link.children, format),
+                Inline::LinkReference(reference) => {
+                    match self.definitions.link_destination(&reference.label) {
+                        Some(destination) => self.hyperlink(destination, &reference.text, format),
+                        None => self.inlines(&reference.text, format),
+                    }
+                }
+                Inline::Image(image) => self.image(image, format),
+                Inline::Autolink(url) => {
+                    let destination = if has_scheme(url) {
+                        url.clone()
+                    } else {
+                        format!("mailto:{url}")
+                    };
+                    let text = run(
+                        Format {
+                            link: true,
+                            ..format
+                        },
+                        url,
+                    );
+                    self.hyperlink_runs(&destination, text, format)
+                }
+                Inline::FootnoteReference(label) => self.footnote_reference(label, format),
+                // Raw HTML has no equivalent
+                Inline::Html(_) | Inline::Empty => String::new(),
+            };
+            runs.push_str(&xml);
+        }
+        runs
+    }
+
+    fn hyperlink(&mut self, destination: &str, content: &'a [Inline], format: Format) -> String {
+        let runs = self.inlines(
+            content,
+            Format {
+                link: true,
+                ..format
+            },
+        );
+        self.hyperlink_runs(destination, runs, format)
+    }
+
+    /// Wrap runs in a hyperlink; links within `#` point to heading bookmarks.
+    fn hyperlink_runs(&mut self, destination: &str, runs: String, format: Format) -> String {
+        // Nested links are not possible
+        if format.link {
+            return runs;
+        }
+        if let Some(anchor) = destination.strip_prefix('#') {
+            return format!(
+                r#"<w:hyperlink w:anchor="{}" w:history="1">{runs}</w:hyperlink>"#,
+                escape(anchor)
+            );
+        }
+        let id = self.relationship("hyperlink", destination, true);
+        format!(r#"<w:hyperlink r:id="{id}" w:history="1">{runs}</w:hyperlink>"#)
+    }
+
+    /// ID of a relationship of the current part, added if it's new.
+    fn relationship(&mut self, kind: &'static str, target: &str, external: bool) -> String {
+        if let Some(relationship) = self.relationships.iter().find(|relationship| {
+            relationship.part == self.part
+                && relationship.kind == kind
+                && relationship.target == target
+        }) {
+            return relationship.id.clone();
+        }
+        // The first IDs are taken by the fixed parts of the document
+        let id = format!("rId{}", self.relationships.len() + 10);
+        self.relationships.push(Relationship {
+            part: self.part,
+            id: id.clone(),
+            kind,
+            target: target.to_string(),
+            external,
+        });
+        id
+    }
+
+    /// Embedded local image; remote images become links and images that
+    /// can't be read their alternative text.
+    fn image(&mut self, image: &'a Image, format: Format) -> String {
+        if has_scheme(&image.destination) || image.destination.starts_with("data:") {
+            let text = if image.alt.is_empty() {
+                &image.destination
+            } else {
+                &image.alt
+            };
+            let runs = run(
+                Format {
+                    link: true,
+                    ..format
+                },
+                text,
+            );
+            return self.hyperlink_runs(&image.destination, runs, format);
+        }
+
+        if !self.media.contains_key(image.destination.as_str()) {
+            let media = self.embed(&image.destination);
+            self.media.insert(&image.destination, media);
+        }
+        let Some(media) = &self.media[image.destination.as_str()] else {
+            return run(
+                Format {
+                    italic: true,
+                    ..format
+                },
+                &image.alt,
+            );
+        };
+        let (name, width, height) = (media.name.clone(), media.width, media.height);
+
+        let id = self.relationship("image", &format!("media/{name}"), false);
+        self.drawings += 1;
+        let drawing = self.drawings;
+        let description = escape(&image.alt);
+        format!(
+            concat!(
+                r#"<w:r><w:drawing><wp:inline distT="0" distB="0" distL="0" distR="0">"#,
+                r#"<wp:extent cx="{width}" cy="{height}"/>"#,
+                r#"<wp:docPr id="{drawing}" name="Picture {drawing}" descr="{description}"/>"#,
+                r#"<a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">"#,
+                r#"<pic:pic><pic:nvPicPr><pic:cNvPr id="0" name="{name}"/><pic:cNvPicPr/></pic:nvPicPr>"#,
+                r#"<pic:blipFill><a:blip r:embed="{id}"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>"#,
+                r#"<pic:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="{width}" cy="{height}"/></a:xfrm>"#,
+                r#"<a:prstGeom prst="rect"><a:avLst/></a:prstGeom></pic:spPr></pic:pic>"#,
+                r#"</a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"#
+            ),
+            width = width,
+            height = height,
+            drawing = drawing,
+            description = description,
+            name = name,
+            id = id,
+        )
+    }
+
+    /// Read an image file into the package; size in EMU, scaled down to the
+    /// text width.
+    fn embed(&mut self, destination: &str) -> Option<Media> {
+        let path: &Path = destination
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .as_ref();
+        let path = self.config.base_dir.join(path);
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) => {
+                eprintln!("Warning: image not embedded: {}: {err}", path.display());
+                return None;
+            }
+        };
+        let Some((extension, width, height)) = image_size(&data) else {
+            eprintln!(
+                "Warning: image not embedded, only PNG, JPEG and GIF are supported: {}",
+                path.display()
+            );
+            return None;
+        };
+
+        let (mut width, mut height) = (width * EMU_PER_PIXEL, height * EMU_PER_PIXEL);
+        if width > MAX_IMAGE_WIDTH {
+            height = height * MAX_IMAGE_WIDTH / width;
+            width = MAX_IMAGE_WIDTH;
+        }
+        let name = format!("image{}.{extension}", self.media_files.len() + 1);
+        self.media_files.push((name.clone(), data));
+        Some(Media {
+            name,
+            width,
+            height,
+        })
+    }
+
+    /// Footnote reference; the footnote gets the definition's content.
+    /// References within footnotes stay as text, since Word can't nest them.
+    fn footnote_reference(&mut self, label: &'a str, format: Format) -> String {
+        let footnote = match self.definitions.footnotes.get(label) {
+            Some(footnote) if self.part == Part::Document => *footnote,
+            _ => return run(format, &format!("[^{label}]")),
+        };
+
+        let id = self.footnotes.len() + 1;
+        // Reserve the ID, the content may add more footnotes
+        self.footnotes.push(String::new());
+        self.part = Part::Footnotes;
+        let first_heading = self.footnote_headings.get(label).copied();
+        let resume = std::mem::replace(
+            &mut self.next_heading,
+            first_heading.unwrap_or(self.anchors.len()),
+        );
+        let content = self.isolated(Some("FootnoteText"), |printer| {
+            printer.pending_prefix = Some(concat!(
+                r#"<w:r><w:rPr><w:rStyle w:val="FootnoteReference"/></w:rPr><w:footnoteRef/></w:r>"#,
+                r#"<w:r><w:t xml:space="preserve"> </w:t></w:r>"#
+            )
+            .to_string());
+            let mut xml = printer.blocks(&footnote.blocks);
+            if printer.pending_prefix.is_some() {
+                xml.push_str(&printer.paragraph(None, "", ""));
+            }
+            xml
+        });
+        self.part = Part::Document;
+        self.next_heading = resume;
+        self.footnotes[id - 1] = format!(r#"<w:footnote w:id="{id}">{content}</w:footnote>"#);
+
+        format!(
+            r#"<w:r><w:rPr><w:rStyle w:val="FootnoteReference"/></w:rPr><w:footnoteReference w:id="{id}"/></w:r>"#
+        )
+    }
+
+    /// Zip package with all parts of the document.
+    fn package(&self, body: &str) -> Result<Vec<u8>> {
+        let mut zip = ZipWriter::new();
+        zip.add("[Content_Types].xml", content_types().as_bytes())?;
+        zip.add("_rels/.rels", PACKAGE_RELATIONSHIPS.as_bytes())?;
+        zip.add("docProps/core.xml", self.core_properties().as_bytes())?;
+        zip.add(
+            "word/document.xml",
+            format!(
+                "{XML_DECLARATION}<w:document {NAMESPACES}><w:body>{body}{SECTION}</w:body></w:document>"
+            )
+            .as_bytes(),
+        )?;
+        zip.add("word/styles.xml", self.styles().as_bytes())?;
+        zip.add("word/numbering.xml", self.numbering().as_bytes())?;
+        zip.add("word/settings.xml", SETTINGS.as_bytes())?;
+        zip.add(
+            "word/footnotes.xml",
+            format!(
+                concat!(
+                    "{}<w:footnotes {}>",
+                    r#"<w:footnote w:type="separator" w:id="-1"><w:p><w:r><w:separator/></w:r></w:p></w:footnote>"#,
+                    r#"<w:footnote w:type="continuationSeparator" w:id="0"><w:p><w:r><w:continuationSeparator/></w:r></w:p></w:footnote>"#,
+                    "{}</w:footnotes>"
+                ),
+                XML_DECLARATION,
+                NAMESPACES,
+                self.footnotes.concat()
+            )
+            .as_bytes(),
+        )?;
+        for part in [Part::Document, Part::Footnotes] {
+            let fixed = match part {
+                Part::Document => DOCUMENT_RELATIONSHIPS,
+                Part::Footnotes => "",
+            };
+            zip.add(
+                &format!("word/_rels/{}.rels", part.file_name()),
+                self.part_relationships(part, fixed).as_bytes(),
+            )?;
+        }
+        for (name, data) in &self.media_files {
+            zip.add(&format!("word/media/{name}"), data)?;
+        }
+        zip.finish()
+    }
+
+    fn part_relationships(&self, part: Part, fixed: &str) -> String {
+        let mut xml = format!(
+            r#"{XML_DECLARATION}<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{fixed}"#
+        );
+        for relationship in self.relationships.iter().filter(|r| r.part == part) {
+            let mode = if relationship.external {
+                r#" TargetMode="External""#
+            } else {
+                ""
+            };
+            xml.push_str(&format!(
+                r#"<Relationship Id="{}" Type="{RELATIONSHIP_TYPES}/{}" Target="{}"{mode}/>"#,
+                relationship.id,
+                relationship.kind,
+                escape(&relationship.target)
+            ));
+        }
+        xml.push_str("</Relationships>");
+        xml
+    }
+
+    fn core_properties(&self) -> String {
+        let mut properties = String::new();
+        if let Some(title) = &self.config.title {
+            properties.push_str(&format!("<dc:title>{}</dc:title>", escape(title)));
+        }
+        if let Some(author) = &self.config.author {
+            properties.push_str(&format!("<dc:creator>{}</dc:creator>", escape(author)));
+        }
+        format!(
+            concat!(
+                "{}<cp:coreProperties ",
+                r#"xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" "#,
+                r#"xmlns:dc="http://purl.org/dc/elements/1.1/">{}</cp:coreProperties>"#
+            ),
+            XML_DECLARATION, properties
+        )
+    }
+
+    /// Styles of the reference document with missing styles added, or the defaults.
+    fn styles(&self) -> String {
+        let Some(reference) = &self.config.styles else {
+            let definitions: String = STYLES.iter().map(|(_, style)| *style).collect();
+            return format!("{XML_DECLARATION}{DEFAULT_STYLES_HEADER}{definitions}</w:styles>");
+        };
+
+        let missing: String = STYLES
+            .iter()
+            .filter(|(id, _)| !reference.contains(&format!(r#"w:styleId="{id}""#)))
+            .map(|(_, style)| *style)
+            .collect();
+        match reference.rfind("</w:styles>") {
+            Some(end) => format!("{}{missing}{}", &reference[..end], &reference[end..]),
+            None => reference.clone(),
+        }
+    }
+
+    fn numbering(&self) -> String {
+        let levels = |format: &dyn Fn(usize) -> (&'static str, String)| -> String {
+            (0..9)
+                .map(|level| {
+                    let (number_format, text) = format(level);
+                    let indent = LIST_INDENT * (level + 1);
+                    format!(
+                        concat!(
+                            r#"<w:lvl w:ilvl="{}"><w:start w:val="1"/><w:numFmt w:val="{}"/>"#,
+                            r#"<w:lvlText w:val="{}"/><w:lvlJc w:val="left"/>"#,
+                            r#"<w:pPr><w:ind w:left="{}" w:hanging="360"/></w:pPr></w:lvl>"#
+                        ),
+                        level, number_format, text, indent
+                    )
+                })
+                .collect()
+        };
+
+        let mut xml = format!(
+            concat!(
+                "{}<w:numbering {}>",
+                r#"<w:abstractNum w:abstractNumId="0"><w:multiLevelType w:val="hybridMultilevel"/>{}</w:abstractNum>"#,
+                r#"<w:abstractNum w:abstractNumId="1"><w:multiLevelType w:val="hybridMultilevel"/>{}</w:abstractNum>"#,
+                r#"<w:num w:numId="{}"><w:abstractNumId w:val="0"/></w:num>"#
+            ),
+            XML_DECLARATION,
+            NAMESPACES,
+            levels(&|level| ("bullet", BULLETS[level % BULLETS.len()].to_string())),
+            levels(&|level| (
+                NUMBER_FORMATS[level % NUMBER_FORMATS.len()],
+                format!("%{}.", level + 1)
+            )),
+            BULLET_NUMBERING
+        );
+        for (index, (level, start)) in self.ordered_lists.iter().enumerate() {
+            xml.push_str(&format!(
+                concat!(
+                    r#"<w:num w:numId="{}"><w:abstractNumId w:val="1"/>"#,
+                    r#"<w:lvlOverride w:ilvl="{}"><w:startOverride w:val="{}"/></w:lvlOverride></w:num>"#
+                ),
+                BULLET_NUMBERING + index + 1,
+                level,
+                start
+            ));
+        }
+        xml.push_str("</w:numbering>");
+        xml
+    }
+}
+
+fn content_types() -> String {
+    let mut xml = format!(
+        concat!(
+            "{}",
+            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+            r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+            r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+            r#"<Default Extension="png" ContentType="image/png"/>"#,
+            r#"<Default Extension="jpeg" ContentType="image/jpeg"/>"#,
+            r#"<Default Extension="gif" ContentType="image/gif"/>"#,
+            r#"<Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>"#
+        ),
+        XML_DECLARATION
+    );
+    for (part, kind) in [
+        ("document", "document.main"),
+        ("styles", "styles"),
+        ("numbering", "numbering"),
+        ("settings", "settings"),
+        ("footnotes", "footnotes"),
+    ] {
+        xml.push_str(&format!(
+            r#"<Override PartName="/word/{part}.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.{kind}+xml"/>"#
+        ));
+    }
+    xml.push_str("</Types>");
+    xml
+}
+
+const PACKAGE_RELATIONSHIPS: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>"#,
+    r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>"#,
+    r#"</Relationships>"#,
+);
+
+const DOCUMENT_RELATIONSHIPS: &str = concat!(
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>"#,
+    r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/numbering" Target="numbering.xml"/>"#,
+    r#"<Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/settings" Target="settings.xml"/>"#,
+    r#"<Relationship Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/footnotes" Target="footnotes.xml"/>"#,
+);
+
+const SETTINGS: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+    r#"<w:settings xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">"#,
+    r#"<w:footnotePr><w:footnote w:id="-1"/><w:footnote w:id="0"/></w:footnotePr>"#,
+    r#"</w:settings>"#,
+);
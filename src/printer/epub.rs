@@ -0,0 +1,413 @@
+use crate::input::html::dom::{self, is_void, Element, Node};
+use crate::output::zip::ZipWriter;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Options of the EPUB printer.
+pub struct Config {
+    pub title: String,
+    pub author: Option<String>,
+    /// Language tag, e.g. `en`
+    pub language: String,
+    /// Unique identifier of the book
+    pub identifier: String,
+    /// Last modification, formatted like `2024-01-31T12:00:00Z`
+    pub modified: String,
+    /// Stylesheets as file name and content; a default one is used if empty
+    pub stylesheets: Vec<(String, String)>,
+}
+
+/// Chapter rendered to HTML.
+pub struct Chapter {
+    pub title: String,
+    pub html: String,
+    /// Directory that relative image paths are resolved against
+    pub base_dir: PathBuf,
+}
+
+const DEFAULT_STYLESHEET: &str = "\
+body { font-family: serif; line-height: 1.5; margin: 0 5%; }
+h1, h2, h3, h4, h5, h6 { font-family: sans-serif; line-height: 1.2; }
+pre, code { font-family: monospace; font-size: 0.9em; }
+pre { background: #f5f5f5; padding: 0.5em; white-space: pre-wrap; }
+blockquote { border-left: 3px solid #ccc; margin-left: 0; padding-left: 1em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #aaa; padding: 0.2em 0.5em; }
+img { max-width: 100%; }
+.markdown-table-align-center { text-align: center; }
+.markdown-table-align-right { text-align: right; }
+";
+
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+/// Media types of images that EPUB readers support.
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("svg", "image/svg+xml"),
+    ("webp", "image/webp"),
+];
+
+/// Embedded image file.
+struct Resource {
+    href: String,
+    media_type: &'static str,
+    data: Vec<u8>,
+}
+
+#[derive(Default)]
+struct Printer {
+    /// Locations of embedded images by source path; `None` if one couldn't be embedded
+    images: HashMap<PathBuf, Option<String>>,
+    resources: Vec<Resource>,
+    /// Manifest properties of the chapter being written, e.g. `svg`
+    properties: Vec<&'static str>,
+}
+
+/// Render the chapters as an EPUB 3 package.
+pub fn render_epub(chapters: &[Chapter], config: &Config) -> Result<Vec<u8>> {
+    let mut printer = Printer::default();
+    let stylesheets: Vec<(String, String)> = if config.stylesheets.is_empty() {
+        vec![("style.css".to_string(), DEFAULT_STYLESHEET.to_string())]
+    } else {
+        config.stylesheets.clone()
+    };
+    let stylesheet_links: String = stylesheets
+        .iter()
+        .map(|(name, _)| {
+            format!(
+                "<link rel=\"stylesheet\" type=\"text/css\" href=\"css/{}\"/>\n",
+                escape(name)
+            )
+        })
+        .collect();
+
+    let mut documents = Vec::new();
+    let mut chapter_properties = Vec::new();
+    for chapter in chapters {
+        printer.properties.clear();
+        let body = printer.xhtml(&dom::parse(&chapter.html), &chapter.base_dir);
+        documents.push(xhtml_document(
+            &chapter.title,
+            &config.language,
+            &stylesheet_links,
+            &body,
+        ));
+        chapter_properties.push(printer.properties.join(" "));
+    }
+
+    let mut zip = ZipWriter::new();
+    // The media type must be the first entry, uncompressed
+    zip.add_stored("mimetype", b"application/epub+zip")?;
+    zip.add("META-INF/container.xml", CONTAINER.as_bytes())?;
+    zip.add(
+        "OEBPS/content.opf",
+        package_document(config, &stylesheets, &chapter_properties, &printer).as_bytes(),
+    )?;
+    zip.add("OEBPS/nav.xhtml", navigation(chapters, config).as_bytes())?;
+    for (index, document) in documents.iter().enumerate() {
+        zip.add(
+            &format!("OEBPS/{}", chapter_file(index)),
+            document.as_bytes(),
+        )?;
+    }
+    for (name, content) in &stylesheets {
+        zip.add(&format!("OEBPS/css/{name}"), content.as_bytes())?;
+    }
+    for resource in &printer.resources {
+        zip.add(&format!("OEBPS/{}", resource.href), &resource.data)?;
+    }
+    zip.finish()
+}
+
+fn chapter_file(index: usize) -> String {
+    format!("chapter-{}.xhtml", index + 1)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Whether the attribute name is a valid XML name.
+fn is_xml_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+}
+
+fn xhtml_document(title: &str, language: &str, stylesheets: &str, body: &str) -> String {
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n",
+            "<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" ",
+            "xml:lang=\"{language}\" lang=\"{language}\">\n<head>\n<meta charset=\"utf-8\"/>\n",
+            "<title>{title}</title>\n{stylesheets}</head>\n<body>\n{body}\n</body>\n</html>\n"
+        ),
+        language = escape(language),
+        title = escape(title),
+        stylesheets = stylesheets,
+        body = body.trim_end(),
+    )
+}
+
+fn navigation(chapters: &[Chapter], config: &Config) -> String {
+    let items: String = chapters
+        .iter()
+        .enumerate()
+        .map(|(index, chapter)| {
+            format!(
+                "<li><a href=\"{}\">{}</a></li>\n",
+                chapter_file(index),
+                escape(&chapter.title)
+            )
+        })
+        .collect();
+    let body = format!(
+        "<nav epub:type=\"toc\" id=\"toc\">\n<h1>Contents</h1>\n<ol>\n{items}</ol>\n</nav>"
+    );
+    xhtml_document(&config.title, &config.language, "", &body)
+}
+
+/// The OPF file with the metadata, the manifest of all files and the reading order.
+fn package_document(
+    config: &Config,
+    stylesheets: &[(String, String)],
+    chapter_properties: &[String],
+    printer: &Printer,
+) -> String {
+    let mut metadata = format!(
+        "<dc:identifier id=\"book-id\">{}</dc:identifier>\n<dc:title>{}</dc:title>\n",
+        escape(&config.identifier),
+        escape(&config.title)
+    );
+    if let Some(author) = &config.author {
+        metadata.push_str(&format!("<dc:creator>{}</dc:creator>\n", escape(author)));
+    }
+    metadata.push_str(&format!(
+        "<dc:language>{}</dc:language>\n<meta property=\"dcterms:modified\">{}</meta>\n",
+        escape(&config.language),
+        escape(&config.modified)
+    ));
+
+    let mut manifest = String::from(
+        "<item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n",
+    );
+    let mut spine = String::new();
+    for (index, properties) in chapter_properties.iter().enumerate() {
+        let properties = if properties.is_empty() {
+            String::new()
+        } else {
+            format!(" properties=\"{properties}\"")
+        };
+        manifest.push_str(&format!(
+            "<item id=\"chapter-{number}\" href=\"{}\" media-type=\"application/xhtml+xml\"{properties}/>\n",
+            chapter_file(index),
+            number = index + 1
+        ));
+        spine.push_str(&format!("<itemref idref=\"chapter-{}\"/>\n", index + 1));
+    }
+    for (index, (name, _)) in stylesheets.iter().enumerate() {
+        manifest.push_str(&format!(
+            "<item id=\"css-{}\" href=\"css/{}\" media-type=\"text/css\"/>\n",
+            index + 1,
+            escape(name)
+        ));
+    }
+    for (index, resource) in printer.resources.iter().enumerate() {
+        manifest.push_str(&format!(
+            "<item id=\"image-{}\" href=\"{}\" media-type=\"{}\"/>\n",
+            index + 1,
+            resource.href,
+            resource.media_type
+        ));
+    }
+
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\">\n",
+            "<metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n{}</metadata>\n",
+            "<manifest>\n{}</manifest>\n<spine>\n{}</spine>\n</package>\n"
+        ),
+        metadata, manifest, spine
+    )
+}
+
+impl Printer {
+    /// Serialize parsed HTML as XHTML: void elements are closed and local
+    /// images are embedded.
+    fn xhtml(&mut self, nodes: &[Node], base_dir: &Path) -> String {
+        let mut xhtml = String::new();
+        for node in nodes {
+            match node {
+                Node::Text(text) => xhtml.push_str(&escape(text)),
+                // Comments may contain `--`, which XML doesn't allow
+                Node::Comment(_) => {}
+                Node::Element(element) if element.name == "img" => {
+                    xhtml.push_str(&self.image(element, base_dir))
+                }
+                Node::Element(element) => {
+                    let mut attributes = element.attributes.clone();
+                    if let Some((property, namespace)) = foreign_namespace(&element.name) {
+                        if !self.properties.contains(&property) {
+                            self.properties.push(property);
+                        }
+                        if element.attribute("xmlns").is_none() {
+                            attributes.insert(0, ("xmlns".to_string(), namespace.to_string()));
+                        }
+                        if uses_xlink(element) && element.attribute("xmlns:xlink").is_none() {
+                            attributes.push(("xmlns:xlink".to_string(), XLINK.to_string()));
+                        }
+                    }
+                    let is_empty = is_void(&element.name);
+                    xhtml.push_str(&start_tag(&element.name, &attributes, is_empty));
+                    if is_empty {
+                        continue;
+                    }
+                    xhtml.push_str(&self.xhtml(&element.children, base_dir));
+                    xhtml.push_str(&format!("</{}>", element.name));
+                }
+            }
+        }
+        xhtml
+    }
+
+    /// Image with its file embedded; remote images become links and images
+    /// that can't be embedded their alternative text.
+    fn image(&mut self, element: &Element, base_dir: &Path) -> String {
+        let source = element.attribute("src").unwrap_or_default();
+        let alt = element.attribute("alt").unwrap_or_default();
+        if source.contains("://") || source.starts_with("data:") {
+            let text = if alt.is_empty() { source } else { alt };
+            return format!("<a href=\"{}\">{}</a>", escape(source), escape(text));
+        }
+
+        let path = base_dir.join(source.split(['?', '#']).next().unwrap_or_default());
+        if !self.images.contains_key(&path) {
+            let href = self.embed(&path);
+            self.images.insert(path.clone(), href);
+        }
+        let Some(href) = &self.images[&path] else {
+            return escape(alt);
+        };
+
+        let attributes: Vec<(String, String)> = element
+            .attributes
+            .iter()
+            .map(|(name, value)| match name.as_str() {
+                "src" => (name.clone(), href.clone()),
+                _ => (name.clone(), value.clone()),
+            })
+            .chain(
+                element
+                    .attribute("alt")
+                    .is_none()
+                    .then(|| ("alt".to_string(), String::new())),
+            )
+            .collect();
+        start_tag(&element.name, &attributes, true)
+    }
+
+    /// Add an image file to the package and return its location.
+    fn embed(&mut self, path: &Path) -> Option<String> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let Some((_, media_type)) = IMAGE_TYPES.iter().find(|(known, _)| *known == extension)
+        else {
+            eprintln!(
+                "Warning: image not embedded, unsupported format: {}",
+                path.display()
+            );
+            return None;
+        };
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(err) => {
+                eprintln!("Warning: image not embedded: {}: {err}", path.display());
+                return None;
+            }
+        };
+        if image_media_type(&data) != Some(media_type) {
+            eprintln!(
+                "Warning: image not embedded, content is not {media_type}: {}",
+                path.display()
+            );
+            return None;
+        }
+
+        let href = format!("images/image-{}.{extension}", self.resources.len() + 1);
+        self.resources.push(Resource {
+            href: href.clone(),
+            media_type,
+            data,
+        });
+        Some(href)
+    }
+}
+
+/// Media type of an image detected from its content.
+fn image_media_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some("image/png");
+    }
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+    if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+        return Some("image/webp");
+    }
+    // SVG is XML text, possibly after a declaration or comments
+    let text = String::from_utf8_lossy(&data[..data.len().min(4096)]);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    (text.starts_with('<') && text.contains("<svg")).then_some("image/svg+xml")
+}
+
+const XLINK: &str = "http://www.w3.org/1999/xlink";
+
+/// Manifest property and namespace of an SVG or MathML root element, which
+/// XHTML requires to declare its namespace.
+fn foreign_namespace(name: &str) -> Option<(&'static str, &'static str)> {
+    match name {
+        "svg" => Some(("svg", "http://www.w3.org/2000/svg")),
+        "math" => Some(("mathml", "http://www.w3.org/1998/Math/MathML")),
+        _ => None,
+    }
+}
+
+/// Whether the element or one of its descendants has an `xlink:` attribute.
+fn uses_xlink(element: &Element) -> bool {
+    element
+        .attributes
+        .iter()
+        .any(|(name, _)| name.starts_with("xlink:"))
+        || element.elements().any(uses_xlink)
+}
+
+/// Start tag, or the whole element if it's `empty`; attributes that aren't
+/// valid in XML are left out.
+fn start_tag(name: &str, attributes: &[(String, String)], empty: bool) -> String {
+    let mut tag = format!("<{name}");
+    for (name, value) in attributes.iter().filter(|(name, _)| is_xml_name(name)) {
+        tag.push_str(&format!(" {name}=\"{}\"", escape(value)));
+    }
+    tag.push_str(if empty { "/>" } else { ">" });
+    tag
+}
@@ -0,0 +1,459 @@
+use super::{wrap_markup, BREAK};
+use crate::ast::{alert_title, heading_level, inline_text, Definitions};
+use markdown_ppp::ast::{
+    Alignment, Block, CodeBlock, Document, Heading, Inline, List, ListItem, ListKind, Table,
+    TaskState,
+};
+use std::collections::HashMap;
+
+/// Options of the man page printer.
+pub struct Config {
+    /// Maximum line width of the roff source
+    pub width: usize,
+    /// Fields of the `.TH` header
+    pub name: String,
+    pub section: String,
+    pub date: String,
+    pub source: String,
+    pub manual: String,
+}
+
+struct Printer<'a> {
+    config: &'a Config,
+    definitions: Definitions<'a>,
+    /// Footnotes, printed in a NOTES section at the end
+    notes: Vec<&'a [Block]>,
+    /// Note numbers of footnotes that were already referenced
+    footnote_numbers: HashMap<String, usize>,
+    /// Inside a list item, where paragraphs continue the item's indentation
+    in_item: bool,
+    /// Number of enclosing bold and italic spans
+    bold: usize,
+    italic: usize,
+    has_tables: bool,
+}
+
+/// Render the document as a man page with a `.TH` header.
+pub fn render_man(document: &Document, config: &Config) -> String {
+    let mut printer = Printer {
+        config,
+        definitions: Definitions::collect(document),
+        notes: Vec::new(),
+        footnote_numbers: HashMap::new(),
+        in_item: false,
+        bold: 0,
+        italic: 0,
+        has_tables: false,
+    };
+
+    let mut body = printer.blocks(&document.blocks);
+
+    // Footnotes may reference further notes, so the list can grow while printing
+    let mut index = 0;
+    while index < printer.notes.len() {
+        if index == 0 {
+            body.extend([".SH".to_string(), "NOTES".to_string()]);
+        }
+        let blocks = printer.notes[index];
+        body.extend(printer.item(blocks, &format!(".IP [{}] 5", index + 1)));
+        index += 1;
+    }
+
+    let mut lines = Vec::new();
+    // Tells man(1) to run the page through tbl
+    if printer.has_tables {
+        lines.push("'\\\" t".to_string());
+    }
+    let header: Vec<String> = [
+        &config.name,
+        &config.section,
+        &config.date,
+        &config.source,
+        &config.manual,
+    ]
+    .iter()
+    .map(|field| argument(field))
+    .collect();
+    lines.push(format!(".TH {}", header.join(" ")));
+    lines.extend(body);
+    lines.join("\n")
+}
+
+/// Escape text for roff.
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\e"),
+            // A plain `-` is a hyphen, which breaks copying command options
+            '-' => escaped.push_str("\\-"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quoted macro argument.
+fn argument(text: &str) -> String {
+    format!("\"{}\"", escape_text(text).replace('"', "\\(dq"))
+}
+
+/// Guard a line that roff would read as a request.
+fn escape_line_start(line: String) -> String {
+    if line.starts_with(['.', '\'']) {
+        format!("\\&{line}")
+    } else {
+        line
+    }
+}
+
+fn code_lines(code: &CodeBlock) -> Vec<String> {
+    code.literal
+        .trim_end_matches('\n')
+        .lines()
+        .map(|line| escape_line_start(escape_text(line)))
+        .collect()
+}
+
+/// Bullet list whose items all start with a term, as in ``- `--width`: ...``,
+/// which is printed as a tagged paragraph list.
+fn is_term_list(list: &List) -> bool {
+    matches!(list.kind, ListKind::Bullet(_)) && list.items.iter().all(|item| term(item).is_some())
+}
+
+/// Term of a list item and the rest of its first paragraph.
+fn term(item: &ListItem) -> Option<(&Inline, &str, &[Inline])> {
+    let Some(Block::Paragraph(inlines)) = item.blocks.first() else {
+        return None;
+    };
+    match inlines.as_slice() {
+        [term @ (Inline::Code(_) | Inline::Strong(_)), Inline::Text(text), rest @ ..] => {
+            let description = text.strip_prefix(':')?;
+            Some((term, description.trim_start(), rest))
+        }
+        _ => None,
+    }
+}
+
+impl<'a> Printer<'a> {
+    fn blocks(&mut self, blocks: &'a [Block]) -> Vec<String> {
+        blocks.iter().flat_map(|block| self.block(block)).collect()
+    }
+
+    /// Macro starting a paragraph; inside list items `.IP` keeps the indentation.
+    fn paragraph_macro(&self) -> String {
+        if self.in_item {
+            ".IP".to_string()
+        } else {
+            ".PP".to_string()
+        }
+    }
+
+    fn block(&mut self, block: &'a Block) -> Vec<String> {
+        match block {
+            Block::Paragraph(inlines) => {
+                let mut lines = vec![self.paragraph_macro()];
+                lines.extend(self.text(inlines));
+                lines
+            }
+            Block::Heading(heading) => self.heading(heading),
+            Block::ThematicBreak => vec![self.paragraph_macro(), "* * *".to_string()],
+            Block::BlockQuote(blocks) => self.inset(blocks),
+            Block::List(list) => self.list(list),
+            Block::CodeBlock(code) => {
+                let mut lines = vec![
+                    self.paragraph_macro(),
+                    ".RS 4".to_string(),
+                    ".nf".to_string(),
+                ];
+                lines.extend(code_lines(code));
+                lines.extend([".fi".to_string(), ".RE".to_string()]);
+                lines
+            }
+            Block::Table(table) => self.table(table),
+            Block::GitHubAlert(alert) => {
+                let mut lines = vec![
+                    self.paragraph_macro(),
+                    format!("\\fB{}:\\fR", alert_title(&alert.alert_type)),
+                ];
+                lines.extend(self.inset(&alert.blocks));
+                lines
+            }
+            // Raw HTML can't be shown, footnotes are printed as notes at the end
+            Block::HtmlBlock(_)
+            | Block::Definition(_)
+            | Block::FootnoteDefinition(_)
+            | Block::Empty => Vec::new(),
+        }
+    }
+
+    /// Indented blocks, e.g. of a quote.
+    fn inset(&mut self, blocks: &'a [Block]) -> Vec<String> {
+        let in_item = std::mem::replace(&mut self.in_item, false);
+        let mut lines = vec![".RS 4".to_string()];
+        lines.extend(self.blocks(blocks));
+        lines.push(".RE".to_string());
+        self.in_item = in_item;
+        lines
+    }
+
+    /// Paragraph text filled to the configured width; hard line breaks
+    /// become `.br` requests.
+    fn text(&mut self, inlines: &'a [Inline]) -> Vec<String> {
+        let markup = self.inlines(inlines);
+        let mut lines = Vec::new();
+        for (index, segment) in markup.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(".br".to_string());
+            }
+            lines.extend(
+                wrap_markup(segment, self.config.width)
+                    .into_iter()
+                    .map(escape_line_start),
+            );
+        }
+        lines
+    }
+
+    fn heading(&mut self, heading: &'a Heading) -> Vec<String> {
+        let text = inline_text(&heading.content).trim().to_string();
+        match heading_level(heading) {
+            // Section names are upper case by convention
+            1 => vec![
+                ".SH".to_string(),
+                escape_line_start(escape_text(&text.to_uppercase())),
+            ],
+            2 => vec![".SS".to_string(), escape_line_start(escape_text(&text))],
+            _ => vec![
+                self.paragraph_macro(),
+                format!("\\fB{}\\fR", escape_text(&text)),
+            ],
+        }
+    }
+
+    fn list(&mut self, list: &'a List) -> Vec<String> {
+        // Nested lists are indented relative to the enclosing item
+        let nested = self.in_item;
+        let mut lines = Vec::new();
+        if nested {
+            lines.push(".RS".to_string());
+        }
+
+        if is_term_list(list) {
+            for item in &list.items {
+                lines.extend(self.term_item(item));
+            }
+        } else {
+            let markers: Vec<String> = (0..list.items.len())
+                .map(|index| match &list.kind {
+                    ListKind::Bullet(_) => "\\(bu".to_string(),
+                    ListKind::Ordered(options) => {
+                        format!("{}.", options.start + index as u64)
+                    }
+                })
+                .collect();
+            let width = match &list.kind {
+                ListKind::Bullet(_) => 2,
+                ListKind::Ordered(_) => markers.iter().map(String::len).max().unwrap_or(0) + 1,
+            };
+
+            for (item, marker) in list.items.iter().zip(markers) {
+                let mut item_lines = self.item(&item.blocks, &format!(".IP {marker} {width}"));
+                let task = match item.task {
+                    Some(TaskState::Complete) => "[x] ",
+                    Some(TaskState::Incomplete) => "[ ] ",
+                    None => "",
+                };
+                let starts_with_text = matches!(item.blocks.first(), Some(Block::Paragraph(_)));
+                if let Some(text) = item_lines.get_mut(1).filter(|_| starts_with_text) {
+                    text.insert_str(0, task);
+                }
+                lines.extend(item_lines);
+            }
+        }
+
+        if nested {
+            lines.push(".RE".to_string());
+        }
+        lines
+    }
+
+    /// Blocks of a list item or note; the first paragraph starts with the
+    /// tagged `item_macro`.
+    fn item(&mut self, blocks: &'a [Block], item_macro: &str) -> Vec<String> {
+        let in_item = std::mem::replace(&mut self.in_item, true);
+        let mut lines = self.blocks(blocks);
+        self.in_item = in_item;
+
+        match lines.first_mut() {
+            Some(first) if first == ".IP" => *first = item_macro.to_string(),
+            _ => lines.insert(0, item_macro.to_string()),
+        }
+        lines
+    }
+
+    /// Item of a term list: `.TP` with the term on the next line.
+    fn term_item(&mut self, item: &'a ListItem) -> Vec<String> {
+        let Some((term, description, rest)) = term(item) else {
+            return Vec::new();
+        };
+
+        let term_markup = self.inlines(std::slice::from_ref(term));
+        let mut lines = vec![
+            ".TP".to_string(),
+            escape_line_start(term_markup.replace(BREAK, " ")),
+        ];
+        let in_item = std::mem::replace(&mut self.in_item, true);
+        let mut markup = escape_text(description).replace(char::is_whitespace, &BREAK.to_string());
+        markup.push_str(&self.inlines(rest));
+        lines.extend(
+            wrap_markup(&markup, self.config.width)
+                .into_iter()
+                .map(escape_line_start),
+        );
+        lines.extend(self.blocks(&item.blocks[1..]));
+        self.in_item = in_item;
+        lines
+    }
+
+    fn table(&mut self, table: &'a Table) -> Vec<String> {
+        let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return Vec::new();
+        }
+        self.has_tables = true;
+
+        let formats: Vec<&str> = (0..columns)
+            .map(|column| match table.alignments.get(column) {
+                Some(Alignment::Center) => "c",
+                Some(Alignment::Right) => "r",
+                Some(Alignment::Left) | Some(Alignment::None) | None => "l",
+            })
+            .collect();
+
+        let mut lines = vec![
+            self.paragraph_macro(),
+            ".TS".to_string(),
+            "tab(|);".to_string(),
+        ];
+        // The header row is bold, the last format line applies to all other rows
+        if table.rows.len() > 1 {
+            let header: Vec<String> = formats.iter().map(|format| format!("{format}B")).collect();
+            lines.push(header.join(" "));
+        }
+        lines.push(format!("{}.", formats.join(" ")));
+
+        for (index, row) in table.rows.iter().enumerate() {
+            let cells: Vec<String> = (0..columns)
+                .map(|column| {
+                    let content = row
+                        .get(column)
+                        .map(|cell| self.inlines(cell))
+                        .unwrap_or_default()
+                        .replace(['\n', BREAK], " ")
+                        .replace('|', "\\[ba]");
+                    match content.trim() {
+                        // A lone `_` or `=` would draw a line
+                        cell @ ("_" | "=") => format!("\\&{cell}"),
+                        cell => cell.to_string(),
+                    }
+                })
+                .collect();
+            lines.push(escape_line_start(cells.join("|")));
+            if index == 0 && table.rows.len() > 1 {
+                lines.push("_".to_string());
+            }
+        }
+        lines.push(".TE".to_string());
+        lines
+    }
+
+    /// Font escape for the current bold and italic nesting.
+    fn font(&self) -> &'static str {
+        match (self.bold > 0, self.italic > 0) {
+            (true, true) => "\\f(BI",
+            (true, false) => "\\fB",
+            (false, true) => "\\fI",
+            (false, false) => "\\fR",
+        }
+    }
+
+    /// Markup of inline content; spaces that may be broken are [`BREAK`] and
+    /// hard line breaks are `\n`.
+    fn inlines(&mut self, inlines: &'a [Inline]) -> String {
+        let mut markup = String::new();
+        for inline in inlines {
+            match inline {
+                Inline::Text(text) => markup
+                    .push_str(&escape_text(text).replace(char::is_whitespace, &BREAK.to_string())),
+                Inline::LineBreak => markup.push('\n'),
+                Inline::Code(code) => {
+                    self.bold += 1;
+                    markup.push_str(self.font());
+                    markup.push_str(&escape_text(&code.replace('\n', " ")));
+                    self.bold -= 1;
+                    markup.push_str(self.font());
+                }
+                Inline::Strong(children) => {
+                    self.bold += 1;
+                    markup.push_str(self.font());
+                    markup.push_str(&self.inlines(children));
+                    self.bold -= 1;
+                    markup.push_str(self.font());
+                }
+                Inline::Emphasis(children) => {
+                    self.italic += 1;
+                    markup.push_str(self.font());
+                    markup.push_str(&self.inlines(children));
+                    self.italic -= 1;
+                    markup.push_str(self.font());
+                }
+                // roff has no strikethrough; the text is kept
+                Inline::Strikethrough(children) => markup.push_str(&self.inlines(children)),
+                Inline::Link(link) => {
+                    let text = self.inlines(&link.children);
+                    markup.push_str(&text);
+                    self.link_target(&mut markup, &inline_text(&link.children), &link.destination);
+                }
+                Inline::LinkReference(link) => {
+                    let text = self.inlines(&link.text);
+                    markup.push_str(&text);
+                    if let Some(destination) = self.definitions.link_destination(&link.label) {
+                        self.link_target(&mut markup, &inline_text(&link.text), destination);
+                    }
+                }
+                Inline::Autolink(url) => markup.push_str(&escape_text(url)),
+                Inline::Image(image) => markup.push_str(
+                    &escape_text(&image.alt).replace(char::is_whitespace, &BREAK.to_string()),
+                ),
+                Inline::FootnoteReference(label) => {
+                    if let Some(number) = self.footnote_number(label) {
+                        markup.push_str(&format!("[{number}]"));
+                    }
+                }
+                Inline::Html(_) | Inline::Empty => {}
+            }
+        }
+        markup
+    }
+
+    /// Append the URL of a link in angle brackets unless the text shows it.
+    fn link_target(&self, markup: &mut String, text: &str, url: &str) {
+        if url.is_empty() || text == url || text == url.trim_start_matches("mailto:") {
+            return;
+        }
+        markup.push(BREAK);
+        markup.push_str(&format!("<{}>", escape_text(url)));
+    }
+
+    fn footnote_number(&mut self, label: &str) -> Option<usize> {
+        if let Some(number) = self.footnote_numbers.get(label) {
+            return Some(*number);
+        }
+        let footnote = self.definitions.footnotes.get(label)?;
+        self.notes.push(&footnote.blocks);
+        let number = self.notes.len();
+        self.footnote_numbers.insert(label.to_string(), number);
+        Some(number)
+    }
+}
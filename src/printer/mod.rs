@@ -0,0 +1,95 @@
+pub mod asciidoc;
+pub mod docx;
+pub mod epub;
+pub mod man;
+pub mod org;
+pub mod rst;
+pub mod text;
+pub mod typst;
+
+use markdown_ppp::ast::Alignment;
+use unicode_width::UnicodeWidthStr;
+
+/// Break opportunity between words of markup; spaces inside strings, URLs and
+/// raw text are never broken.
+pub const BREAK: char = '\u{1}';
+
+/// Number of terminal columns the text takes up.
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Greedy fill of words into lines of at most `width` columns; longer
+/// words get a line of their own.
+pub fn fill<'a>(words: impl IntoIterator<Item = &'a str>, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    for word in words {
+        let word_width = display_width(word);
+        if line_width > 0 && line_width + 1 + word_width > width {
+            lines.push(std::mem::take(&mut line));
+            line_width = 0;
+        }
+        if line_width > 0 {
+            line.push(' ');
+            line_width += 1;
+        }
+        line.push_str(word);
+        line_width += word_width;
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Indent every non-empty line by `width` spaces.
+pub fn indent(lines: Vec<String>, width: usize) -> Vec<String> {
+    let prefix = " ".repeat(width);
+    lines
+        .into_iter()
+        .map(|line| {
+            if line.is_empty() {
+                line
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect()
+}
+
+/// Prefix the first line with `marker` and indent the others to match.
+pub fn hang(lines: Vec<String>, marker: &str) -> Vec<String> {
+    if lines.is_empty() {
+        return vec![marker.trim_end().to_string()];
+    }
+    let mut lines = lines.into_iter();
+    let first = lines.next().unwrap_or_default();
+    let mut result = vec![format!("{marker}{first}")];
+    result.extend(indent(lines.collect(), marker.chars().count()));
+    result
+}
+
+/// Fill markup with [`BREAK`] between words; `\n` forces a line break.
+pub fn wrap_markup(markup: &str, width: usize) -> Vec<String> {
+    markup
+        .split('\n')
+        .flat_map(|segment| fill(segment.split(BREAK).filter(|word| !word.is_empty()), width))
+        .collect()
+}
+
+/// Pad a table cell to `width` columns according to its column alignment.
+pub fn align(cell: &str, width: usize, alignment: Alignment) -> String {
+    let padding = width.saturating_sub(display_width(cell));
+    match alignment {
+        Alignment::Right => format!("{}{cell}", " ".repeat(padding)),
+        Alignment::Center => {
+            let left = padding / 2;
+            format!("{}{cell}{}", " ".repeat(left), " ".repeat(padding - left))
+        }
+        Alignment::Left | Alignment::None => format!("{cell}{}", " ".repeat(padding)),
+    }
+}
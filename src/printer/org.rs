@@ -0,0 +1,472 @@
+use super::{align, display_width, hang, wrap_markup, BREAK};
+use crate::ast::{heading_level, Definitions};
+use markdown_ppp::ast::{
+    Alignment, Block, CodeBlock, CodeBlockKind, Document, GitHubAlertType, Heading, Image, Inline,
+    List, ListKind, Table, TaskState,
+};
+
+/// Options of the Org printer.
+pub struct Config {
+    /// Maximum line width of paragraphs
+    pub width: usize,
+}
+
+/// Zero width space, Org's way to keep text from being read as markup.
+pub const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+struct Printer<'a> {
+    config: &'a Config,
+    definitions: Definitions<'a>,
+    /// Number of enclosing lists and blocks, where headlines can't appear
+    depth: usize,
+}
+
+/// Render the document as Org markup.
+pub fn render_org(document: &Document, config: &Config) -> String {
+    let mut printer = Printer {
+        config,
+        definitions: Definitions::collect(document),
+        depth: 0,
+    };
+    printer.blocks(&document.blocks).join("\n")
+}
+
+/// Name of an Org special block for a GitHub alert, e.g. `NOTE`.
+pub fn alert_block(alert_type: &GitHubAlertType) -> &'static str {
+    match alert_type {
+        GitHubAlertType::Note => "NOTE",
+        GitHubAlertType::Tip => "TIP",
+        GitHubAlertType::Important => "IMPORTANT",
+        GitHubAlertType::Warning => "WARNING",
+        GitHubAlertType::Caution => "CAUTION",
+    }
+}
+
+/// File extensions of links that Org displays as images.
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "svg", "webp", "bmp"];
+
+/// Guard a line that Org would read as block markup, e.g. a headline, list
+/// item, keyword, table or fixed-width line.
+fn escape_line_start(line: String) -> String {
+    let first_word = line.split(' ').next().unwrap_or_default();
+    let is_enumerator = first_word
+        .strip_suffix(['.', ')'])
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+    let is_markup = line.starts_with(['*', '#', '|', ':'])
+        || line.starts_with("[fn:")
+        || matches!(first_word, "-" | "+")
+        || is_enumerator
+        || (line.len() >= 5 && line.chars().all(|c| c == '-'));
+
+    if is_markup {
+        format!("{ZERO_WIDTH_SPACE}{line}")
+    } else {
+        line
+    }
+}
+
+/// Link target; brackets would end the link.
+fn link_target(destination: &str) -> String {
+    destination.replace('[', "%5B").replace(']', "%5D")
+}
+
+/// Whether the URL has a scheme, so that Org does not read it as a file name.
+fn has_scheme(url: &str) -> bool {
+    url.contains("://") || url.starts_with("mailto:")
+}
+
+/// Link target; paths without a scheme need the `file:` prefix, since Org
+/// reads other targets as searches in the document.
+fn target(destination: &str) -> String {
+    let destination = link_target(destination);
+    if has_scheme(&destination)
+        || destination.starts_with(['#', '/'])
+        || destination.starts_with("file:")
+    {
+        destination
+    } else {
+        format!("file:{destination}")
+    }
+}
+
+fn image_link(image: &Image) -> String {
+    format!("[[{}]]", target(&image.destination))
+}
+
+/// Label usable in `[fn:label]`.
+fn footnote_label(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Whether Org reads a marker between `before` and `after` as emphasis:
+/// markers need whitespace or punctuation outside.
+fn is_marker_boundary(before: Option<char>, after: Option<char>) -> bool {
+    let outside = |c: Option<char>, allowed: &str| {
+        c.map_or(true, |c| {
+            c.is_whitespace() || c == BREAK || c == ZERO_WIDTH_SPACE || allowed.contains(c)
+        })
+    };
+    outside(before, "-('\"{") && outside(after, "-.,:!?;'\")}[")
+}
+
+fn code_block(code: &CodeBlock) -> Vec<String> {
+    let language = match &code.kind {
+        CodeBlockKind::Fenced { info: Some(info) } => {
+            info.split_whitespace().next().unwrap_or_default()
+        }
+        _ => "",
+    };
+    let (begin, end) = if language.is_empty() {
+        ("#+BEGIN_EXAMPLE".to_string(), "#+END_EXAMPLE")
+    } else {
+        (format!("#+BEGIN_SRC {language}"), "#+END_SRC")
+    };
+
+    let mut lines = vec![begin];
+    lines.extend(
+        code.literal
+            .trim_end_matches('\n')
+            .lines()
+            .map(escape_code_line),
+    );
+    lines.push(end.to_string());
+    lines
+}
+
+/// Lines of block content starting with `*` or `#+` are escaped with a comma.
+pub fn escape_code_line(line: &str) -> String {
+    let content = line.trim_start_matches(',');
+    if content.starts_with('*') || content.starts_with("#+") {
+        format!(",{line}")
+    } else {
+        line.to_string()
+    }
+}
+
+impl<'a> Printer<'a> {
+    /// Lines of a sequence of blocks separated by blank lines.
+    fn blocks(&mut self, blocks: &'a [Block]) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut previous: Option<&Block> = None;
+        for block in blocks {
+            let block_lines = self.block(block);
+            if block_lines.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+                // Two blank lines end a list or footnote definition, otherwise
+                // the next block would become part of it
+                let ends_previous = match (previous, block) {
+                    (Some(Block::List(_)), Block::List(_)) => true,
+                    (Some(Block::FootnoteDefinition(_)), Block::FootnoteDefinition(_)) => false,
+                    (Some(Block::FootnoteDefinition(_)), _) => true,
+                    _ => false,
+                };
+                if ends_previous {
+                    lines.push(String::new());
+                }
+            }
+            lines.extend(block_lines);
+            previous = Some(block);
+        }
+        lines
+    }
+
+    fn block(&mut self, block: &'a Block) -> Vec<String> {
+        match block {
+            Block::Paragraph(inlines) => self.paragraph(inlines),
+            Block::Heading(heading) => self.heading(heading),
+            Block::ThematicBreak => vec!["-----".to_string()],
+            Block::BlockQuote(blocks) => self.special_block("QUOTE", blocks),
+            Block::List(list) => self.list(list),
+            Block::CodeBlock(code) => code_block(code),
+            Block::Table(table) => self.table(table),
+            Block::GitHubAlert(alert) => {
+                self.special_block(alert_block(&alert.alert_type), &alert.blocks)
+            }
+            Block::HtmlBlock(html) => {
+                let mut lines = vec!["#+BEGIN_EXPORT html".to_string()];
+                lines.extend(html.trim_end_matches('\n').lines().map(escape_code_line));
+                lines.push("#+END_EXPORT".to_string());
+                lines
+            }
+            Block::FootnoteDefinition(footnote) => {
+                // Definitions must start at the beginning of a line
+                let depth = std::mem::replace(&mut self.depth, 1);
+                let content = self.blocks(&footnote.blocks);
+                self.depth = depth;
+                let marker = format!("[fn:{}] ", footnote_label(&footnote.label));
+                hang(content, &marker)
+            }
+            // Link references are resolved
+            Block::Definition(_) | Block::Empty => Vec::new(),
+        }
+    }
+
+    fn paragraph(&mut self, inlines: &'a [Inline]) -> Vec<String> {
+        // A paragraph of just an image is a block image with alternative text
+        if let [Inline::Image(image)] = inlines {
+            let mut lines = Vec::new();
+            if !image.alt.is_empty() {
+                lines.push(format!(
+                    "#+ATTR_HTML: :alt {}",
+                    image.alt.replace('\n', " ")
+                ));
+            }
+            lines.push(image_link(image));
+            return lines;
+        }
+        wrap_markup(&self.inlines(inlines), self.config.width)
+            .into_iter()
+            .map(escape_line_start)
+            .collect()
+    }
+
+    fn heading(&mut self, heading: &'a Heading) -> Vec<String> {
+        let content = self
+            .inlines(&heading.content)
+            .replace("\\\\\n", " ")
+            .replace(['\n', BREAK], " ");
+        let content = content.trim();
+
+        // Headlines end lists and blocks, so nested headings become bold text
+        if self.depth > 0 {
+            return vec![escape_line_start(format!("*{content}*"))];
+        }
+        vec![format!(
+            "{} {content}",
+            "*".repeat(heading_level(heading) as usize)
+        )]
+    }
+
+    fn special_block(&mut self, name: &str, blocks: &'a [Block]) -> Vec<String> {
+        self.depth += 1;
+        let content = self.blocks(blocks);
+        self.depth -= 1;
+
+        let mut lines = vec![format!("#+BEGIN_{name}")];
+        lines.extend(content);
+        lines.push(format!("#+END_{name}"));
+        lines
+    }
+
+    fn list(&mut self, list: &'a List) -> Vec<String> {
+        self.depth += 1;
+        let mut lines = Vec::new();
+        for (index, item) in list.items.iter().enumerate() {
+            let marker = match &list.kind {
+                ListKind::Bullet(_) => "- ".to_string(),
+                ListKind::Ordered(options) => format!("{}. ", options.start + index as u64),
+            };
+            let mut cookies = String::new();
+            if let ListKind::Ordered(options) = &list.kind {
+                if index == 0 && options.start != 1 {
+                    cookies.push_str(&format!("[@{}] ", options.start));
+                }
+            }
+            match item.task {
+                Some(TaskState::Complete) => cookies.push_str("[X] "),
+                Some(TaskState::Incomplete) => cookies.push_str("[ ] "),
+                None => {}
+            }
+
+            let mut item_lines: Vec<String> = Vec::new();
+            for block in &item.blocks {
+                let block_lines = self.block(block);
+                if block_lines.is_empty() {
+                    continue;
+                }
+                // Nested lists follow the item text directly
+                if !item_lines.is_empty() && !matches!(block, Block::List(_)) {
+                    item_lines.push(String::new());
+                }
+                item_lines.extend(block_lines);
+            }
+            if let Some(first) = item_lines.first_mut() {
+                *first = format!("{cookies}{first}");
+            } else if !cookies.is_empty() {
+                item_lines.push(cookies.trim_end().to_string());
+            }
+            lines.extend(hang(item_lines, &marker));
+        }
+        self.depth -= 1;
+        lines
+    }
+
+    fn table(&mut self, table: &'a Table) -> Vec<String> {
+        let mut rows: Vec<Vec<String>> = table
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| {
+                        self.inlines(cell)
+                            .replace("\\\\\n", " ")
+                            .replace(['\n', BREAK], " ")
+                            .trim()
+                            .replace("\\|", "|")
+                            .replace('|', "\\vert{}")
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        // Alignment cookies go in a row of their own
+        let has_alignment = table
+            .alignments
+            .iter()
+            .any(|alignment| !matches!(alignment, Alignment::None));
+        if has_alignment {
+            let cookies = (0..columns)
+                .map(|column| match table.alignments.get(column) {
+                    Some(Alignment::Left) => "<l>",
+                    Some(Alignment::Center) => "<c>",
+                    Some(Alignment::Right) => "<r>",
+                    Some(Alignment::None) | None => "",
+                })
+                .map(str::to_string)
+                .collect();
+            rows.insert(0, cookies);
+        }
+
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                rows.iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| display_width(cell))
+                    .max()
+                    .unwrap_or(0)
+                    .max(1)
+            })
+            .collect();
+
+        let mut lines = Vec::new();
+        let header = usize::from(has_alignment);
+        for (index, row) in rows.iter().enumerate() {
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(column, width)| {
+                    let cell = row.get(column).map_or("", String::as_str);
+                    align(cell, *width, Alignment::Left)
+                })
+                .collect();
+            lines.push(format!("| {} |", cells.join(" | ")));
+            if index == header {
+                let rules: Vec<String> = widths.iter().map(|width| "-".repeat(width + 2)).collect();
+                lines.push(format!("|{}|", rules.join("+")));
+            }
+        }
+        lines
+    }
+
+    /// Markup of inline content; spaces that may be broken are [`BREAK`] and
+    /// hard line breaks are `\\` followed by `\n`.
+    fn inlines(&mut self, inlines: &'a [Inline]) -> String {
+        let mut markup = String::new();
+        for (index, inline) in inlines.iter().enumerate() {
+            let next = inlines.get(index + 1);
+            match inline {
+                Inline::Text(text) => {
+                    markup.extend(
+                        text.chars()
+                            .map(|c| if c.is_whitespace() { BREAK } else { c }),
+                    )
+                }
+                Inline::LineBreak => markup.push_str("\\\\\n"),
+                Inline::Code(code) => {
+                    let code = code.replace('\n', " ");
+                    let mark = if code.contains('~') { '=' } else { '~' };
+                    self.mark(&mut markup, mark, code, next);
+                }
+                Inline::Emphasis(children) => {
+                    let content = self.marked_content(children);
+                    self.mark(&mut markup, '/', content, next);
+                }
+                Inline::Strong(children) => {
+                    let content = self.marked_content(children);
+                    self.mark(&mut markup, '*', content, next);
+                }
+                Inline::Strikethrough(children) => {
+                    let content = self.marked_content(children);
+                    self.mark(&mut markup, '+', content, next);
+                }
+                Inline::Link(link) => {
+                    let text = self.link_text(&link.children);
+                    markup.push_str(&link_markup(&link.destination, &text));
+                }
+                Inline::LinkReference(reference) => {
+                    let text = self.link_text(&reference.text);
+                    match self.definitions.link_destination(&reference.label) {
+                        Some(destination) => markup.push_str(&link_markup(destination, &text)),
+                        None => markup.push_str(&self.inlines(&reference.text)),
+                    }
+                }
+                Inline::Image(image) => markup.push_str(&image_link(image)),
+                Inline::Autolink(url) if has_scheme(url) => markup.push_str(url),
+                Inline::Autolink(url) => markup.push_str(&format!("[[mailto:{url}]]")),
+                Inline::FootnoteReference(label) => {
+                    markup.push_str(&format!("[fn:{}]", footnote_label(label)))
+                }
+                Inline::Html(html) => markup.push_str(&format!("@@html:{html}@@")),
+                Inline::Empty => {}
+            }
+        }
+        markup
+    }
+
+    /// Content of an emphasis marker, which is kept on one line.
+    fn marked_content(&mut self, inlines: &'a [Inline]) -> String {
+        self.inlines(inlines).replace(BREAK, " ")
+    }
+
+    /// Append `content` between `mark`s; a zero width space separates marks
+    /// from adjacent words, which would keep Org from reading them.
+    fn mark(&self, markup: &mut String, mark: char, content: String, next: Option<&Inline>) {
+        let content = content.trim();
+        if content.is_empty() {
+            return;
+        }
+        if !is_marker_boundary(markup.chars().last(), Some(' ')) {
+            markup.push(ZERO_WIDTH_SPACE);
+        }
+        markup.push(mark);
+        markup.push_str(content);
+        markup.push(mark);
+        let after = match next {
+            Some(Inline::Text(text)) => text.chars().next(),
+            Some(Inline::LineBreak) | None => None,
+            Some(_) => Some('x'),
+        };
+        if !is_marker_boundary(Some(' '), after) {
+            markup.push(ZERO_WIDTH_SPACE);
+        }
+    }
+
+    /// Description of a link, kept on one line.
+    fn link_text(&mut self, inlines: &'a [Inline]) -> String {
+        self.inlines(inlines)
+            .replace("\\\\\n", " ")
+            .replace(['\n', BREAK], " ")
+            .replace("]]", "] ]")
+    }
+}
+
+fn link_markup(destination: &str, text: &str) -> String {
+    let target = target(destination);
+    if text.trim().is_empty() || text == destination {
+        return format!("[[{target}]]");
+    }
+    format!("[[{target}][{text}]]")
+}
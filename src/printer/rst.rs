@@ -0,0 +1,577 @@
+use super::{align, display_width, hang, indent, wrap_markup, BREAK};
+use crate::ast::{alert_title, heading_level, inline_text, link_label, Definitions};
+use markdown_ppp::ast::{
+    Alignment, Block, CodeBlock, CodeBlockKind, Document, FootnoteDefinition, Heading, Image,
+    Inline, LinkDefinition, List, ListKind, Table, TaskState,
+};
+use std::collections::HashSet;
+
+/// Underline characters of section titles by level.
+const HEADING_CHARS: [char; 6] = ['=', '-', '~', '^', '"', '\''];
+
+#[derive(Clone, Copy)]
+pub enum TableStyle {
+    /// Grid table drawn with `+`, `-` and `|`
+    Grid,
+    /// `list-table` directive
+    ListTable,
+}
+
+/// Options of the reStructuredText printer.
+pub struct Config {
+    /// Maximum line width of paragraphs
+    pub width: usize,
+    pub table_style: TableStyle,
+}
+
+struct Printer<'a> {
+    config: &'a Config,
+    definitions: Definitions<'a>,
+    /// Normalized labels of hyperlink targets already printed
+    printed_targets: HashSet<String>,
+    /// Level of the last section title; levels may not be skipped
+    section_level: usize,
+    /// Number of enclosing lists, quotes and directives, where sections are
+    /// not allowed
+    depth: usize,
+    /// Substitution definitions of inline images, printed after their block
+    substitutions: Vec<String>,
+    image_count: usize,
+}
+
+/// Render the document as reStructuredText.
+pub fn render_rst(document: &Document, config: &Config) -> String {
+    let mut printer = Printer {
+        config,
+        definitions: Definitions::collect(document),
+        printed_targets: HashSet::new(),
+        section_level: 0,
+        depth: 0,
+        substitutions: Vec::new(),
+        image_count: 0,
+    };
+    printer.blocks(&document.blocks, config.width).join("\n")
+}
+
+/// Escape text so that reStructuredText shows it literally.
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | '*' | '`' | '|' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            // `name_` is a reference
+            '_' if !chars.peek().is_some_and(|c| c.is_alphanumeric()) => escaped.push_str("\\_"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape text inside interpreted text or a hyperlink reference.
+fn escape_interpreted(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Text markup with spaces that may be broken replaced by [`BREAK`].
+fn text_markup(text: &str) -> String {
+    escape_text(text)
+        .chars()
+        .map(|c| if c.is_whitespace() { BREAK } else { c })
+        .collect()
+}
+
+/// Guard a line that reStructuredText would read as block markup, e.g. a list
+/// item, explicit markup, field or section underline.
+fn escape_line_start(line: String) -> String {
+    // A line starting with an escaped character can't be block markup
+    if line.starts_with('\\') {
+        return line;
+    }
+    let first_word = line.split(' ').next().unwrap_or_default();
+    let is_bullet = matches!(first_word, "-" | "+" | "*" | "•" | "‣" | "⁃");
+    let is_enumerator = first_word
+        .strip_suffix(['.', ')'])
+        .map(|number| number.strip_prefix('(').unwrap_or(number))
+        .is_some_and(|number| {
+            number == "#"
+                || (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+                || (number.chars().count() == 1 && number.chars().all(char::is_alphabetic))
+                || (!number.is_empty() && number.chars().all(|c| "ivxlcdmIVXLCDM".contains(c)))
+        });
+    let is_punctuation = line.chars().all(|c| c.is_ascii_punctuation());
+    let is_markup = line.starts_with("..") || line.starts_with(">>>") || line.starts_with(':');
+
+    if is_bullet || is_enumerator || is_punctuation || is_markup {
+        format!("\\{line}")
+    } else {
+        line
+    }
+}
+
+/// Label usable as an auto-numbered footnote label `[#label]`.
+fn footnote_label(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Reference name in a hyperlink target; names with colons must be quoted.
+fn target_name(name: &str) -> String {
+    if name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '.' | '_'))
+    {
+        name.to_string()
+    } else {
+        format!("`{}`", escape_interpreted(name))
+    }
+}
+
+/// Whether the URL is recognized as a standalone hyperlink in text.
+fn is_standalone_url(url: &str) -> bool {
+    url.contains([':', '@'])
+        && !url.ends_with(['_', '.'])
+        && url
+            .chars()
+            .all(|c| c.is_alphanumeric() || "/:._~%?#&=+@-".contains(c))
+}
+
+fn link(text: &str, url: &str) -> String {
+    if text.is_empty() || text == url {
+        return if is_standalone_url(url) {
+            url.to_string()
+        } else {
+            format!("`<{}>`__", escape_interpreted(url))
+        };
+    }
+    // Anonymous references, so that link texts may repeat
+    format!(
+        "`{} <{}>`__",
+        escape_interpreted(text).replace(char::is_whitespace, &BREAK.to_string()),
+        escape_interpreted(url)
+    )
+}
+
+/// Whether inline markup after `markup` needs an escaped space to be recognized.
+fn needs_separator_before(markup: &str) -> bool {
+    markup
+        .chars()
+        .last()
+        .is_some_and(|c| !(c.is_whitespace() || c == BREAK || "-:/'\"<([{".contains(c)))
+}
+
+/// Whether inline markup before `next` needs an escaped space to be recognized.
+fn needs_separator_after(next: Option<&Inline>) -> bool {
+    match next {
+        None | Some(Inline::LineBreak) | Some(Inline::Html(_)) | Some(Inline::Empty) => false,
+        Some(Inline::Text(text)) => text
+            .chars()
+            .next()
+            .is_some_and(|c| !(c.is_whitespace() || "-.,:;!?\\/'\")]}>".contains(c))),
+        Some(_) => true,
+    }
+}
+
+fn directive(name: &str, argument: &str, content: Vec<String>) -> Vec<String> {
+    let mut lines = vec![format!(".. {name}:: {argument}").trim_end().to_string()];
+    if !content.is_empty() {
+        lines.push(String::new());
+        lines.extend(indent(content, 3));
+    }
+    lines
+}
+
+fn code_block(code: &CodeBlock) -> Vec<String> {
+    let literal = code.literal.trim_end_matches('\n');
+    if literal.trim().is_empty() {
+        return Vec::new();
+    }
+    let content = literal.lines().map(str::to_string).collect();
+    match &code.kind {
+        CodeBlockKind::Fenced { info: Some(info) } if !info.trim().is_empty() => {
+            let language = info.split_whitespace().next().unwrap_or_default();
+            directive("code-block", language, content)
+        }
+        _ => {
+            let mut lines = vec!["::".to_string(), String::new()];
+            lines.extend(indent(content, 3));
+            lines
+        }
+    }
+}
+
+/// `image` directive, as a substitution definition if `substitution` is given.
+fn image_directive(substitution: Option<&str>, image: &Image) -> Vec<String> {
+    let mut lines = vec![match substitution {
+        Some(name) => format!(".. |{name}| image:: {}", image.destination),
+        None => format!(".. image:: {}", image.destination),
+    }];
+    if !image.alt.is_empty() {
+        lines.push(format!("   :alt: {}", image.alt.replace('\n', " ")));
+    }
+    lines
+}
+
+fn grid_table(rows: &[Vec<String>], alignments: &[Alignment]) -> Vec<String> {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| display_width(cell))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let border = |c: char| {
+        let segments: Vec<String> = widths
+            .iter()
+            .map(|width| c.to_string().repeat(width + 2))
+            .collect();
+        format!("+{}+", segments.join("+"))
+    };
+
+    let mut lines = vec![border('-')];
+    for (index, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, width)| {
+                let cell = row.get(column).map_or("", String::as_str);
+                let alignment = alignments.get(column).copied().unwrap_or_default();
+                align(cell, *width, alignment)
+            })
+            .collect();
+        lines.push(format!("| {} |", cells.join(" | ")));
+        // A header needs at least one body row
+        lines.push(if index == 0 && rows.len() > 1 {
+            border('=')
+        } else {
+            border('-')
+        });
+    }
+    lines
+}
+
+fn list_table(rows: &[Vec<String>]) -> Vec<String> {
+    let mut lines = vec![".. list-table::".to_string()];
+    if rows.len() > 1 {
+        lines.push("   :header-rows: 1".to_string());
+    }
+    lines.push(String::new());
+
+    let mut content = Vec::new();
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| format!("- {cell}").trim_end().to_string())
+            .collect();
+        content.extend(hang(cells, "* "));
+    }
+    lines.extend(indent(content, 3));
+    lines
+}
+
+impl<'a> Printer<'a> {
+    /// Lines of a sequence of blocks separated by blank lines.
+    fn blocks(&mut self, blocks: &'a [Block], width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut previous: Option<&Block> = None;
+        for block in blocks {
+            let block_lines = self.block(block, width);
+            if block_lines.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+                // An empty comment ends a list, quote or directive that would
+                // otherwise continue with the next block
+                let continues = match block {
+                    Block::List(_) => matches!(previous, Some(Block::List(_))),
+                    Block::BlockQuote(_) => !matches!(previous, Some(Block::Heading(_))),
+                    _ => false,
+                };
+                if continues {
+                    lines.push("..".to_string());
+                    lines.push(String::new());
+                }
+            }
+            lines.extend(block_lines);
+            if !self.substitutions.is_empty() {
+                lines.push(String::new());
+                lines.append(&mut self.substitutions);
+            }
+            previous = Some(block);
+        }
+        lines
+    }
+
+    fn block(&mut self, block: &'a Block, width: usize) -> Vec<String> {
+        match block {
+            Block::Paragraph(inlines) => self.paragraph(inlines, width),
+            Block::Heading(heading) => self.heading(heading),
+            // Transitions are only allowed between sections and paragraphs
+            Block::ThematicBreak if self.depth > 0 => Vec::new(),
+            Block::ThematicBreak => vec!["----".to_string()],
+            Block::BlockQuote(blocks) => {
+                self.depth += 1;
+                let lines = indent(self.blocks(blocks, width.saturating_sub(3)), 3);
+                self.depth -= 1;
+                lines
+            }
+            Block::List(list) => self.list(list, width),
+            Block::CodeBlock(code) => code_block(code),
+            Block::Table(table) => self.table(table),
+            Block::GitHubAlert(alert) => {
+                self.depth += 1;
+                let content = self.blocks(&alert.blocks, width.saturating_sub(3));
+                self.depth -= 1;
+                let name = alert_title(&alert.alert_type).to_lowercase();
+                directive(&name, "", content)
+            }
+            Block::HtmlBlock(html) => directive(
+                "raw",
+                "html",
+                html.trim_end_matches('\n')
+                    .lines()
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            Block::Definition(definition) => self.target(definition),
+            Block::FootnoteDefinition(footnote) => self.footnote_definition(footnote, width),
+            Block::Empty => Vec::new(),
+        }
+    }
+
+    fn paragraph(&mut self, inlines: &'a [Inline], width: usize) -> Vec<String> {
+        // A paragraph of just an image is an image directive
+        if let [Inline::Image(image)] = inlines {
+            return image_directive(None, image);
+        }
+
+        let markup = self.inlines(inlines);
+        // Hard line breaks need a line block
+        let mut lines: Vec<String> = if markup.contains('\n') {
+            markup
+                .split('\n')
+                .flat_map(|segment| hang(wrap_markup(segment, width.saturating_sub(2)), "| "))
+                .collect()
+        } else {
+            wrap_markup(&markup, width)
+                .into_iter()
+                .map(escape_line_start)
+                .collect()
+        };
+        // A paragraph ending in `::` would start a literal block
+        if let Some(last) = lines.last_mut() {
+            if last.ends_with("::") {
+                last.insert(last.len() - 1, '\\');
+            }
+        }
+        lines
+    }
+
+    fn heading(&mut self, heading: &'a Heading) -> Vec<String> {
+        let content = self.inlines(&heading.content).replace(['\n', BREAK], " ");
+        let content = content.trim().to_string();
+        if content.is_empty() {
+            return Vec::new();
+        }
+
+        // Sections can't be nested in lists, quotes or directives
+        if self.depth > 0 {
+            return vec![format!(".. rubric:: {content}")];
+        }
+        let level = (heading_level(heading) as usize).min(self.section_level + 1);
+        self.section_level = level;
+        let underline = HEADING_CHARS[level - 1]
+            .to_string()
+            .repeat(display_width(&content));
+        vec![content, underline]
+    }
+
+    fn list(&mut self, list: &'a List, width: usize) -> Vec<String> {
+        self.depth += 1;
+        // Items with several blocks are separated by blank lines
+        let loose = list.items.iter().any(|item| item.blocks.len() > 1);
+
+        let mut lines = Vec::new();
+        for (index, item) in list.items.iter().enumerate() {
+            let marker = match &list.kind {
+                ListKind::Bullet(_) => "- ".to_string(),
+                ListKind::Ordered(options) => format!("{}. ", options.start + index as u64),
+            };
+            let content_width = width.saturating_sub(marker.chars().count());
+            let mut content = self.blocks(&item.blocks, content_width);
+
+            let task = match item.task {
+                Some(TaskState::Complete) => "[x] ",
+                Some(TaskState::Incomplete) => "[ ] ",
+                None => "",
+            };
+            match content.first_mut() {
+                Some(first) => first.insert_str(0, task),
+                None if !task.is_empty() => content.push(task.trim_end().to_string()),
+                None => {}
+            }
+
+            if loose && index > 0 {
+                lines.push(String::new());
+            }
+            lines.extend(hang(content, &marker));
+        }
+        self.depth -= 1;
+        lines
+    }
+
+    fn table(&mut self, table: &'a Table) -> Vec<String> {
+        let rows: Vec<Vec<String>> = table
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| {
+                        let content = self.inlines(cell).replace(['\n', BREAK], " ");
+                        escape_line_start(content.trim().to_string())
+                    })
+                    .collect()
+            })
+            .collect();
+
+        match self.config.table_style {
+            TableStyle::Grid => grid_table(&rows, &table.alignments),
+            TableStyle::ListTable => list_table(&rows),
+        }
+    }
+
+    /// Hyperlink target of a link reference definition; the first definition
+    /// of a label wins.
+    fn target(&mut self, definition: &LinkDefinition) -> Vec<String> {
+        let label = link_label(&definition.label);
+        if !self.printed_targets.insert(label.clone()) {
+            return Vec::new();
+        }
+        vec![format!(
+            ".. _{}: {}",
+            target_name(&label),
+            definition.destination
+        )]
+    }
+
+    fn footnote_definition(
+        &mut self,
+        footnote: &'a FootnoteDefinition,
+        width: usize,
+    ) -> Vec<String> {
+        self.depth += 1;
+        let content = self.blocks(&footnote.blocks, width.saturating_sub(3));
+        self.depth -= 1;
+        let mut lines = vec![format!(".. [#{}]", footnote_label(&footnote.label))];
+        lines.extend(indent(content, 3));
+        lines
+    }
+
+    /// Markup of inline content; spaces that may be broken are [`BREAK`] and
+    /// hard line breaks are `\n`.
+    fn inlines(&mut self, inlines: &'a [Inline]) -> String {
+        let mut markup = String::new();
+        for (index, inline) in inlines.iter().enumerate() {
+            let item = match inline {
+                Inline::Text(text) => {
+                    markup.push_str(&text_markup(text));
+                    continue;
+                }
+                Inline::LineBreak => {
+                    markup.push('\n');
+                    continue;
+                }
+                // Strikethrough has no markup; its text is kept
+                Inline::Strikethrough(children) => {
+                    let text = self.inlines(children);
+                    markup.push_str(&text);
+                    continue;
+                }
+                Inline::Code(code) => {
+                    let code = code.replace('\n', " ");
+                    if code.trim().is_empty() {
+                        continue;
+                    }
+                    format!("``{}``", code.trim())
+                }
+                // Inline markup can't be nested, so the content is plain text
+                Inline::Emphasis(children) => match inline_text(children).trim() {
+                    "" => continue,
+                    text => format!("*{}*", text_markup(text)),
+                },
+                Inline::Strong(children) => match inline_text(children).trim() {
+                    "" => continue,
+                    text => format!("**{}**", text_markup(text)),
+                },
+                Inline::Link(link_inline) => link(
+                    inline_text(&link_inline.children).trim(),
+                    &link_inline.destination,
+                ),
+                Inline::LinkReference(reference) => {
+                    let label = link_label(&reference.label);
+                    let text = inline_text(&reference.text);
+                    if !self.definitions.links.contains_key(&label) {
+                        markup.push_str(&text_markup(&text));
+                        continue;
+                    }
+                    format!(
+                        "`{} <{}_>`__",
+                        escape_interpreted(text.trim())
+                            .replace(char::is_whitespace, &BREAK.to_string()),
+                        escape_interpreted(&label)
+                    )
+                }
+                Inline::Autolink(url) => link("", url),
+                Inline::Image(image) => self.image_substitution(image),
+                Inline::FootnoteReference(label) => {
+                    if !self.definitions.footnotes.contains_key(label.as_str()) {
+                        continue;
+                    }
+                    format!("[#{}]_", footnote_label(label))
+                }
+                Inline::Html(_) | Inline::Empty => continue,
+            };
+
+            if needs_separator_before(&markup) {
+                markup.push_str("\\ ");
+            }
+            markup.push_str(&item);
+            if needs_separator_after(inlines.get(index + 1)) {
+                markup.push_str("\\ ");
+            }
+        }
+        markup
+    }
+
+    /// Substitution reference of an inline image; the definition is printed
+    /// after the block.
+    fn image_substitution(&mut self, image: &Image) -> String {
+        self.image_count += 1;
+        let name = format!("image{}", self.image_count);
+        self.substitutions
+            .extend(image_directive(Some(&name), image));
+        format!("|{name}|")
+    }
+}
@@ -0,0 +1,257 @@
+use super::{align, display_width, fill, hang, indent};
+use crate::ast::{alert_title, Definitions};
+use markdown_ppp::ast::{Block, Document, Inline, List, ListKind, Table, TaskState};
+use std::collections::HashMap;
+
+/// Options of the plain-text printer.
+pub struct Config {
+    /// Maximum line width
+    pub width: usize,
+    /// Marker of bullet list items
+    pub bullet: String,
+    /// Number links and list their URLs after the text
+    pub link_footnotes: bool,
+}
+
+/// Numbered note printed after the text.
+enum Note<'a> {
+    Url(String),
+    Footnote(&'a [Block]),
+}
+
+struct Printer<'a> {
+    config: &'a Config,
+    definitions: Definitions<'a>,
+    notes: Vec<Note<'a>>,
+    /// Note numbers of footnotes that were already referenced
+    footnote_numbers: HashMap<String, usize>,
+}
+
+/// Render the document as plain text without any Markdown syntax.
+pub fn render_text(document: &Document, config: &Config) -> String {
+    let mut printer = Printer {
+        config,
+        definitions: Definitions::collect(document),
+        notes: Vec::new(),
+        footnote_numbers: HashMap::new(),
+    };
+
+    let mut lines = printer.blocks(&document.blocks, config.width);
+
+    // Footnotes may reference further notes, so the list can grow while printing
+    let mut index = 0;
+    while index < printer.notes.len() {
+        if index == 0 && !lines.is_empty() {
+            lines.push(String::new());
+        }
+        let marker = format!("[{}] ", index + 1);
+        let note = match printer.notes[index] {
+            Note::Url(ref url) => vec![url.clone()],
+            Note::Footnote(blocks) => {
+                printer.blocks(blocks, config.width.saturating_sub(marker.len()))
+            }
+        };
+        lines.extend(hang(note, &marker));
+        index += 1;
+    }
+
+    lines.join("\n")
+}
+
+impl<'a> Printer<'a> {
+    /// Lines of a sequence of blocks separated by blank lines.
+    fn blocks(&mut self, blocks: &'a [Block], width: usize) -> Vec<String> {
+        self.joined_blocks(blocks, width, true)
+    }
+
+    fn joined_blocks(
+        &mut self,
+        blocks: &'a [Block],
+        width: usize,
+        blank_lines: bool,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+        for block in blocks {
+            let block_lines = self.block(block, width);
+            if block_lines.is_empty() {
+                continue;
+            }
+            if blank_lines && !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(block_lines);
+        }
+        lines
+    }
+
+    fn block(&mut self, block: &'a Block, width: usize) -> Vec<String> {
+        match block {
+            Block::Paragraph(inlines) => wrap(&self.inlines(inlines), width),
+            Block::Heading(heading) => wrap(&self.inlines(&heading.content), width),
+            Block::ThematicBreak => vec!["* * *".to_string()],
+            Block::BlockQuote(blocks) => indent(self.blocks(blocks, width.saturating_sub(4)), 4),
+            Block::List(list) => self.list(list, width),
+            Block::CodeBlock(code) => indent(
+                code.literal
+                    .trim_end_matches('\n')
+                    .lines()
+                    .map(str::to_string)
+                    .collect(),
+                4,
+            ),
+            Block::Table(table) => self.table(table),
+            Block::GitHubAlert(alert) => {
+                let mut lines = vec![format!("{}:", alert_title(&alert.alert_type))];
+                lines.extend(indent(
+                    self.blocks(&alert.blocks, width.saturating_sub(4)),
+                    4,
+                ));
+                lines
+            }
+            // HTML is dropped, footnotes are printed as notes after the text
+            Block::HtmlBlock(_)
+            | Block::Definition(_)
+            | Block::FootnoteDefinition(_)
+            | Block::Empty => Vec::new(),
+        }
+    }
+
+    fn list(&mut self, list: &'a List, width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (index, item) in list.items.iter().enumerate() {
+            let mut marker = match &list.kind {
+                ListKind::Bullet(_) => self.config.bullet.clone(),
+                ListKind::Ordered(options) => format!("{}.", options.start + index as u64),
+            };
+            marker.push(' ');
+            match item.task {
+                Some(TaskState::Complete) => marker.push_str("[x] "),
+                Some(TaskState::Incomplete) => marker.push_str("[ ] "),
+                None => {}
+            }
+
+            let content_width = width.saturating_sub(marker.chars().count());
+            // Blocks of an item are kept together to keep lists compact
+            let item_lines = self.joined_blocks(&item.blocks, content_width, false);
+            lines.extend(hang(item_lines, &marker));
+        }
+        lines
+    }
+
+    fn table(&mut self, table: &'a Table) -> Vec<String> {
+        let rows: Vec<Vec<String>> = table
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| self.inlines(cell).replace('\n', " ").trim().to_string())
+                    .collect()
+            })
+            .collect();
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                rows.iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| display_width(cell))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let format_row = |row: &[String]| {
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(column, width)| {
+                    let cell = row.get(column).map_or("", String::as_str);
+                    let alignment = table.alignments.get(column).copied().unwrap_or_default();
+                    align(cell, *width, alignment)
+                })
+                .collect();
+            cells.join("  ").trim_end().to_string()
+        };
+
+        let mut lines = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            lines.push(format_row(row));
+            if index == 0 {
+                let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+                lines.push(rule.join("  "));
+            }
+        }
+        lines
+    }
+
+    /// Text of inline content; hard line breaks are kept as `\n`.
+    fn inlines(&mut self, inlines: &'a [Inline]) -> String {
+        let mut text = String::new();
+        for inline in inlines {
+            match inline {
+                // Soft line breaks are re-wrapped
+                Inline::Text(value) | Inline::Code(value) => {
+                    text.push_str(&value.replace('\n', " "))
+                }
+                Inline::Autolink(url) => text.push_str(url),
+                Inline::LineBreak => text.push('\n'),
+                Inline::Link(link) => {
+                    text.push_str(&self.inlines(&link.children));
+                    self.link_note(&mut text, &link.destination);
+                }
+                Inline::LinkReference(link) => {
+                    text.push_str(&self.inlines(&link.text));
+                    if let Some(destination) = self.definitions.link_destination(&link.label) {
+                        self.link_note(&mut text, destination);
+                    }
+                }
+                Inline::Image(image) => {
+                    text.push_str(&image.alt);
+                    self.link_note(&mut text, &image.destination);
+                }
+                Inline::Emphasis(children)
+                | Inline::Strong(children)
+                | Inline::Strikethrough(children) => text.push_str(&self.inlines(children)),
+                Inline::FootnoteReference(label) => {
+                    if let Some(number) = self.footnote_number(label) {
+                        text.push_str(&format!("[{number}]"));
+                    }
+                }
+                Inline::Html(_) | Inline::Empty => {}
+            }
+        }
+        text
+    }
+
+    /// Append a note marker for the URL if link footnotes are enabled.
+    fn link_note(&mut self, text: &mut String, url: &str) {
+        if !self.config.link_footnotes || url.is_empty() || shows_url(text, url) {
+            return;
+        }
+        self.notes.push(Note::Url(url.to_string()));
+        text.push_str(&format!("[{}]", self.notes.len()));
+    }
+
+    fn footnote_number(&mut self, label: &str) -> Option<usize> {
+        if let Some(number) = self.footnote_numbers.get(label) {
+            return Some(*number);
+        }
+        let footnote = self.definitions.footnotes.get(label)?;
+        self.notes.push(Note::Footnote(&footnote.blocks));
+        let number = self.notes.len();
+        self.footnote_numbers.insert(label.to_string(), number);
+        Some(number)
+    }
+}
+
+/// Whether the link text already shows the URL, as in `[https://x](https://x)`.
+fn shows_url(text: &str, url: &str) -> bool {
+    text.ends_with(url) || text.ends_with(url.trim_start_matches("mailto:"))
+}
+
+/// Greedy word wrap; `\n` in the text forces a line break.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    text.split('\n')
+        .flat_map(|segment| fill(segment.split_whitespace(), width))
+        .collect()
+}
@@ -0,0 +1,399 @@
+use super::{hang, indent, wrap_markup, BREAK};
+use crate::ast::{alert_title, Definitions};
+use markdown_ppp::ast::{
+    Alignment, Block, CodeBlockKind, Document, GitHubAlertType, Heading, Inline, List, ListKind,
+    Table, TaskState,
+};
+use std::collections::HashSet;
+
+#[derive(Clone, Copy)]
+pub enum TableStyle {
+    /// Lines around every cell
+    Grid,
+    /// Horizontal rules above and below the table and under the header
+    Booktabs,
+    /// No lines
+    Plain,
+}
+
+#[derive(Clone, Copy)]
+pub enum CodeStyle {
+    /// Raw block
+    Raw,
+    /// Raw block in a shaded box
+    Block,
+}
+
+/// Options of the Typst printer.
+pub struct Config {
+    /// Maximum line width of paragraphs
+    pub width: usize,
+    pub table_style: TableStyle,
+    pub code_style: CodeStyle,
+}
+
+struct Printer<'a> {
+    config: &'a Config,
+    definitions: Definitions<'a>,
+    /// Footnotes already printed, later references point to their label
+    printed_footnotes: HashSet<String>,
+}
+
+/// Render the document as Typst markup.
+pub fn render_typst(document: &Document, config: &Config) -> String {
+    let mut printer = Printer {
+        config,
+        definitions: Definitions::collect(document),
+        printed_footnotes: HashSet::new(),
+    };
+    printer.blocks(&document.blocks, config.width).join("\n")
+}
+
+/// Escape text for Typst markup.
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | '*' | '_' | '`' | '$' | '#' | '[' | ']' | '<' | '>' | '@' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            // `//` and `/*` start comments
+            '/' if matches!(chars.peek(), Some('/') | Some('*')) => escaped.push_str("\\/"),
+            // `--` and `---` are dashes, `-?` is a soft hyphen
+            '-' if matches!(chars.peek(), Some('-') | Some('?')) => escaped.push_str("\\-"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Typst string literal.
+pub fn string_literal(text: &str) -> String {
+    let mut literal = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Escape a word at the start of a line that Typst would read as a list,
+/// enumeration, term or heading marker.
+fn escape_line_start(line: String) -> String {
+    let first_word = line.split(' ').next().unwrap_or_default();
+    let is_marker = matches!(first_word, "-" | "+" | "/")
+        || (!first_word.is_empty() && first_word.chars().all(|c| c == '='))
+        || first_word
+            .strip_suffix('.')
+            .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+
+    if !is_marker {
+        return line;
+    }
+    match first_word.strip_suffix('.') {
+        Some(number) if first_word != "." && number.chars().all(|c| c.is_ascii_digit()) => {
+            format!("{number}\\.{}", &line[first_word.len()..])
+        }
+        _ => format!("\\{line}"),
+    }
+}
+
+/// Label usable as a Typst `<label>`.
+fn footnote_label(label: &str) -> String {
+    let label: String = label
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("fn-{label}")
+}
+
+fn alignment(alignment: &Alignment) -> &'static str {
+    match alignment {
+        Alignment::None => "auto",
+        Alignment::Left => "left",
+        Alignment::Center => "center",
+        Alignment::Right => "right",
+    }
+}
+
+/// Border color of the callout box of a GitHub alert.
+fn alert_color(alert_type: &GitHubAlertType) -> &'static str {
+    match alert_type {
+        GitHubAlertType::Note => "#0969da",
+        GitHubAlertType::Tip => "#1a7f37",
+        GitHubAlertType::Important => "#8250df",
+        GitHubAlertType::Warning => "#9a6700",
+        GitHubAlertType::Caution => "#d1242f",
+    }
+}
+
+/// Shortest backtick fence longer than any backtick run in the code.
+fn fence(code: &str) -> String {
+    let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+impl<'a> Printer<'a> {
+    /// Lines of a sequence of blocks separated by blank lines.
+    fn blocks(&mut self, blocks: &'a [Block], width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for block in blocks {
+            let block_lines = self.block(block, width);
+            if block_lines.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(block_lines);
+        }
+        lines
+    }
+
+    fn block(&mut self, block: &'a Block, width: usize) -> Vec<String> {
+        match block {
+            Block::Paragraph(inlines) => self.paragraph(inlines, width),
+            Block::Heading(heading) => vec![self.heading(heading)],
+            Block::ThematicBreak => vec!["#line(length: 100%)".to_string()],
+            Block::BlockQuote(blocks) => {
+                let mut lines = vec!["#quote(block: true)[".to_string()];
+                lines.extend(indent(self.blocks(blocks, width.saturating_sub(2)), 2));
+                lines.push("]".to_string());
+                lines
+            }
+            Block::List(list) => self.list(list, width),
+            Block::CodeBlock(code) => {
+                let language = match &code.kind {
+                    CodeBlockKind::Fenced { info: Some(info) } => {
+                        info.split_whitespace().next().unwrap_or_default()
+                    }
+                    _ => "",
+                };
+                let literal = code.literal.trim_end_matches('\n');
+                let fence = fence(literal);
+                let mut lines = vec![format!("{fence}{language}")];
+                lines.extend(literal.lines().map(str::to_string));
+                lines.push(fence);
+
+                match self.config.code_style {
+                    CodeStyle::Raw => lines,
+                    CodeStyle::Block => {
+                        let mut boxed = vec![
+                            "#block(fill: luma(245), inset: 8pt, radius: 4pt, width: 100%)["
+                                .to_string(),
+                        ];
+                        boxed.extend(lines);
+                        boxed.push("]".to_string());
+                        boxed
+                    }
+                }
+            }
+            Block::Table(table) => self.table(table),
+            Block::GitHubAlert(alert) => {
+                let color = alert_color(&alert.alert_type);
+                let mut lines = vec![
+                    format!(
+                        "#block(width: 100%, inset: 8pt, radius: 2pt, fill: rgb(\"{color}\").lighten(90%), stroke: (left: 3pt + rgb(\"{color}\")))["
+                    ),
+                    format!("  #strong[{}]", alert_title(&alert.alert_type)),
+                    String::new(),
+                ];
+                lines.extend(indent(
+                    self.blocks(&alert.blocks, width.saturating_sub(2)),
+                    2,
+                ));
+                lines.push("]".to_string());
+                lines
+            }
+            // Typst can't render HTML; footnotes are printed where they are referenced
+            Block::HtmlBlock(_)
+            | Block::Definition(_)
+            | Block::FootnoteDefinition(_)
+            | Block::Empty => Vec::new(),
+        }
+    }
+
+    fn paragraph(&mut self, inlines: &'a [Inline], width: usize) -> Vec<String> {
+        wrap_markup(&self.inlines(inlines), width)
+            .into_iter()
+            .map(escape_line_start)
+            .collect()
+    }
+
+    fn heading(&mut self, heading: &'a Heading) -> String {
+        let level = crate::ast::heading_level(heading) as usize;
+        let content = self.inlines(&heading.content).replace(['\n', BREAK], " ");
+        format!("{} {}", "=".repeat(level), content.trim())
+    }
+
+    fn list(&mut self, list: &'a List, width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (index, item) in list.items.iter().enumerate() {
+            let mut marker = match &list.kind {
+                ListKind::Bullet(_) => "- ".to_string(),
+                ListKind::Ordered(options) => format!("{}. ", options.start + index as u64),
+            };
+            match item.task {
+                Some(TaskState::Complete) => marker.push_str("☑ "),
+                Some(TaskState::Incomplete) => marker.push_str("☐ "),
+                None => {}
+            }
+
+            let content_width = width.saturating_sub(marker.chars().count());
+            // Nested lists follow the item text directly to keep the list tight
+            let mut item_lines: Vec<String> = Vec::new();
+            for block in &item.blocks {
+                let block_lines = self.block(block, content_width);
+                if block_lines.is_empty() {
+                    continue;
+                }
+                if !item_lines.is_empty() && !matches!(block, Block::List(_)) {
+                    item_lines.push(String::new());
+                }
+                item_lines.extend(block_lines);
+            }
+            lines.extend(hang(item_lines, &marker));
+        }
+        lines
+    }
+
+    fn table(&mut self, table: &'a Table) -> Vec<String> {
+        let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let alignments: Vec<&str> = (0..columns)
+            .map(|column| table.alignments.get(column).map_or("auto", alignment))
+            .collect();
+
+        let mut lines = vec!["#table(".to_string()];
+        lines.push(format!("  columns: {columns},"));
+        // A single-element array needs a trailing comma
+        let comma = if columns == 1 { "," } else { "" };
+        lines.push(format!("  align: ({}{comma}),", alignments.join(", ")));
+        match self.config.table_style {
+            TableStyle::Grid => {}
+            TableStyle::Booktabs | TableStyle::Plain => lines.push("  stroke: none,".to_string()),
+        }
+        let rule = matches!(self.config.table_style, TableStyle::Booktabs);
+
+        for (index, row) in table.rows.iter().enumerate() {
+            if index == 0 && rule {
+                lines.push("  table.hline(),".to_string());
+            }
+            let cells: Vec<String> = (0..columns)
+                .map(|column| {
+                    let content = row
+                        .get(column)
+                        .map(|cell| self.inlines(cell))
+                        .unwrap_or_default();
+                    format!("[{}]", content.replace(['\n', BREAK], " ").trim())
+                })
+                .collect();
+            if index == 0 {
+                lines.push(format!("  table.header({}),", cells.join(", ")));
+                if rule {
+                    lines.push("  table.hline(),".to_string());
+                }
+            } else {
+                lines.push(format!("  {},", cells.join(", ")));
+            }
+        }
+        if rule {
+            lines.push("  table.hline(),".to_string());
+        }
+        lines.push(")".to_string());
+        lines
+    }
+
+    /// Markup of inline content; spaces that may be broken are [`BREAK`] and
+    /// hard line breaks are `\n`.
+    fn inlines(&mut self, inlines: &'a [Inline]) -> String {
+        let mut markup = String::new();
+        for inline in inlines {
+            match inline {
+                Inline::Text(text) => {
+                    let text = escape_text(text);
+                    markup.extend(
+                        text.chars()
+                            .map(|c| if c.is_whitespace() { BREAK } else { c }),
+                    );
+                }
+                Inline::LineBreak => markup.push_str(" \\\n"),
+                Inline::Code(code) => {
+                    if code.contains('`') || code.contains('\n') {
+                        markup.push_str(&format!("#raw({})", string_literal(code)));
+                    } else {
+                        markup.push_str(&format!("`{code}`"));
+                    }
+                }
+                Inline::Html(_) | Inline::Empty => {}
+                Inline::Link(link) => {
+                    let text = self.inlines(&link.children);
+                    markup.push_str(&format!(
+                        "#link({})[{text}]",
+                        string_literal(&link.destination)
+                    ));
+                }
+                Inline::LinkReference(reference) => {
+                    let text = self.inlines(&reference.text);
+                    match self.definitions.link_destination(&reference.label) {
+                        Some(destination) => markup
+                            .push_str(&format!("#link({})[{text}]", string_literal(destination))),
+                        None => markup.push_str(&text),
+                    }
+                }
+                Inline::Image(image) => {
+                    markup.push_str(&format!("#image({}", string_literal(&image.destination)));
+                    if !image.alt.is_empty() {
+                        markup.push_str(&format!(", alt: {}", string_literal(&image.alt)));
+                    }
+                    markup.push(')');
+                }
+                Inline::Emphasis(children) => {
+                    markup.push_str(&format!("#emph[{}]", self.inlines(children)))
+                }
+                Inline::Strong(children) => {
+                    markup.push_str(&format!("#strong[{}]", self.inlines(children)))
+                }
+                Inline::Strikethrough(children) => {
+                    markup.push_str(&format!("#strike[{}]", self.inlines(children)))
+                }
+                Inline::Autolink(url) => {
+                    markup.push_str(&format!("#link({})", string_literal(url)))
+                }
+                Inline::FootnoteReference(label) => markup.push_str(&self.footnote(label)),
+            }
+        }
+        markup
+    }
+
+    /// Footnote with its content at the first reference, a reference to the
+    /// labelled footnote afterwards.
+    fn footnote(&mut self, label: &str) -> String {
+        let typst_label = footnote_label(label);
+        if self.printed_footnotes.contains(label) {
+            return format!("#footnote(<{typst_label}>)");
+        }
+        let Some(definition) = self.definitions.footnotes.get(label) else {
+            return String::new();
+        };
+        self.printed_footnotes.insert(label.to_string());
+
+        let content = self
+            .blocks(&definition.blocks, usize::MAX)
+            .join("\n")
+            .replace(BREAK, " ");
+        format!("#footnote[{}]<{typst_label}>", content.trim())
+    }
+}
@@ -0,0 +1,294 @@
+//! Selectors over the document tree, like `section[title=Installation] link`.
+//!
+//! Headings open sections: a section contains its heading and the blocks after
+//! it up to the next heading of the same or a higher level, so the blocks of a
+//! section are its children.
+
+pub mod selector;
+
+use crate::ast::{block_text, heading_level, inline_text, CodeInfo};
+use crate::toc::slugify;
+use markdown_ppp::ast::{Block, Document, Heading, Inline, List, ListItem, ListKind, TaskState};
+pub use selector::Selector;
+use selector::{Combinator, Compound, Step};
+use serde::{Serialize, Serializer};
+
+/// Node of the document tree a selector can match.
+#[derive(Clone, Copy)]
+pub enum Node<'a> {
+    /// Heading together with the blocks of its section, the heading included
+    Section(&'a Heading, &'a [Block]),
+    Block(&'a Block),
+    ListItem(&'a List, &'a ListItem),
+    Inline(&'a Inline),
+}
+
+impl<'a> Node<'a> {
+    /// Kind named in selectors, see [`selector::KINDS`].
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Node::Section(..) => "section",
+            Node::ListItem(..) => "list-item",
+            Node::Block(block) => match block {
+                Block::Paragraph(_) => "paragraph",
+                Block::Heading(_) => "heading",
+                Block::ThematicBreak => "thematic-break",
+                Block::BlockQuote(_) => "block-quote",
+                Block::List(_) => "list",
+                Block::CodeBlock(_) => "code-block",
+                Block::HtmlBlock(_) => "html-block",
+                Block::Definition(_) => "definition",
+                Block::Table(_) => "table",
+                Block::FootnoteDefinition(_) => "footnote-definition",
+                Block::GitHubAlert(_) => "github-alert",
+                Block::Empty => "empty",
+            },
+            Node::Inline(inline) => match inline {
+                Inline::Text(_) => "text",
+                Inline::LineBreak => "line-break",
+                Inline::Code(_) => "code",
+                Inline::Html(_) => "html",
+                Inline::Link(_) => "link",
+                Inline::LinkReference(_) => "link-reference",
+                Inline::Image(_) => "image",
+                Inline::Emphasis(_) => "emphasis",
+                Inline::Strong(_) => "strong",
+                Inline::Strikethrough(_) => "strikethrough",
+                Inline::Autolink(_) => "autolink",
+                Inline::FootnoteReference(_) => "footnote-reference",
+                Inline::Empty => "empty",
+            },
+        }
+    }
+
+    /// Value of an attribute used in `[name=value]` conditions. Every node has
+    /// a `text` attribute, its plain text with whitespace collapsed.
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        if name == "text" {
+            return Some(self.text().split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+
+        match (self, name) {
+            (Node::Section(heading, _), "level") => Some(heading_level(heading).to_string()),
+            (Node::Section(heading, _), "title") => Some(inline_text(&heading.content)),
+            (Node::Section(heading, _), "id") => Some(slugify(&inline_text(&heading.content))),
+            (Node::ListItem(_, item), "task") => item.task.map(|task| {
+                match task {
+                    TaskState::Complete => "complete",
+                    TaskState::Incomplete => "incomplete",
+                }
+                .to_string()
+            }),
+            (Node::Block(block), name) => block_attribute(block, name),
+            (Node::Inline(inline), name) => inline_attribute(inline, name),
+            _ => None,
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Node::Section(_, blocks) => block_text(blocks),
+            Node::Block(block) => block_text(std::slice::from_ref(block)),
+            Node::ListItem(_, item) => block_text(&item.blocks),
+            Node::Inline(inline) => inline_text(std::slice::from_ref(inline)),
+        }
+    }
+}
+
+/// AST JSON of the node; a section is the array of its blocks.
+impl Serialize for Node<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Node::Section(_, blocks) => blocks.serialize(serializer),
+            Node::Block(block) => block.serialize(serializer),
+            Node::ListItem(_, item) => item.serialize(serializer),
+            Node::Inline(inline) => inline.serialize(serializer),
+        }
+    }
+}
+
+fn block_attribute(block: &Block, name: &str) -> Option<String> {
+    match (block, name) {
+        (Block::Heading(heading), "level") => Some(heading_level(heading).to_string()),
+        (Block::Heading(heading), "id") => Some(slugify(&inline_text(&heading.content))),
+        (Block::List(list), "ordered") => {
+            Some(matches!(list.kind, ListKind::Ordered(_)).to_string())
+        }
+        (
+            Block::List(List {
+                kind: ListKind::Ordered(options),
+                ..
+            }),
+            "start",
+        ) => Some(options.start.to_string()),
+        (Block::CodeBlock(code_block), name) => {
+            let info = CodeInfo::parse(&code_block.kind);
+            match name {
+                "lang" => info.language,
+                "info" => match &code_block.kind {
+                    markdown_ppp::ast::CodeBlockKind::Fenced { info } => info.clone(),
+                    markdown_ppp::ast::CodeBlockKind::Indented => None,
+                },
+                name => info.attribute(name).map(str::to_string),
+            }
+        }
+        (Block::Definition(definition), "label") => Some(inline_text(&definition.label)),
+        (Block::Definition(definition), "href") => Some(definition.destination.clone()),
+        (Block::Definition(definition), "title") => definition.title.clone(),
+        (Block::Table(table), "columns") => Some(table.alignments.len().to_string()),
+        (Block::FootnoteDefinition(footnote), "label") => Some(footnote.label.clone()),
+        (Block::GitHubAlert(alert), "type") => {
+            Some(crate::ast::alert_title(&alert.alert_type).to_lowercase())
+        }
+        _ => None,
+    }
+}
+
+fn inline_attribute(inline: &Inline, name: &str) -> Option<String> {
+    match (inline, name) {
+        (Inline::Link(link), "href") => Some(link.destination.clone()),
+        (Inline::Link(link), "title") => link.title.clone(),
+        (Inline::Autolink(destination), "href") => Some(destination.clone()),
+        (Inline::LinkReference(link), "label") => Some(inline_text(&link.label)),
+        (Inline::Image(image), "src") => Some(image.destination.clone()),
+        (Inline::Image(image), "alt") => Some(image.alt.clone()),
+        (Inline::Image(image), "title") => image.title.clone(),
+        (Inline::FootnoteReference(label), "label") => Some(label.clone()),
+        _ => None,
+    }
+}
+
+/// Nodes of a document in document order, each with the index of its parent.
+struct Tree<'a> {
+    nodes: Vec<(Node<'a>, Option<usize>)>,
+}
+
+impl<'a> Tree<'a> {
+    fn new(document: &'a Document) -> Self {
+        let mut tree = Self { nodes: Vec::new() };
+        tree.add_blocks(&document.blocks, None);
+        tree
+    }
+
+    fn push(&mut self, node: Node<'a>, parent: Option<usize>) -> usize {
+        self.nodes.push((node, parent));
+        self.nodes.len() - 1
+    }
+
+    fn add_blocks(&mut self, blocks: &'a [Block], parent: Option<usize>) {
+        // Open sections as (level, node index), innermost last
+        let mut sections: Vec<(u8, usize)> = Vec::new();
+        for (index, block) in blocks.iter().enumerate() {
+            if let Block::Heading(heading) = block {
+                let level = heading_level(heading);
+                while sections.last().is_some_and(|(open, _)| *open >= level) {
+                    sections.pop();
+                }
+                let end = blocks[index + 1..]
+                    .iter()
+                    .position(|block| {
+                        matches!(block, Block::Heading(next) if heading_level(next) <= level)
+                    })
+                    .map_or(blocks.len(), |position| index + 1 + position);
+                let section_parent = sections.last().map(|(_, node)| *node).or(parent);
+                let node = self.push(Node::Section(heading, &blocks[index..end]), section_parent);
+                sections.push((level, node));
+            }
+
+            let block_parent = sections.last().map(|(_, node)| *node).or(parent);
+            self.add_block(block, block_parent);
+        }
+    }
+
+    fn add_block(&mut self, block: &'a Block, parent: Option<usize>) {
+        let node = Some(self.push(Node::Block(block), parent));
+        match block {
+            Block::Paragraph(inlines) => self.add_inlines(inlines, node),
+            Block::Heading(heading) => self.add_inlines(&heading.content, node),
+            Block::BlockQuote(blocks) => self.add_blocks(blocks, node),
+            Block::List(list) => {
+                for item in &list.items {
+                    let item_node = Some(self.push(Node::ListItem(list, item), node));
+                    self.add_blocks(&item.blocks, item_node);
+                }
+            }
+            Block::Table(table) => {
+                for cell in table.rows.iter().flatten() {
+                    self.add_inlines(cell, node);
+                }
+            }
+            Block::FootnoteDefinition(footnote) => self.add_blocks(&footnote.blocks, node),
+            Block::GitHubAlert(alert) => self.add_blocks(&alert.blocks, node),
+            Block::ThematicBreak
+            | Block::CodeBlock(_)
+            | Block::HtmlBlock(_)
+            | Block::Definition(_)
+            | Block::Empty => {}
+        }
+    }
+
+    fn add_inlines(&mut self, inlines: &'a [Inline], parent: Option<usize>) {
+        for inline in inlines {
+            let node = Some(self.push(Node::Inline(inline), parent));
+            match inline {
+                Inline::Link(link) => self.add_inlines(&link.children, node),
+                Inline::LinkReference(link) => self.add_inlines(&link.text, node),
+                Inline::Emphasis(children)
+                | Inline::Strong(children)
+                | Inline::Strikethrough(children) => self.add_inlines(children, node),
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether the node at `index` matches the steps, the last step matching
+    /// the node itself.
+    fn matches(&self, steps: &[Step], index: usize) -> bool {
+        let Some((step, before)) = steps.split_last() else {
+            return true;
+        };
+        let (node, parent) = &self.nodes[index];
+        if !compound_matches(&step.compound, node) {
+            return false;
+        }
+        if before.is_empty() {
+            return true;
+        }
+
+        match step.combinator {
+            Combinator::Child => parent.is_some_and(|parent| self.matches(before, parent)),
+            Combinator::Descendant => {
+                let mut ancestor = *parent;
+                while let Some(index) = ancestor {
+                    if self.matches(before, index) {
+                        return true;
+                    }
+                    ancestor = self.nodes[index].1;
+                }
+                false
+            }
+        }
+    }
+}
+
+fn compound_matches(compound: &Compound, node: &Node) -> bool {
+    compound.kind.map_or(true, |kind| kind == node.kind())
+        && compound
+            .filters
+            .iter()
+            .all(|filter| filter.matches(node.attribute(&filter.name).as_deref()))
+}
+
+/// Nodes of the document matched by the selector, in document order.
+pub fn select<'a>(document: &'a Document, selector: &Selector) -> Vec<Node<'a>> {
+    let tree = Tree::new(document);
+    (0..tree.nodes.len())
+        .filter(|index| {
+            selector
+                .alternatives
+                .iter()
+                .any(|steps| tree.matches(steps, *index))
+        })
+        .map(|index| tree.nodes[index].0)
+        .collect()
+}
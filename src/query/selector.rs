@@ -0,0 +1,350 @@
+use core::str::FromStr;
+
+/// Node kinds a selector can name, besides `*` and the `h1` to `h6` shorthands.
+pub const KINDS: &[&str] = &[
+    "section",
+    "paragraph",
+    "heading",
+    "thematic-break",
+    "block-quote",
+    "list",
+    "list-item",
+    "code-block",
+    "html-block",
+    "definition",
+    "table",
+    "footnote-definition",
+    "github-alert",
+    "text",
+    "line-break",
+    "code",
+    "html",
+    "link",
+    "link-reference",
+    "image",
+    "emphasis",
+    "strong",
+    "strikethrough",
+    "autolink",
+    "footnote-reference",
+];
+
+/// Comma-separated list of selectors; a node is selected if any of them matches.
+#[derive(Clone, Debug)]
+pub struct Selector {
+    pub alternatives: Vec<Vec<Step>>,
+}
+
+/// Compound selector together with its relation to the step before it.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub combinator: Combinator,
+    pub compound: Compound,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Combinator {
+    /// `a b`: b is anywhere inside a
+    Descendant,
+    /// `a > b`: b is directly inside a
+    Child,
+}
+
+/// Node kind and attribute conditions, e.g. `code-block[lang=sql]`.
+#[derive(Clone, Debug)]
+pub struct Compound {
+    /// `None` for `*`
+    pub kind: Option<&'static str>,
+    pub filters: Vec<Filter>,
+}
+
+/// Attribute condition like `[level<=2]` or `[text^="Install" i]`.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    pub name: String,
+    pub operator: Operator,
+    pub value: String,
+    pub ignore_case: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    /// `[name]`
+    Exists,
+    /// `[name=value]`
+    Equals,
+    /// `[name!=value]`, also true if the attribute is missing
+    NotEquals,
+    /// `[name^=value]`
+    StartsWith,
+    /// `[name$=value]`
+    EndsWith,
+    /// `[name*=value]`
+    Contains,
+    /// `[name~=value]`: one of the whitespace-separated words
+    HasWord,
+    /// `[name<value]` and the other numeric comparisons
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Filter {
+    /// Whether an attribute with the given value, if any, passes the condition.
+    pub fn matches(&self, attribute: Option<&str>) -> bool {
+        let Some(attribute) = attribute else {
+            return self.operator == Operator::NotEquals;
+        };
+        let (attribute, value) = if self.ignore_case {
+            (attribute.to_lowercase(), self.value.to_lowercase())
+        } else {
+            (attribute.to_string(), self.value.clone())
+        };
+        let number = |text: &str| text.trim().parse::<f64>().ok();
+        let compare = |accept: fn(f64, f64) -> bool| match (number(&attribute), number(&value)) {
+            (Some(attribute), Some(value)) => accept(attribute, value),
+            _ => false,
+        };
+
+        match self.operator {
+            Operator::Exists => true,
+            Operator::Equals => attribute == value,
+            Operator::NotEquals => attribute != value,
+            Operator::StartsWith => attribute.starts_with(&value),
+            Operator::EndsWith => attribute.ends_with(&value),
+            Operator::Contains => attribute.contains(&value),
+            Operator::HasWord => attribute.split_whitespace().any(|word| word == value),
+            Operator::Less => compare(|a, b| a < b),
+            Operator::LessOrEqual => compare(|a, b| a <= b),
+            Operator::Greater => compare(|a, b| a > b),
+            Operator::GreaterOrEqual => compare(|a, b| a >= b),
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s,
+            position: 0,
+        };
+        parser
+            .selector()
+            .map_err(|message| format!("Invalid selector at column {}: {message}", parser.column()))
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    /// Byte offset of the next character
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn selector(&mut self) -> Result<Selector, String> {
+        let mut alternatives = vec![self.complex()?];
+        while self.eat(',') {
+            alternatives.push(self.complex()?);
+        }
+        match self.peek() {
+            None => Ok(Selector { alternatives }),
+            Some(c) => Err(format!("unexpected '{c}'")),
+        }
+    }
+
+    fn complex(&mut self) -> Result<Vec<Step>, String> {
+        self.skip_whitespace();
+        let mut steps = vec![Step {
+            combinator: Combinator::Descendant,
+            compound: self.compound()?,
+        }];
+        loop {
+            let had_whitespace = self.skip_whitespace();
+            let combinator = match self.peek() {
+                Some('>') => {
+                    self.position += 1;
+                    self.skip_whitespace();
+                    Combinator::Child
+                }
+                Some(',') | None => break,
+                Some(_) if had_whitespace => Combinator::Descendant,
+                Some(c) => return Err(format!("unexpected '{c}'")),
+            };
+            steps.push(Step {
+                combinator,
+                compound: self.compound()?,
+            });
+        }
+        Ok(steps)
+    }
+
+    fn compound(&mut self) -> Result<Compound, String> {
+        let mut compound = Compound {
+            kind: None,
+            filters: Vec::new(),
+        };
+        let start = self.position;
+        if !self.eat('*') {
+            let name = self.identifier();
+            if let Some(level) = heading_shorthand(name) {
+                compound.kind = Some("heading");
+                compound.filters.push(Filter {
+                    name: "level".to_string(),
+                    operator: Operator::Equals,
+                    value: level.to_string(),
+                    ignore_case: false,
+                });
+            } else if !name.is_empty() {
+                let Some(kind) = KINDS.iter().find(|kind| **kind == name) else {
+                    self.position = start;
+                    return Err(format!(
+                        "unknown node kind '{name}', expected one of: {}, h1-h6",
+                        KINDS.join(", ")
+                    ));
+                };
+                compound.kind = Some(kind);
+            }
+        }
+
+        while self.eat('[') {
+            compound.filters.push(self.filter()?);
+        }
+        if self.position == start {
+            return Err(match self.peek() {
+                Some(c) => format!("expected a node kind, '*' or '[' but found '{c}'"),
+                None => "expected a node kind, '*' or '['".to_string(),
+            });
+        }
+        Ok(compound)
+    }
+
+    /// Attribute condition after the opening `[`.
+    fn filter(&mut self) -> Result<Filter, String> {
+        self.skip_whitespace();
+        let name = self.identifier().to_string();
+        if name.is_empty() {
+            return Err("expected an attribute name".to_string());
+        }
+        self.skip_whitespace();
+
+        let operators = [
+            ("!=", Operator::NotEquals),
+            ("^=", Operator::StartsWith),
+            ("$=", Operator::EndsWith),
+            ("*=", Operator::Contains),
+            ("~=", Operator::HasWord),
+            ("<=", Operator::LessOrEqual),
+            (">=", Operator::GreaterOrEqual),
+            ("=", Operator::Equals),
+            ("<", Operator::Less),
+            (">", Operator::Greater),
+        ];
+        let rest = &self.input[self.position..];
+        let Some((token, operator)) = operators.iter().find(|(token, _)| rest.starts_with(token))
+        else {
+            return if self.eat(']') {
+                Ok(Filter {
+                    name,
+                    operator: Operator::Exists,
+                    value: String::new(),
+                    ignore_case: false,
+                })
+            } else {
+                Err(format!("expected an operator or ']' after '{name}'"))
+            };
+        };
+        self.position += token.len();
+        self.skip_whitespace();
+
+        let value = self.value()?;
+        self.skip_whitespace();
+        let ignore_case = self.eat('i') || self.eat('I');
+        self.skip_whitespace();
+        if !self.eat(']') {
+            return Err("expected ']'".to_string());
+        }
+        Ok(Filter {
+            name,
+            operator: *operator,
+            value,
+            ignore_case,
+        })
+    }
+
+    /// Quoted string, or a bare word up to whitespace or `]`.
+    fn value(&mut self) -> Result<String, String> {
+        let Some(quote) = self.peek().filter(|c| *c == '"' || *c == '\'') else {
+            let rest = &self.input[self.position..];
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == ']')
+                .unwrap_or(rest.len());
+            if end == 0 {
+                return Err("expected a value".to_string());
+            }
+            self.position += end;
+            return Ok(rest[..end].to_string());
+        };
+
+        self.position += 1;
+        let mut value = String::new();
+        let mut chars = self.input[self.position..].chars();
+        while let Some(c) = chars.next() {
+            self.position += c.len_utf8();
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        self.position += escaped.len_utf8();
+                        value.push(escaped);
+                    }
+                }
+                c if c == quote => return Ok(value),
+                c => value.push(c),
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+
+    fn identifier(&mut self) -> &'a str {
+        let rest = &self.input[self.position..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+        self.position += end;
+        &rest[..end]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Skip whitespace, returning whether there was any.
+    fn skip_whitespace(&mut self) -> bool {
+        let rest = &self.input[self.position..];
+        let skipped = rest.len() - rest.trim_start().len();
+        self.position += skipped;
+        skipped > 0
+    }
+
+    /// Column of the current position, counting characters from 1.
+    fn column(&self) -> usize {
+        self.input[..self.position].chars().count() + 1
+    }
+}
+
+/// Heading level of `h1` to `h6`.
+fn heading_shorthand(name: &str) -> Option<u8> {
+    let level = name.strip_prefix('h')?.parse::<u8>().ok()?;
+    (1..=6).contains(&level).then_some(level)
+}
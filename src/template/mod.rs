@@ -0,0 +1,379 @@
+use crate::diagnostic::SourceDiagnostic;
+use crate::input::{read_file, ParsedDocument};
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Piece of a parsed template.
+enum Node {
+    Text(String),
+    /// `$name$` or `$name.field$`
+    Variable(String),
+    /// `$if(name)$ ... $else$ ... $endif$`
+    If {
+        name: String,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    /// `$for(name)$ ... $sep$ ... $endfor$`; inside the loop `name` refers to
+    /// the current item
+    For {
+        name: String,
+        body: Vec<Node>,
+        separator: Vec<Node>,
+    },
+}
+
+/// Document template with pandoc-style `$...$` directives; `$$` is a literal `$`.
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+/// Literal text or directive between two `$` signs.
+enum Token<'a> {
+    Text(String),
+    Variable(&'a str),
+    If(&'a str),
+    Else,
+    EndIf,
+    For(&'a str),
+    Separator,
+    EndFor,
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('.').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        })
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token<'_>)>, SourceDiagnostic> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut text_start = 0;
+    let mut rest = source;
+
+    while let Some(start) = rest.find('$') {
+        let offset = source.len() - rest.len() + start;
+        text.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        if let Some(after) = rest.strip_prefix('$') {
+            text.push('$');
+            rest = after;
+            continue;
+        }
+
+        let Some(end) = rest.find('$') else {
+            return Err(SourceDiagnostic::error("Unterminated template directive")
+                .at_offset(source, offset));
+        };
+        let directive = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let token = match directive {
+            "else" => Token::Else,
+            "endif" => Token::EndIf,
+            "sep" => Token::Separator,
+            "endfor" => Token::EndFor,
+            _ => {
+                let argument = |keyword: &str| {
+                    directive
+                        .strip_prefix(keyword)?
+                        .strip_prefix('(')?
+                        .strip_suffix(')')
+                        .filter(|name| is_name(name))
+                };
+                if let Some(name) = argument("if") {
+                    Token::If(name)
+                } else if let Some(name) = argument("for") {
+                    Token::For(name)
+                } else if is_name(directive) {
+                    Token::Variable(directive)
+                } else {
+                    return Err(SourceDiagnostic::error(format!(
+                        "Invalid template directive: ${directive}$ (use $$ for a literal $)"
+                    ))
+                    .at_offset(source, offset));
+                }
+            }
+        };
+
+        if !text.is_empty() {
+            tokens.push((text_start, Token::Text(std::mem::take(&mut text))));
+        }
+        tokens.push((offset, token));
+        text_start = source.len() - rest.len();
+    }
+
+    text.push_str(rest);
+    if !text.is_empty() {
+        tokens.push((text_start, Token::Text(text)));
+    }
+    Ok(tokens)
+}
+
+/// Builds the node tree, checking that blocks are properly nested.
+struct Parser<'a> {
+    source: &'a str,
+    tokens: std::vec::IntoIter<(usize, Token<'a>)>,
+}
+
+impl Parser<'_> {
+    /// Nodes up to one of the `terminators`, which is returned as well;
+    /// `None` at the end of the template.
+    fn nodes(
+        &mut self,
+        terminators: &[&str],
+    ) -> Result<(Vec<Node>, Option<&'static str>), SourceDiagnostic> {
+        let mut nodes = Vec::new();
+
+        while let Some((offset, token)) = self.tokens.next() {
+            let keyword = match token {
+                Token::Text(text) => {
+                    nodes.push(Node::Text(text));
+                    continue;
+                }
+                Token::Variable(name) => {
+                    nodes.push(Node::Variable(name.to_string()));
+                    continue;
+                }
+                Token::If(name) => {
+                    let (then, end) = self.nodes(&["else", "endif"])?;
+                    let otherwise = match end {
+                        Some("else") => self.block_end(offset, "if", &["endif"])?,
+                        Some(_) => Vec::new(),
+                        None => return Err(self.unclosed(offset, "if", "endif")),
+                    };
+                    nodes.push(Node::If {
+                        name: name.to_string(),
+                        then,
+                        otherwise,
+                    });
+                    continue;
+                }
+                Token::For(name) => {
+                    let (body, end) = self.nodes(&["sep", "endfor"])?;
+                    let separator = match end {
+                        Some("sep") => self.block_end(offset, "for", &["endfor"])?,
+                        Some(_) => Vec::new(),
+                        None => return Err(self.unclosed(offset, "for", "endfor")),
+                    };
+                    nodes.push(Node::For {
+                        name: name.to_string(),
+                        body,
+                        separator,
+                    });
+                    continue;
+                }
+                Token::Else => "else",
+                Token::EndIf => "endif",
+                Token::Separator => "sep",
+                Token::EndFor => "endfor",
+            };
+
+            if terminators.contains(&keyword) {
+                return Ok((nodes, Some(keyword)));
+            }
+            return Err(
+                SourceDiagnostic::error(format!("Unexpected ${keyword}$ in template"))
+                    .at_offset(self.source, offset),
+            );
+        }
+
+        Ok((nodes, None))
+    }
+
+    /// Remaining nodes of a block after `$else$` or `$sep$`.
+    fn block_end(
+        &mut self,
+        start: usize,
+        block: &str,
+        terminators: &[&str],
+    ) -> Result<Vec<Node>, SourceDiagnostic> {
+        match self.nodes(terminators)? {
+            (nodes, Some(_)) => Ok(nodes),
+            (_, None) => Err(self.unclosed(start, block, terminators[0])),
+        }
+    }
+
+    fn unclosed(&self, offset: usize, block: &str, end: &str) -> SourceDiagnostic {
+        SourceDiagnostic::error(format!("${block}(...)$ without matching ${end}$"))
+            .at_offset(self.source, offset)
+    }
+}
+
+/// Whether any of the nodes refers to the variable `variable`.
+fn uses(nodes: &[Node], variable: &str) -> bool {
+    let refers = |name: &str| name.split('.').next() == Some(variable);
+    nodes.iter().any(|node| match node {
+        Node::Text(_) => false,
+        Node::Variable(name) => refers(name),
+        Node::If {
+            name,
+            then,
+            otherwise,
+        } => refers(name) || uses(then, variable) || uses(otherwise, variable),
+        Node::For {
+            name,
+            body,
+            separator,
+        } => refers(name) || uses(body, variable) || uses(separator, variable),
+    })
+}
+
+fn is_truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+        Some(Value::String(text)) => !text.is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(_) => true,
+    }
+}
+
+fn write_value(output: &mut String, value: &Value) {
+    match value {
+        Value::String(text) => output.push_str(text),
+        Value::Number(number) => output.push_str(&number.to_string()),
+        Value::Bool(true) => output.push_str("true"),
+        Value::Array(items) => items.iter().for_each(|item| write_value(output, item)),
+        Value::Bool(false) | Value::Null | Value::Object(_) => {}
+    }
+}
+
+/// Variable lookup with loop variables shadowing the outer context.
+struct Scope<'a> {
+    context: &'a Map<String, Value>,
+    loop_variables: Vec<(&'a str, &'a Value)>,
+}
+
+impl<'a> Scope<'a> {
+    fn lookup(&self, path: &str) -> Option<&'a Value> {
+        let mut parts = path.split('.');
+        let first = parts.next()?;
+        let root = self
+            .loop_variables
+            .iter()
+            .rev()
+            .find(|(name, _)| *name == first)
+            .map(|(_, value)| *value)
+            .or_else(|| self.context.get(first))?;
+        parts.try_fold(root, |value, part| value.get(part))
+    }
+
+    fn render(&mut self, nodes: &'a [Node], output: &mut String) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => output.push_str(text),
+                Node::Variable(name) => {
+                    if let Some(value) = self.lookup(name) {
+                        write_value(output, value);
+                    }
+                }
+                Node::If {
+                    name,
+                    then,
+                    otherwise,
+                } => {
+                    if is_truthy(self.lookup(name)) {
+                        self.render(then, output);
+                    } else {
+                        self.render(otherwise, output);
+                    }
+                }
+                Node::For {
+                    name,
+                    body,
+                    separator,
+                } => {
+                    let value = self.lookup(name);
+                    let items: Vec<&Value> = match value {
+                        Some(Value::Array(items)) => items.iter().collect(),
+                        value if is_truthy(value) => value.into_iter().collect(),
+                        _ => Vec::new(),
+                    };
+                    for (index, item) in items.into_iter().enumerate() {
+                        if index > 0 {
+                            self.render(separator, output);
+                        }
+                        self.loop_variables.push((name, item));
+                        self.render(body, output);
+                        self.loop_variables.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, SourceDiagnostic> {
+        let mut parser = Parser {
+            source,
+            tokens: tokenize(source)?.into_iter(),
+        };
+        let (nodes, _) = parser.nodes(&[])?;
+        Ok(Self { nodes })
+    }
+
+    /// Read and parse a template file; errors point into the file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = read_file(path)?;
+        Self::parse(&source).map_err(|diagnostic| diagnostic.with_path(Some(path)).into())
+    }
+
+    /// Whether the template refers to the variable `name` anywhere.
+    pub fn uses(&self, name: &str) -> bool {
+        uses(&self.nodes, name)
+    }
+
+    pub fn render(&self, context: &Map<String, Value>) -> String {
+        let mut output = String::new();
+        let mut scope = Scope {
+            context,
+            loop_variables: Vec::new(),
+        };
+        scope.render(&self.nodes, &mut output);
+        output
+    }
+}
+
+/// Copy of a metadata value with every string escaped for the output format.
+pub fn escape_strings(value: &Value, escape: fn(&str) -> String) -> Value {
+    match value {
+        Value::String(text) => Value::String(escape(text)),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| escape_strings(item, escape))
+            .collect(),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), escape_strings(value, escape)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Front matter fields with strings escaped for the output format.
+pub fn metadata_variables(
+    document: &ParsedDocument,
+    escape: fn(&str) -> String,
+) -> Map<String, Value> {
+    match document
+        .front_matter
+        .as_ref()
+        .map(|front_matter| &front_matter.data)
+    {
+        Some(Value::Object(fields)) => fields
+            .iter()
+            .map(|(key, value)| (key.clone(), escape_strings(value, escape)))
+            .collect(),
+        _ => Map::new(),
+    }
+}
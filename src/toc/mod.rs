@@ -0,0 +1,210 @@
+use crate::ast::{heading_level, inline_text};
+use crate::config::TocConfig;
+use markdown_ppp::ast::{
+    Block, Document, Heading, Inline, Link, List, ListBulletKind, ListItem, ListKind,
+};
+use markdown_ppp::ast_transform::{VisitWith, Visitor};
+use std::collections::HashMap;
+
+/// Marker opening the region maintained by `toc --insert` and `format`
+pub const TOC_START: &str = "<!-- toc -->";
+/// Marker closing the table of contents region
+pub const TOC_STOP: &str = "<!-- tocstop -->";
+
+/// GitHub-style anchor slug of a heading text: lowercase, punctuation removed,
+/// whitespace replaced with `-`.
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_whitespace() => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Heading of the document together with its anchor.
+pub struct TocEntry {
+    pub level: u8,
+    pub text: String,
+    pub anchor: String,
+}
+
+#[derive(Default)]
+struct HeadingCollector {
+    anchor_prefix: String,
+    seen: HashMap<String, usize>,
+    entries: Vec<TocEntry>,
+}
+
+impl Visitor for HeadingCollector {
+    fn visit_heading(&mut self, heading: &Heading) {
+        let text = inline_text(&heading.content).trim().to_string();
+        let slug = slugify(&text);
+
+        // Repeated headings get -1, -2, ... suffixes like on GitHub
+        let count = self.seen.entry(slug.clone()).or_insert(0);
+        let slug = match *count {
+            0 => slug,
+            n => format!("{slug}-{n}"),
+        };
+        *count += 1;
+
+        self.entries.push(TocEntry {
+            level: heading_level(heading),
+            text,
+            anchor: format!("{}{slug}", self.anchor_prefix),
+        });
+    }
+}
+
+/// All headings of the document in order, with unique anchors.
+pub fn collect_headings(document: &Document, anchor_prefix: &str) -> Vec<TocEntry> {
+    let mut collector = HeadingCollector {
+        anchor_prefix: anchor_prefix.to_string(),
+        ..Default::default()
+    };
+    document.visit_with(&mut collector);
+    collector.entries
+}
+
+fn toc_item(entry: &TocEntry) -> ListItem {
+    ListItem {
+        task: None,
+        blocks: vec![Block::Paragraph(vec![Inline::Link(Link {
+            destination: format!("#{}", entry.anchor),
+            title: None,
+            children: vec![Inline::Text(entry.text.clone())],
+        })])],
+    }
+}
+
+fn bullet_list(items: Vec<ListItem>) -> Block {
+    Block::List(List {
+        kind: ListKind::Bullet(ListBulletKind::Dash),
+        items,
+    })
+}
+
+/// Build the nested list of links, or `None` if no heading is in the depth range.
+pub fn build_toc(entries: &[TocEntry], min_depth: u8, max_depth: u8) -> Option<Block> {
+    let entries: Vec<_> = entries
+        .iter()
+        .filter(|entry| (min_depth..=max_depth).contains(&entry.level))
+        .collect();
+
+    let items = build_items(&entries);
+    (!items.is_empty()).then(|| bullet_list(items))
+}
+
+/// Each entry becomes an item whose sub-list holds the following deeper entries.
+fn build_items(entries: &[&TocEntry]) -> Vec<ListItem> {
+    let mut items = Vec::new();
+    let mut index = 0;
+
+    while index < entries.len() {
+        let level = entries[index].level;
+        let end = entries[index + 1..]
+            .iter()
+            .position(|entry| entry.level <= level)
+            .map_or(entries.len(), |offset| index + 1 + offset);
+
+        let mut item = toc_item(entries[index]);
+        let children = build_items(&entries[index + 1..end]);
+        if !children.is_empty() {
+            item.blocks.push(bullet_list(children));
+        }
+        items.push(item);
+        index = end;
+    }
+
+    items
+}
+
+fn is_marker(block: &Block, marker: &str) -> bool {
+    matches!(block, Block::HtmlBlock(html) if html.trim() == marker)
+}
+
+/// Replace the blocks between the TOC markers with `toc`.
+///
+/// Returns `None` if the document has no complete marker pair, otherwise
+/// whether the region changed.
+pub fn replace_in_document(document: &mut Document, toc: Option<Block>) -> Option<bool> {
+    let start = document
+        .blocks
+        .iter()
+        .position(|block| is_marker(block, TOC_START))?;
+    let stop = start
+        + 1
+        + document.blocks[start + 1..]
+            .iter()
+            .position(|block| is_marker(block, TOC_STOP))?;
+
+    let replacement: Vec<Block> = toc.into_iter().collect();
+    if document.blocks[start + 1..stop] == replacement[..] {
+        return Some(false);
+    }
+    document.blocks.splice(start + 1..stop, replacement);
+    Some(true)
+}
+
+/// End of the start marker line and beginning of the stop marker line,
+/// ignoring markers inside fenced code blocks.
+fn find_markers(text: &str) -> Option<(usize, Option<usize>)> {
+    let mut start = None;
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let content = line.trim();
+
+        if let Some(marker) = fence {
+            if content.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if content.starts_with("```") || content.starts_with("~~~") {
+            fence = Some(&content[..3]);
+        } else if content == TOC_START && start.is_none() {
+            start = Some(offset);
+        } else if content == TOC_STOP && start.is_some() {
+            return start.map(|start| (start, Some(line_start)));
+        }
+    }
+
+    start.map(|start| (start, None))
+}
+
+/// Replace the text between the TOC markers with the rendered table of contents.
+///
+/// A start marker without a stop marker gets the table of contents and a stop
+/// marker inserted after it. Returns `None` if there is no start marker.
+pub fn replace_in_text(text: &str, rendered_toc: &str) -> Option<String> {
+    let (start, stop) = find_markers(text)?;
+
+    let region = match rendered_toc.trim_start_matches('\n').trim_end() {
+        "" => "\n".to_string(),
+        toc => format!("\n{toc}\n\n"),
+    };
+
+    let result = match stop {
+        Some(stop) => format!("{}{region}{}", &text[..start], &text[stop..]),
+        None => format!("{}{region}{TOC_STOP}\n{}", &text[..start], &text[start..]),
+    };
+    Some(result)
+}
+
+/// Table of contents of the document for the configured depth range.
+pub fn generate(document: &Document, config: &TocConfig, anchor_prefix: &str) -> Option<Block> {
+    let (min_depth, max_depth) = config.depth();
+    build_toc(
+        &collect_headings(document, anchor_prefix),
+        min_depth,
+        max_depth,
+    )
+}
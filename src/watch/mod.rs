@@ -0,0 +1,77 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Quiet period after a change before the files are processed, so that a burst
+/// of writes (e.g. an editor saving through a temporary file) triggers one run
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Modification time and size of a file, `None` if it can't be read.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn snapshot(paths: &[PathBuf]) -> BTreeMap<&Path, Stamp> {
+    paths
+        .iter()
+        .map(|path| (path.as_path(), stamp(path)))
+        .collect()
+}
+
+fn changed<'a>(
+    before: &BTreeMap<&'a Path, Stamp>,
+    after: &BTreeMap<&'a Path, Stamp>,
+) -> Vec<&'a Path> {
+    after
+        .iter()
+        .filter(|(path, stamp)| before.get(*path) != Some(*stamp))
+        .map(|(path, _)| *path)
+        .collect()
+}
+
+/// Poll `paths` forever and call `on_change` with the files that changed
+/// after each debounced burst of changes.
+///
+/// `on_change` returns the files it wrote, whose changes don't trigger another
+/// run; other files changed while it runs are processed in the next run.
+pub fn watch(paths: &[PathBuf], mut on_change: impl FnMut(&[&Path]) -> Vec<PathBuf>) -> ! {
+    eprintln!(
+        "Watching {} file(s) for changes, press Ctrl-C to stop",
+        paths.len()
+    );
+
+    let mut stamps = snapshot(paths);
+    loop {
+        sleep(POLL_INTERVAL);
+        let mut latest = snapshot(paths);
+        let mut pending: BTreeSet<&Path> = changed(&stamps, &latest).into_iter().collect();
+        if pending.is_empty() {
+            continue;
+        }
+
+        loop {
+            sleep(DEBOUNCE);
+            let next = snapshot(paths);
+            if next == latest {
+                break;
+            }
+            pending.extend(changed(&latest, &next));
+            latest = next;
+        }
+
+        let pending: Vec<&Path> = pending.into_iter().collect();
+        for written in on_change(&pending) {
+            if let Some(latest) = latest.get_mut(written.as_path()) {
+                *latest = stamp(&written);
+            }
+        }
+        stamps = latest;
+    }
+}
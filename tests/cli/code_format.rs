@@ -0,0 +1,174 @@
+use super::{assert_success, TestCommand};
+use std::fs;
+use tempfile::TempDir;
+
+const DOCUMENT: &str = "# Snippets
+
+```json
+{\"name\": \"tool\", \"tags\": [], \"nested\": {\"b\": 1, \"a\": \"x, y: {z}\"}}
+```
+
+```sql
+select 1
+```
+
+> ```sh
+> ls
+> ```
+";
+
+/// Directory with `doc.md` after running `format` with `args` in it, and the
+/// command's output.
+fn format(args: &[&str], config: Option<&str>) -> (TempDir, std::process::Output) {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("doc.md"), DOCUMENT).unwrap();
+    if let Some(config) = config {
+        fs::write(dir.path().join(".markdown-tool.toml"), config).unwrap();
+    }
+    let mut all_args = vec!["format"];
+    all_args.extend_from_slice(args);
+    all_args.push("doc.md");
+    let output = TestCommand::new().run_in_dir(dir.path(), &all_args);
+    (dir, output)
+}
+
+fn read(dir: &TempDir) -> String {
+    fs::read_to_string(dir.path().join("doc.md")).unwrap()
+}
+
+#[test]
+fn test_builtin_json_formatter_keeps_key_order() {
+    let (dir, output) = format(&["--code-formatter", "json=builtin:json"], None);
+    assert_success(&output);
+
+    assert!(read(&dir).contains(
+        "```json
+{
+  \"name\": \"tool\",
+  \"tags\": [],
+  \"nested\": {
+    \"b\": 1,
+    \"a\": \"x, y: {z}\"
+  }
+}
+```"
+    ));
+    assert!(read(&dir).contains("```sql\nselect 1\n```"));
+}
+
+#[test]
+fn test_external_formatters_from_config_file() {
+    let config = "[code_format.formatters]\nsql = \"tr a-z A-Z\"\nsh = \"sed 's/^/echo /'\"\n";
+    let (dir, output) = format(&["--allow-config-formatters"], Some(config));
+    assert_success(&output);
+
+    let content = read(&dir);
+    assert!(content.contains("```sql\nSELECT 1\n```"));
+    assert!(content.contains("> ```sh\n> echo ls\n> ```"));
+}
+
+#[test]
+fn test_config_file_formatter_commands_need_opt_in() {
+    let config = "[code_format.formatters]\njson = \"builtin:json\"\nsql = \"tr a-z A-Z\"\n";
+    let (dir, output) = format(&[], Some(config));
+    assert_success(&output);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr
+        .contains("code formatter commands not run, pass --allow-config-formatters to run them"));
+    let content = read(&dir);
+    assert!(content.contains("```sql\nselect 1\n```"));
+    assert!(content.contains("  \"name\": \"tool\","));
+}
+
+#[test]
+fn test_command_line_formatter_overrides_config_file() {
+    let config = "[code_format.formatters]\nsql = \"tr a-z A-Z\"\n";
+    let (dir, output) = format(&["--code-formatter", "SQL=sed s/1/2/"], Some(config));
+    assert_success(&output);
+
+    assert!(read(&dir).contains("```sql\nselect 2\n```"));
+}
+
+#[test]
+fn test_failing_formatter_leaves_block_unchanged() {
+    let (dir, output) = format(
+        &[
+            "--code-formatter",
+            "sql=false",
+            "--code-formatter",
+            "sh=no-such-formatter-command",
+        ],
+        None,
+    );
+    assert_success(&output);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Warning: doc.md: sql code block left unchanged, `false` failed"));
+    assert!(stderr.contains("`no-such-formatter-command` could not be started"));
+    let content = read(&dir);
+    assert!(content.contains("```sql\nselect 1\n```"));
+    assert!(content.contains("> ```sh\n> ls\n> ```"));
+}
+
+#[test]
+fn test_invalid_json_is_left_unchanged() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("doc.md"), "```json\n{\"a\": 1,}\n```\n").unwrap();
+
+    let output = TestCommand::new().run_in_dir(
+        dir.path(),
+        &["format", "--code-formatter", "json=builtin:json", "doc.md"],
+    );
+    assert_success(&output);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid JSON"));
+    assert_eq!(read(&dir), "```json\n{\"a\": 1,}\n```\n");
+}
+
+#[test]
+fn test_formatter_timeout() {
+    let (dir, output) = format(
+        &[
+            "--code-formatter",
+            "sql=sleep 5",
+            "--code-formatter-timeout",
+            "1",
+        ],
+        None,
+    );
+    assert_success(&output);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("`sleep 5` timed out after 1s"));
+    assert!(read(&dir).contains("```sql\nselect 1\n```"));
+}
+
+#[test]
+fn test_formatter_timeout_stops_child_processes() {
+    let (dir, output) = format(
+        &[
+            "--code-formatter",
+            "sql=sh -c '(sleep 2; touch late) & wait'",
+            "--code-formatter-timeout",
+            "1",
+        ],
+        None,
+    );
+    assert_success(&output);
+
+    // The subshell is stopped together with `sh` and never creates the file
+    std::thread::sleep(std::time::Duration::from_secs(3));
+    assert!(!dir.path().join("late").exists());
+    assert!(read(&dir).contains("```sql\nselect 1\n```"));
+}
+
+#[test]
+fn test_invalid_formatter_argument() {
+    let (_dir, output) = format(&["--code-formatter", "rustfmt"], None);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid code formatter: rustfmt. Expected LANG=COMMAND"));
+}
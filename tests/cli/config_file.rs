@@ -0,0 +1,143 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+use std::fs;
+
+const LONG_LINE: &str = "This paragraph is deliberately long enough to be wrapped at forty columns but not at a hundred.\n";
+
+#[test]
+fn test_config_file_discovered_from_parent_directory() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(".markdown-tool.toml"),
+        "[markdown]\nwidth = 40\n",
+    )
+    .unwrap();
+    let nested = dir.path().join("docs");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(nested.join("doc.md"), LONG_LINE).unwrap();
+
+    let output = cmd.run_in_dir(&nested, &["convert-to", "markdown", "doc.md"]);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().all(|line| line.len() <= 40), "{stdout}");
+}
+
+#[test]
+fn test_cli_flag_overrides_config_file() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(".markdown-tool.toml"),
+        "[markdown]\nwidth = 40\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("doc.md"), LONG_LINE).unwrap();
+
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &["convert-to", "markdown", "--width", "200", "doc.md"],
+    );
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+}
+
+#[test]
+fn test_config_show_applies_overrides() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(".markdown-tool.toml"),
+        "[markdown]\nwidth = 100\n\n[[override]]\nfiles = [\"CHANGELOG.md\"]\nmarkdown = { width = 120 }\n",
+    )
+    .unwrap();
+
+    let output = cmd.run_in_dir(dir.path(), &["config", "show"]);
+    assert_success(&output);
+    assert_output_contains(&output, "width = 100");
+    assert_output_contains(&output, "table_style = \"tabular\"");
+
+    let output = cmd.run_in_dir(dir.path(), &["config", "show", "docs/CHANGELOG.md"]);
+    assert_success(&output);
+    assert_output_contains(&output, "width = 120");
+}
+
+#[test]
+fn test_explicit_config_option() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("custom.toml");
+    fs::write(&config, "[latex]\ncode_style = \"minted\"\n").unwrap();
+
+    let output = cmd.run(&["--config", config.to_str().unwrap(), "config", "show"]);
+
+    assert_success(&output);
+    assert_output_contains(&output, "code_style = \"minted\"");
+}
+
+#[test]
+fn test_invalid_config_file() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(".markdown-tool.toml"),
+        "[markdown]\nunknown_option = 1\n",
+    )
+    .unwrap();
+
+    let output = cmd.run_in_dir(dir.path(), &["config", "show"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to parse config file"));
+    assert!(stderr.contains(".markdown-tool.toml:2:1"));
+}
+
+#[test]
+fn test_config_file_paths_are_relative_to_config_file() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(".markdown-tool.toml"),
+        "[html]\ntemplate = \"tpl/page.html\"\n\n[latex]\ntemplate = \"tpl/page.tex\"\n\n\
+         [epub]\ncss = [\"tpl/book.css\"]\n\n[docx]\nreference_docx = \"tpl/reference.docx\"\n",
+    )
+    .unwrap();
+    let templates = dir.path().join("tpl");
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(templates.join("page.html"), "<main>$body$</main>\n").unwrap();
+    fs::write(templates.join("page.tex"), "% page\n$body$\n").unwrap();
+    fs::write(templates.join("book.css"), "body { color: navy; }\n").unwrap();
+    fs::copy(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/reference.docx"),
+        templates.join("reference.docx"),
+    )
+    .unwrap();
+    let nested = dir.path().join("docs");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(nested.join("doc.md"), "# Title\n").unwrap();
+
+    let html = cmd.run_in_dir(&nested, &["convert-to", "html", "doc.md"]);
+    assert_success(&html);
+    assert_output_contains(&html, "<main><h1>Title</h1>");
+
+    let latex = cmd.run_in_dir(&nested, &["convert-to", "latex", "doc.md"]);
+    assert_success(&latex);
+    assert_output_contains(&latex, "% page\n");
+
+    for format in ["epub", "docx"] {
+        let output = cmd.run_in_dir(
+            &nested,
+            &[
+                "convert-to",
+                format,
+                "-o",
+                &format!("doc.{format}"),
+                "doc.md",
+            ],
+        );
+        assert_success(&output);
+    }
+}
@@ -0,0 +1,130 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+
+#[test]
+fn test_convert_to_asciidoc_headings_and_inline_markup() {
+    let cmd = TestCommand::new();
+    let input = "# Title\n\nSome *emphasis*, **strong**, ~~gone~~ and `code`.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "== Title\n\nSome _emphasis_, *strong*, [.line-through]#gone# and `code`.\n"
+    );
+}
+
+#[test]
+fn test_convert_to_asciidoc_unconstrained_marks_inside_words() {
+    let cmd = TestCommand::new();
+    let input = "pre*fix*\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "pre__fix__");
+}
+
+#[test]
+fn test_convert_to_asciidoc_section_levels_are_not_skipped() {
+    let cmd = TestCommand::new();
+    let input = "# One\n\n### Three\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "== One\n\n=== Three\n");
+}
+
+#[test]
+fn test_convert_to_asciidoc_escapes_text() {
+    let cmd = TestCommand::new();
+    let input = "2 * 3 in C++ and C++\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "2 {asterisk} 3 in C{plus}{plus} and C{plus}{plus}");
+}
+
+#[test]
+fn test_convert_to_asciidoc_lists() {
+    let cmd = TestCommand::new();
+    let input = "3. three\n4. four\n   - nested\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "[start=3]\n. three\n. four\n** nested\n");
+}
+
+#[test]
+fn test_convert_to_asciidoc_table() {
+    let cmd = TestCommand::new();
+    let input = "| A | B | C |\n|:--|:-:|---|\n| 1 | 2 | 3 |\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "[cols=\"<,^,1\",options=\"header\"]\n|===\n|A |B |C\n\n|1 |2 |3\n|===\n"
+    );
+}
+
+#[test]
+fn test_convert_to_asciidoc_code_block() {
+    let cmd = TestCommand::new();
+    let input = "```rust\nfn main() {}\n```\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "[source,rust]\n----\nfn main() {}\n----\n");
+}
+
+#[test]
+fn test_convert_to_asciidoc_admonition() {
+    let cmd = TestCommand::new();
+    let input = "> [!WARNING]\n> Be careful.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "[WARNING]\n====\nBe careful.\n====\n");
+}
+
+#[test]
+fn test_convert_to_asciidoc_links_and_footnotes() {
+    let cmd = TestCommand::new();
+    let input = "A [site][home] and [page](docs/page.html)[^n], again[^n].\n\n\
+                 [home]: https://example.com\n[^n]: The note.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "A https://example.com[site] and link:docs/page.html[page]footnote:n[The note.],\n\
+         againfootnote:n[].\n"
+    );
+}
+
+#[test]
+fn test_convert_to_asciidoc_document_header() {
+    let cmd = TestCommand::new();
+    let input = "---\ntitle: Report\nauthor: Ada\n---\nBody\n";
+
+    let output = cmd.run_with_input(&["convert-to", "asciidoc"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "= Report\nAda\n\nBody\n");
+}
@@ -158,3 +158,188 @@ fn test_invalid_ast_yaml_input() {
         "Should provide error message for invalid YAML"
     );
 }
+
+#[test]
+fn test_convert_to_ast_json_pretty() {
+    let cmd = TestCommand::new();
+    let input = "# Hi\n";
+
+    let output = cmd.run_with_input(&["convert-to", "ast-json", "--pretty"], input);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("{\n  \"blocks\": [\n    {\n      \"Heading\": {\n"));
+
+    let output = cmd.run_with_input(&["convert-to", "ast-json", "--indent", "4"], input);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("{\n    \"blocks\": [\n        {\n"));
+}
+
+#[test]
+fn test_convert_to_ast_json_pretty_from_config() {
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join(".markdown-tool.toml"),
+        "[ast_json]\nindent = 1\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("doc.md"), "Text\n").unwrap();
+
+    let cmd = TestCommand::new();
+    let output = cmd.run_in_dir(dir.path(), &["convert-to", "ast-json", "doc.md"]);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("{\n \"blocks\": [\n  {\n"));
+}
+
+#[test]
+fn test_convert_to_ast_json_omit_defaults_roundtrip() {
+    let cmd = TestCommand::new();
+    let input = "# Title\n\n- [link](https://example.com)\n\n```\ncode\n```\n";
+
+    let output = cmd.run_with_input(&["convert-to", "ast-json", "--omit-defaults"], input);
+    assert_success(&output);
+    let json = String::from_utf8_lossy(&output.stdout);
+    assert!(!json.contains("null"));
+    assert!(json.contains(
+        r#"{"Link":{"children":[{"Text":"link"}],"destination":"https://example.com"}}"#
+    ));
+    assert!(json.contains(r#"{"blocks":[{"Paragraph""#));
+    assert!(json.contains(r#""kind":{"Fenced":{}}"#));
+
+    let markdown = cmd.run_with_input(&["convert-to", "-f", "ast-json", "markdown"], &json);
+    assert_success(&markdown);
+    assert_eq!(
+        String::from_utf8_lossy(&markdown.stdout),
+        "# Title\n\n - [link](https://example.com)\n\n```\ncode\n```\n"
+    );
+}
+
+#[test]
+fn test_convert_to_ast_yaml_omit_defaults_roundtrip() {
+    let cmd = TestCommand::new();
+    let input = "---\ntitle: Notes\n---\n\n# Title\n\n- [link](https://example.com)\n";
+
+    let output = cmd.run_with_input(&["convert-to", "ast-yaml", "--omit-defaults"], input);
+    assert_success(&output);
+    let yaml = String::from_utf8_lossy(&output.stdout);
+    assert!(!yaml.contains("null"));
+    assert!(yaml.contains("- Link:\n            children:\n            - Text: link\n            destination: https://example.com\n"));
+
+    let markdown = cmd.run_with_input(&["convert-to", "-f", "ast-yaml", "markdown"], &yaml);
+    assert_success(&markdown);
+    assert_eq!(
+        String::from_utf8_lossy(&markdown.stdout),
+        "---\ntitle: Notes\n---\n\n# Title\n\n - [link](https://example.com)\n"
+    );
+}
+
+#[test]
+fn test_ast_json_input_fills_left_out_fields() {
+    let cmd = TestCommand::new();
+    let input = r#"{"blocks":[{"Heading":{"kind":{"Atx":2}}},{"List":{"kind":{"Bullet":"Star"},"items":[{}]}},{"Paragraph":[{"Image":{"destination":"a.png","alt":"A"}}]}]}"#;
+
+    let output = cmd.run_with_input(&["convert-to", "-f", "ast-json", "html"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("<h2"));
+    assert!(stdout.contains("<li>"));
+    assert!(stdout.contains(r#"<img src="a.png" alt="A""#));
+}
+
+#[test]
+fn test_convert_to_ast_json_schema() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run(&["convert-to", "ast-json", "--schema"]);
+
+    assert_success(&output);
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        schema["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(
+        schema["$defs"]["CodeBlock"]["required"],
+        serde_json::json!(["kind", "literal"])
+    );
+    let blocks = schema["$defs"]["Block"]["oneOf"].as_array().unwrap();
+    assert!(blocks.iter().any(|block| block["const"] == "ThematicBreak"));
+}
+
+#[test]
+fn test_convert_to_ast_json_schema_rejects_inputs() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run(&["convert-to", "ast-json", "--schema", "doc.md"]);
+
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_convert_to_ast_jsonl_writes_one_block_per_line() {
+    let cmd = TestCommand::new();
+    let input = "---\ntitle: Doc\n---\n# Hi\n\nText.\n\n---\n";
+
+    let output = cmd.run_with_input(&["convert-to", "ast-jsonl"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            r#"{"front_matter":{"format":"yaml","data":{"title":"Doc"}}}"#,
+            r#"{"Heading":{"kind":{"Atx":1},"content":[{"Text":"Hi"}]}}"#,
+            r#"{"Paragraph":[{"Text":"Text."}]}"#,
+            r#""ThematicBreak""#,
+        ]
+    );
+}
+
+#[test]
+fn test_ast_jsonl_roundtrip() {
+    let cmd = TestCommand::new();
+    let input = "---\ntitle: Doc\n---\n\n# Test\n\n- one\n- two\n\n> Quote.\n";
+
+    let jsonl = cmd.run_with_input(&["convert-to", "ast-jsonl"], input);
+    assert_success(&jsonl);
+    let markdown = cmd.run_with_input(
+        &["convert-to", "-f", "ast-jsonl", "markdown"],
+        &String::from_utf8_lossy(&jsonl.stdout),
+    );
+
+    assert_success(&markdown);
+    let direct = cmd.run_with_input(&["convert-to", "markdown"], input);
+    assert_eq!(markdown.stdout, direct.stdout);
+}
+
+#[test]
+fn test_ast_jsonl_input_filtered_lines() {
+    let cmd = TestCommand::new();
+    // Lines edited by line-oriented tools: blank lines and left out defaults
+    let input = "{\"Heading\":{\"kind\":{\"Atx\":2}}}\n\n\"ThematicBreak\"\n{\"Paragraph\":[{\"Text\":\"End\"}]}\n";
+
+    let output = cmd.run_with_input(&["convert-to", "-f", "ast-jsonl", "html"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("<h2"));
+    assert!(stdout.contains("<hr"));
+    assert!(stdout.contains("<p>End</p>"));
+}
+
+#[test]
+fn test_ast_jsonl_parse_error_shows_line() {
+    let cmd = TestCommand::new();
+    let input = "\"ThematicBreak\"\n{\"Paragraph\": 3}\n";
+
+    let output = cmd.run_with_input(&["convert-to", "-f", "ast-jsonl", "html"], input);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with("error: invalid type"), "{stderr}");
+    assert!(stderr.contains("--> <stdin>:2:15"), "{stderr}");
+}
@@ -0,0 +1,228 @@
+use super::{assert_success, zip_entry, TestCommand};
+use std::fs;
+use tempfile::TempDir;
+
+fn markdown_to_docx(input: &str) -> Vec<u8> {
+    let cmd = TestCommand::new();
+    let output = cmd.run_with_input(&["convert-to", "docx"], input);
+    assert_success(&output);
+    output.stdout
+}
+
+/// 40x20 pixel PNG: signature and the header chunk with the size.
+fn png_header() -> Vec<u8> {
+    let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    png.extend_from_slice(&40u32.to_be_bytes());
+    png.extend_from_slice(&20u32.to_be_bytes());
+    png.extend_from_slice(&[8, 2, 0, 0, 0, 0, 0, 0, 0]);
+    png
+}
+
+#[test]
+fn test_convert_to_docx_package_parts() {
+    let docx = markdown_to_docx("---\ntitle: Report & Notes\nauthor: Ann\n---\n# Title\n\nText\n");
+
+    assert!(docx.starts_with(b"PK\x03\x04"));
+    for part in [
+        "[Content_Types].xml",
+        "_rels/.rels",
+        "word/_rels/document.xml.rels",
+        "word/styles.xml",
+        "word/numbering.xml",
+        "word/footnotes.xml",
+    ] {
+        assert!(zip_entry(&docx, part).is_some(), "missing {part}");
+    }
+    let core = zip_entry(&docx, "docProps/core.xml").unwrap();
+    assert!(core.contains("<dc:title>Report &amp; Notes</dc:title>"));
+    assert!(core.contains("<dc:creator>Ann</dc:creator>"));
+}
+
+#[test]
+fn test_convert_to_docx_parts_are_compressed() {
+    let paragraphs: Vec<String> = (1..=200)
+        .map(|n| format!("Paragraph {n} with **strong** text.\n"))
+        .collect();
+    let docx = markdown_to_docx(&paragraphs.join("\n"));
+
+    // The first entry, [Content_Types].xml, is deflated
+    assert_eq!(&docx[8..10], &[8, 0]);
+    let document = zip_entry(&docx, "word/document.xml").unwrap();
+    assert!(docx.len() * 4 < document.len(), "{} bytes", docx.len());
+    for n in [1, 57, 200] {
+        assert!(document.contains(&format!(">Paragraph {n} with </w:t>")));
+    }
+}
+
+#[test]
+fn test_convert_to_docx_heading_styles_and_bookmarks() {
+    let docx = markdown_to_docx("# Intro\n\n### Details\n\nSee [intro](#intro).\n");
+    let document = zip_entry(&docx, "word/document.xml").unwrap();
+
+    assert!(document.contains(
+        r#"<w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:bookmarkStart w:id="0" w:name="intro"/><w:r><w:t xml:space="preserve">Intro</w:t></w:r>"#
+    ));
+    assert!(document.contains(r#"<w:pStyle w:val="Heading3"/>"#));
+    assert!(document.contains(r#"<w:hyperlink w:anchor="intro" w:history="1">"#));
+    let styles = zip_entry(&docx, "word/styles.xml").unwrap();
+    assert!(styles.contains(r#"w:styleId="Heading3"><w:name w:val="heading 3"/>"#));
+}
+
+#[test]
+fn test_convert_to_docx_bookmarks_of_headings_in_footnotes() {
+    let cmd = TestCommand::new();
+    let heading = r#"{"Heading":{"kind":{"Atx":2},"content":[{"Text":"Setup"}]}}"#;
+    let input = format!(
+        r#"{{"blocks":[{{"Paragraph":[{{"Text":"Text"}},{{"FootnoteReference":"n"}}]}},{{"FootnoteDefinition":{{"label":"n","blocks":[{heading}]}}}},{heading}]}}"#
+    );
+
+    let output = cmd.run_with_input(&["convert-to", "-f", "ast-json", "docx"], &input);
+
+    assert_success(&output);
+    // Anchors count headings in document order, like the table of contents
+    let footnotes = zip_entry(&output.stdout, "word/footnotes.xml").unwrap();
+    assert!(footnotes.contains(r#"w:name="setup"/>"#));
+    let document = zip_entry(&output.stdout, "word/document.xml").unwrap();
+    assert!(document.contains(r#"w:name="setup-1"/>"#));
+}
+
+#[test]
+fn test_convert_to_docx_inline_formatting() {
+    let docx = markdown_to_docx("Some *emphasis*, **strong**, ~~gone~~ and `code`.\n");
+    let document = zip_entry(&docx, "word/document.xml").unwrap();
+
+    assert!(document
+        .contains(r#"<w:r><w:rPr><w:i/></w:rPr><w:t xml:space="preserve">emphasis</w:t></w:r>"#));
+    assert!(document
+        .contains(r#"<w:r><w:rPr><w:b/></w:rPr><w:t xml:space="preserve">strong</w:t></w:r>"#));
+    assert!(document.contains(r#"<w:rPr><w:strike/></w:rPr>"#));
+    assert!(document.contains(
+        r#"<w:r><w:rPr><w:rStyle w:val="VerbatimChar"/></w:rPr><w:t xml:space="preserve">code</w:t></w:r>"#
+    ));
+}
+
+#[test]
+fn test_convert_to_docx_lists_use_numbering() {
+    let docx = markdown_to_docx("- one\n- [x] two\n  3. nested\n  4. more\n");
+    let document = zip_entry(&docx, "word/document.xml").unwrap();
+    let numbering = zip_entry(&docx, "word/numbering.xml").unwrap();
+
+    assert!(document.contains(
+        r#"<w:pStyle w:val="ListParagraph"/><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr>"#
+    ));
+    assert!(document.contains(r#"<w:t xml:space="preserve">☒ </w:t>"#));
+    assert!(document.contains(r#"<w:numPr><w:ilvl w:val="1"/><w:numId w:val="2"/></w:numPr>"#));
+    assert!(numbering.contains(r#"<w:numFmt w:val="bullet"/>"#));
+    assert!(numbering.contains(
+        r#"<w:num w:numId="2"><w:abstractNumId w:val="1"/><w:lvlOverride w:ilvl="1"><w:startOverride w:val="3"/></w:lvlOverride></w:num>"#
+    ));
+}
+
+#[test]
+fn test_convert_to_docx_code_block_and_table() {
+    let docx =
+        markdown_to_docx("```\nfn main() {\n\tok();\n}\n```\n\n| A | B |\n|:-:|--:|\n| 1 | 2 |\n");
+    let document = zip_entry(&docx, "word/document.xml").unwrap();
+
+    assert!(document.contains(
+        r#"<w:p><w:pPr><w:pStyle w:val="SourceCode"/></w:pPr><w:r><w:t xml:space="preserve">fn main() {</w:t></w:r><w:r><w:br/></w:r><w:r><w:tab/><w:t xml:space="preserve">ok();</w:t></w:r>"#
+    ));
+    assert!(document.contains(r#"<w:tblStyle w:val="Table"/>"#));
+    assert!(document.contains("<w:trPr><w:tblHeader/></w:trPr>"));
+    assert!(document.contains(
+        r#"<w:p><w:pPr><w:jc w:val="right"/></w:pPr><w:r><w:t xml:space="preserve">2</w:t></w:r></w:p>"#
+    ));
+}
+
+#[test]
+fn test_convert_to_docx_hyperlinks_and_footnotes() {
+    let docx = markdown_to_docx(
+        "A [link](https://example.com?a=1&b=2) and a note.[^1]\n\n[^1]: See <https://note.example>.\n",
+    );
+    let document = zip_entry(&docx, "word/document.xml").unwrap();
+    let relationships = zip_entry(&docx, "word/_rels/document.xml.rels").unwrap();
+    let footnotes = zip_entry(&docx, "word/footnotes.xml").unwrap();
+    let footnote_relationships = zip_entry(&docx, "word/_rels/footnotes.xml.rels").unwrap();
+
+    assert!(document.contains(r#"<w:hyperlink r:id="rId10" w:history="1"><w:r><w:rPr><w:rStyle w:val="Hyperlink"/></w:rPr>"#));
+    assert!(relationships.contains(
+        r#"Id="rId10" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com?a=1&amp;b=2" TargetMode="External"/>"#
+    ));
+    assert!(document.contains(r#"<w:footnoteReference w:id="1"/>"#));
+    assert!(footnotes.contains(r#"<w:footnote w:id="1"><w:p><w:pPr><w:pStyle w:val="FootnoteText"/></w:pPr><w:r><w:rPr><w:rStyle w:val="FootnoteReference"/></w:rPr><w:footnoteRef/></w:r>"#));
+    assert!(footnote_relationships.contains(r#"Target="https://note.example""#));
+}
+
+#[test]
+fn test_convert_to_docx_embeds_local_images() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("pic.png"), png_header()).unwrap();
+    fs::write(
+        dir.path().join("doc.md"),
+        "![Chart](pic.png)\n\n![Lost](missing.png)\n",
+    )
+    .unwrap();
+
+    let cmd = TestCommand::new();
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &["convert-to", "docx", "doc.md", "-o", "doc.docx"],
+    );
+    assert_success(&output);
+
+    let docx = fs::read(dir.path().join("doc.docx")).unwrap();
+    let document = zip_entry(&docx, "word/document.xml").unwrap();
+    assert!(zip_entry(&docx, "word/media/image1.png").is_some());
+    assert!(document.contains(r#"<wp:extent cx="381000" cy="190500"/>"#));
+    assert!(document.contains(r#"descr="Chart""#));
+    assert!(document.contains(r#"<w:i/></w:rPr><w:t xml:space="preserve">Lost</w:t>"#));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Warning: image not embedded: missing.png"));
+}
+
+#[test]
+fn test_convert_to_docx_reference_docx_styles() {
+    let reference = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/reference.docx");
+    let cmd = TestCommand::new();
+    let output = cmd.run_with_input(
+        &["convert-to", "docx", "--reference-docx", reference],
+        "# Title\n",
+    );
+    assert_success(&output);
+
+    let styles = zip_entry(&output.stdout, "word/styles.xml").unwrap();
+    // Styles of the reference document are kept, missing ones are added
+    assert!(styles.contains("Reference Serif"));
+    assert!(styles.contains(r#"<w:color w:val="C00000"/>"#));
+    assert_eq!(styles.matches(r#"w:styleId="Heading1""#).count(), 1);
+    assert!(styles.contains(r#"w:styleId="SourceCode""#));
+}
+
+#[test]
+fn test_convert_to_docx_missing_reference_docx() {
+    let cmd = TestCommand::new();
+    let output = cmd.run_with_input(
+        &["convert-to", "docx", "--reference-docx", "no-such.docx"],
+        "# Title\n",
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to read reference document: no-such.docx"));
+}
+
+#[test]
+fn test_convert_to_docx_output_dir_extension() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("a.md"), "# A\n").unwrap();
+
+    let cmd = TestCommand::new();
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &["convert-to", "--output-dir", "out", "docx", "a.md"],
+    );
+    assert_success(&output);
+
+    let docx = fs::read(dir.path().join("out/a.docx")).unwrap();
+    assert!(zip_entry(&docx, "word/document.xml").is_some());
+}
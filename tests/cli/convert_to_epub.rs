@@ -0,0 +1,230 @@
+use super::{assert_success, zip_entry, TestCommand};
+use std::fs;
+use tempfile::TempDir;
+
+fn markdown_to_epub(args: &[&str], input: &str) -> Vec<u8> {
+    let cmd = TestCommand::new();
+    let mut all_args = vec!["convert-to", "epub"];
+    all_args.extend_from_slice(args);
+    let output = cmd.run_with_input(&all_args, input);
+    assert_success(&output);
+    output.stdout
+}
+
+/// Directory with the given files and the EPUB built from `args` in it.
+fn build_book(files: &[(&str, &str)], args: &[&str]) -> (TempDir, Vec<u8>) {
+    let dir = TempDir::new().unwrap();
+    for (name, content) in files {
+        fs::write(dir.path().join(name), content).unwrap();
+    }
+    let mut all_args = vec!["convert-to", "epub", "-o", "book.epub"];
+    all_args.extend_from_slice(args);
+
+    let cmd = TestCommand::new();
+    let output = cmd.run_in_dir(dir.path(), &all_args);
+    assert_success(&output);
+    let epub = fs::read(dir.path().join("book.epub")).unwrap();
+    (dir, epub)
+}
+
+#[test]
+fn test_convert_to_epub_package_structure() {
+    let epub = markdown_to_epub(&[], "# Welcome\n\nHello.\n");
+
+    // The media type comes first, uncompressed, as EPUB readers require
+    assert_eq!(&epub[30..38], b"mimetype");
+    assert_eq!(&epub[8..10], &[0, 0]);
+    assert_eq!(
+        zip_entry(&epub, "mimetype").as_deref(),
+        Some("application/epub+zip")
+    );
+    let container = zip_entry(&epub, "META-INF/container.xml").unwrap();
+    assert!(container.contains(r#"full-path="OEBPS/content.opf""#));
+
+    let package = zip_entry(&epub, "OEBPS/content.opf").unwrap();
+    assert!(package.contains(r#"<package xmlns="http://www.idpf.org/2007/opf" version="3.0""#));
+    assert!(package.contains(
+        r#"<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>"#
+    ));
+    assert!(package.contains(
+        r#"<item id="chapter-1" href="chapter-1.xhtml" media-type="application/xhtml+xml"/>"#
+    ));
+    assert!(package.contains(r#"<item id="css-1" href="css/style.css" media-type="text/css"/>"#));
+    assert!(package.contains("<spine>\n<itemref idref=\"chapter-1\"/>\n</spine>"));
+    // A version 5 UUID of the content, the same for every build of the tool
+    assert!(package.contains(
+        "<dc:identifier id=\"book-id\">urn:uuid:1edfd7ab-6d9d-5ef7-90e1-b3bf664ca2f2</dc:identifier>"
+    ));
+    assert!(package.contains("<meta property=\"dcterms:modified\">"));
+
+    let chapter = zip_entry(&epub, "OEBPS/chapter-1.xhtml").unwrap();
+    assert!(chapter.contains("<title>Welcome</title>"));
+    assert!(chapter.contains(r#"<link rel="stylesheet" type="text/css" href="css/style.css"/>"#));
+    assert!(chapter.contains("<h1>Welcome</h1><p>Hello.</p>"));
+}
+
+#[test]
+fn test_convert_to_epub_chapters_from_inputs() {
+    let (_dir, epub) = build_book(
+        &[
+            (
+                "01-intro.md",
+                "---\ntitle: Handbook\nauthor: Ann\n---\n# Introduction\n\nText.\n",
+            ),
+            ("02-setup.md", "# Setup\n\nSteps.\n"),
+            ("03-notes.md", "No heading here.\n"),
+        ],
+        &["01-intro.md", "02-setup.md", "03-notes.md"],
+    );
+
+    let package = zip_entry(&epub, "OEBPS/content.opf").unwrap();
+    assert!(package.contains("<dc:title>Handbook</dc:title>"));
+    assert!(package.contains("<dc:creator>Ann</dc:creator>"));
+    assert!(package.contains(
+        "<itemref idref=\"chapter-1\"/>\n<itemref idref=\"chapter-2\"/>\n<itemref idref=\"chapter-3\"/>"
+    ));
+
+    let navigation = zip_entry(&epub, "OEBPS/nav.xhtml").unwrap();
+    assert!(navigation.contains(concat!(
+        "<nav epub:type=\"toc\" id=\"toc\">\n<h1>Contents</h1>\n<ol>\n",
+        "<li><a href=\"chapter-1.xhtml\">Introduction</a></li>\n",
+        "<li><a href=\"chapter-2.xhtml\">Setup</a></li>\n",
+        "<li><a href=\"chapter-3.xhtml\">03-notes</a></li>\n",
+        "</ol>\n</nav>"
+    )));
+    let chapter = zip_entry(&epub, "OEBPS/chapter-2.xhtml").unwrap();
+    assert!(chapter.contains("<h1>Setup</h1><p>Steps.</p>"));
+}
+
+#[test]
+fn test_convert_to_epub_split_chapters() {
+    let epub = markdown_to_epub(
+        &["--split-chapters"],
+        "Preface.\n\n# One\n\nFirst.\n\n# Two\n\nSecond[^n] and [link][site].\n\n[^n]: The note.\n\n[site]: https://example.com\n",
+    );
+
+    let navigation = zip_entry(&epub, "OEBPS/nav.xhtml").unwrap();
+    assert!(navigation.contains("<li><a href=\"chapter-2.xhtml\">One</a></li>"));
+    assert!(navigation.contains("<li><a href=\"chapter-3.xhtml\">Two</a></li>"));
+
+    let preface = zip_entry(&epub, "OEBPS/chapter-1.xhtml").unwrap();
+    assert!(preface.contains("<p>Preface.</p>"));
+    let second = zip_entry(&epub, "OEBPS/chapter-3.xhtml").unwrap();
+    assert!(second.contains(r#"<a href="https://example.com">link</a>"#));
+    assert!(second.contains("The note."));
+}
+
+#[test]
+fn test_convert_to_epub_metadata_options() {
+    let epub = markdown_to_epub(
+        &["--title", "Guide", "--author", "Bob", "--language", "de"],
+        "---\ntitle: Ignored\nauthor: Ann\nidentifier: isbn-123\n---\n# Start\n",
+    );
+
+    let package = zip_entry(&epub, "OEBPS/content.opf").unwrap();
+    assert!(package.contains("<dc:identifier id=\"book-id\">isbn-123</dc:identifier>"));
+    assert!(package.contains("<dc:title>Guide</dc:title>"));
+    assert!(package.contains("<dc:creator>Bob</dc:creator>"));
+    assert!(package.contains("<dc:language>de</dc:language>"));
+    let chapter = zip_entry(&epub, "OEBPS/chapter-1.xhtml").unwrap();
+    assert!(chapter.contains(r#"xml:lang="de" lang="de""#));
+}
+
+#[test]
+fn test_convert_to_epub_embeds_images_and_css() {
+    let svg = "<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>\n";
+    let (_dir, epub) = build_book(
+        &[
+            ("book.md", "# Pictures\n\n![Chart](chart.svg) ![Again](chart.svg)\n\n![Web](https://example.com/a.png)\n"),
+            ("chart.svg", svg),
+            ("theme.css", "body { color: navy; }\n"),
+        ],
+        &["--css", "theme.css", "book.md"],
+    );
+
+    let package = zip_entry(&epub, "OEBPS/content.opf").unwrap();
+    assert!(package
+        .contains(r#"<item id="image-1" href="images/image-1.svg" media-type="image/svg+xml"/>"#));
+    assert!(!package.contains("image-2"));
+    assert!(package.contains(r#"<item id="css-1" href="css/1-theme.css" media-type="text/css"/>"#));
+    assert_eq!(
+        zip_entry(&epub, "OEBPS/images/image-1.svg").as_deref(),
+        Some(svg)
+    );
+    assert_eq!(
+        zip_entry(&epub, "OEBPS/css/1-theme.css").as_deref(),
+        Some("body { color: navy; }\n")
+    );
+
+    let chapter = zip_entry(&epub, "OEBPS/chapter-1.xhtml").unwrap();
+    assert!(chapter.contains(r#"<img src="images/image-1.svg" alt="Chart"/>"#));
+    assert!(chapter.contains(r#"<a href="https://example.com/a.png">Web</a>"#));
+}
+
+#[test]
+fn test_convert_to_epub_skips_images_with_wrong_content() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("book.md"), "![Chart](chart.png)\n").unwrap();
+    fs::write(dir.path().join("chart.png"), "abc").unwrap();
+
+    let cmd = TestCommand::new();
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &["convert-to", "epub", "-o", "book.epub", "book.md"],
+    );
+
+    assert_success(&output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("image not embedded, content is not image/png"),
+        "{stderr}"
+    );
+    let epub = fs::read(dir.path().join("book.epub")).unwrap();
+    let package = zip_entry(&epub, "OEBPS/content.opf").unwrap();
+    assert!(!package.contains("image-1"));
+    let chapter = zip_entry(&epub, "OEBPS/chapter-1.xhtml").unwrap();
+    assert!(chapter.contains("<p>Chart</p>"));
+}
+
+#[test]
+fn test_convert_to_epub_writes_xhtml() {
+    let epub = markdown_to_epub(
+        &[],
+        "Line  \nbreak\n\n---\n\n<div>raw<br>html &amp; more</div>\n",
+    );
+
+    let chapter = zip_entry(&epub, "OEBPS/chapter-1.xhtml").unwrap();
+    assert!(chapter.contains("<p>Line<br/>break</p><hr/>"));
+    assert!(chapter.contains("<div>raw<br/>html &amp; more</div>"));
+}
+
+#[test]
+fn test_convert_to_epub_writes_alert_icons_as_svg() {
+    let epub = markdown_to_epub(&[], "> [!NOTE]\n> Useful info.\n");
+
+    let chapter = zip_entry(&epub, "OEBPS/chapter-1.xhtml").unwrap();
+    assert!(chapter.contains(
+        r#"<p class="markdown-alert-title"><svg xmlns="http://www.w3.org/2000/svg" class="octicon octicon-info mr-2" viewBox="0 0 16 16""#
+    ));
+    assert!(chapter.contains("Z\"></path></svg>Note</p><p>Useful info.</p></div>"));
+    let package = zip_entry(&epub, "OEBPS/content.opf").unwrap();
+    assert!(package.contains(
+        r#"href="chapter-1.xhtml" media-type="application/xhtml+xml" properties="svg"/>"#
+    ));
+}
+
+#[test]
+fn test_convert_to_epub_rejects_output_dir() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("a.md"), "# A\n").unwrap();
+
+    let cmd = TestCommand::new();
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &["convert-to", "--output-dir", "out", "epub", "a.md"],
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--output-dir is not supported by epub"));
+}
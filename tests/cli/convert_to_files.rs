@@ -0,0 +1,146 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+use std::fs;
+
+#[test]
+fn test_convert_single_file_to_stdout() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("doc.md");
+    fs::write(&input, "# From File\n").unwrap();
+
+    let output = cmd.run(&["convert-to", "html", input.to_str().unwrap()]);
+
+    assert_success(&output);
+    assert_output_contains(&output, "<h1>From File</h1>");
+}
+
+#[test]
+fn test_convert_glob_to_output_dir() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    let docs = dir.path().join("docs");
+    fs::create_dir_all(docs.join("guide")).unwrap();
+    fs::write(docs.join("index.md"), "# Index\n").unwrap();
+    fs::write(docs.join("guide").join("intro.md"), "# Intro\n").unwrap();
+    let out = dir.path().join("out");
+
+    let pattern = format!("{}/**/*.md", docs.display());
+    let output = cmd.run(&[
+        "convert-to",
+        "--output-dir",
+        out.to_str().unwrap(),
+        "html",
+        &pattern,
+    ]);
+
+    assert_success(&output);
+    let index = fs::read_to_string(out.join("index.html")).unwrap();
+    assert!(index.contains("<h1>Index</h1>"));
+    let intro = fs::read_to_string(out.join("guide").join("intro.html")).unwrap();
+    assert!(intro.contains("<h1>Intro</h1>"));
+}
+
+#[test]
+fn test_convert_output_extension_follows_format() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("doc.md");
+    fs::write(&input, "# Title\n").unwrap();
+    let out = dir.path().join("out");
+
+    let output = cmd.run(&[
+        "convert-to",
+        "--output-dir",
+        out.to_str().unwrap(),
+        "latex",
+        input.to_str().unwrap(),
+    ]);
+
+    assert_success(&output);
+    assert!(out.join("doc.tex").exists());
+}
+
+#[test]
+fn test_convert_multiple_inputs_require_output_dir() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("first.md");
+    let second = dir.path().join("second.md");
+    fs::write(&first, "# First\n").unwrap();
+    fs::write(&second, "# Second\n").unwrap();
+
+    let output = cmd.run(&[
+        "convert-to",
+        "html",
+        first.to_str().unwrap(),
+        second.to_str().unwrap(),
+    ]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--output-dir"));
+}
+
+#[test]
+fn test_convert_glob_without_matches() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+
+    let pattern = format!("{}/*.md", dir.path().display());
+    let output = cmd.run(&["convert-to", "html", &pattern]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No files match pattern"));
+}
+
+#[test]
+fn test_convert_relative_glob_keeps_directories() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("docs/guide")).unwrap();
+    fs::write(dir.path().join("docs/index.md"), "# Index\n").unwrap();
+    fs::write(dir.path().join("docs/guide/index.md"), "# Guide\n").unwrap();
+
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &[
+            "convert-to",
+            "--output-dir",
+            "out",
+            "html",
+            "./docs/**/*.md",
+        ],
+    );
+
+    assert_success(&output);
+    assert!(dir.path().join("out/index.html").exists());
+    assert!(dir.path().join("out/guide/index.html").exists());
+}
+
+#[test]
+fn test_convert_inputs_with_same_output_path_fail() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("a")).unwrap();
+    fs::create_dir_all(dir.path().join("b")).unwrap();
+    fs::write(dir.path().join("a/x.md"), "# A\n").unwrap();
+    fs::write(dir.path().join("b/x.md"), "# B\n").unwrap();
+
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &[
+            "convert-to",
+            "--output-dir",
+            "out",
+            "html",
+            "a/x.md",
+            "b/x.md",
+        ],
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Inputs a/x.md and b/x.md would be written to the same output file"));
+    assert!(!dir.path().join("out").exists());
+}
@@ -0,0 +1,231 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+
+#[test]
+fn test_convert_to_man_header_from_front_matter() {
+    let cmd = TestCommand::new();
+    let input = "---\ntitle: my-tool(8)\ndate: 2025-01-01\n---\n# Name\n\nmy-tool - does things\n";
+
+    let output = cmd.run_with_input(&["convert-to", "man"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        ".TH \"MY\\-TOOL\" \"8\" \"2025\\-01\\-01\" \"\" \"\"\n.SH\nNAME\n.PP\nmy\\-tool \\- does things\n"
+    );
+}
+
+#[test]
+fn test_convert_to_man_header_options_override_front_matter() {
+    let cmd = TestCommand::new();
+    let input = "---\nname: tool\nsection: 5\n---\nText\n";
+
+    let output = cmd.run_with_input(
+        &[
+            "convert-to",
+            "man",
+            "--section",
+            "1",
+            "--source",
+            "tool 1.0",
+            "--manual",
+            "Tool Manual",
+        ],
+        input,
+    );
+
+    assert_success(&output);
+    assert_output_contains(
+        &output,
+        ".TH \"TOOL\" \"1\" \"\" \"tool 1.0\" \"Tool Manual\"\n",
+    );
+}
+
+#[test]
+fn test_convert_to_man_fonts() {
+    let cmd = TestCommand::new();
+    let input = "Some *emphasis*, **strong _both_**, and `code`.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "man", "--name", "x"], input);
+
+    assert_success(&output);
+    assert_output_contains(
+        &output,
+        "Some \\fIemphasis\\fR, \\fBstrong \\f(BIboth\\fB\\fR, and \\fBcode\\fR.",
+    );
+}
+
+#[test]
+fn test_convert_to_man_headings() {
+    let cmd = TestCommand::new();
+    let input = "# Synopsis\n\n## Details\n\n### More\n";
+
+    let output = cmd.run_with_input(&["convert-to", "man", "--name", "x"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, ".SH\nSYNOPSIS\n.SS\nDetails\n.PP\n\\fBMore\\fR\n");
+}
+
+#[test]
+fn test_convert_to_man_escapes_text() {
+    let cmd = TestCommand::new();
+    let input = "Use --force in C:\\dir .now\n";
+
+    let output = cmd.run_with_input(&["convert-to", "man", "--name", "x", "--width", "8"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "Use\n\\-\\-force\nin\nC:\\edir\n\\&.now\n");
+}
+
+#[test]
+fn test_convert_to_man_lists() {
+    let cmd = TestCommand::new();
+    let input = "1. first\n2. second\n   - nested\n";
+
+    let output = cmd.run_with_input(&["convert-to", "man", "--name", "x"], input);
+
+    assert_success(&output);
+    assert_output_contains(
+        &output,
+        ".IP 1. 3\nfirst\n.IP 2. 3\nsecond\n.RS\n.IP \\(bu 2\nnested\n.RE\n",
+    );
+}
+
+#[test]
+fn test_convert_to_man_option_list_uses_tagged_paragraphs() {
+    let cmd = TestCommand::new();
+    let input = "- `--width N`: Maximum width.\n- `-v`: Verbose.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "man", "--name", "x"], input);
+
+    assert_success(&output);
+    assert_output_contains(
+        &output,
+        ".TP\n\\fB\\-\\-width N\\fR\nMaximum width.\n.TP\n\\fB\\-v\\fR\nVerbose.\n",
+    );
+}
+
+#[test]
+fn test_convert_to_man_code_block() {
+    let cmd = TestCommand::new();
+    let input = "```sh\n.start \\n\nrun --now\n```\n";
+
+    let output = cmd.run_with_input(&["convert-to", "man", "--name", "x"], input);
+
+    assert_success(&output);
+    assert_output_contains(
+        &output,
+        ".PP\n.RS 4\n.nf\n\\&.start \\en\nrun \\-\\-now\n.fi\n.RE\n",
+    );
+}
+
+#[test]
+fn test_convert_to_man_table_uses_tbl() {
+    let cmd = TestCommand::new();
+    let input = "| Key | Value |\n|:--|--:|\n| a | 1 |\n";
+
+    let output = cmd.run_with_input(&["convert-to", "man", "--name", "x"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("'\\\" t\n.TH"));
+    assert_output_contains(
+        &output,
+        ".TS\ntab(|);\nlB rB\nl r.\nKey|Value\n_\na|1\n.TE\n",
+    );
+}
+
+#[test]
+fn test_convert_to_man_footnotes_and_links() {
+    let cmd = TestCommand::new();
+    let input = "See [docs](https://example.com)[^n].\n\n[^n]: A note.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "man", "--name", "x"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "See docs <https://example.com>[1].\n");
+    assert_output_contains(&output, ".SH\nNOTES\n.IP [1] 5\nA note.\n");
+}
+
+#[test]
+fn test_convert_to_man_output_dir_uses_section_extension() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    std::fs::write(dir.path().join("tool.md"), "# Name\n\ntool\n").unwrap();
+
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &[
+            "convert-to",
+            "--output-dir",
+            "man",
+            "man",
+            "--section",
+            "5",
+            "tool.md",
+        ],
+    );
+
+    assert_success(&output);
+    assert!(dir.path().join("man/tool.5").exists());
+}
+
+#[test]
+fn test_convert_to_man_output_dir_uses_front_matter_section() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    std::fs::write(
+        dir.path().join("daemon.md"),
+        "---\ntitle: daemon(8)\n---\n# Name\n\ndaemon\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("config.md"),
+        "---\nsection: 5\n---\n# Name\n\nconfig\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("tool.md"), "# Name\n\ntool\n").unwrap();
+
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &[
+            "convert-to",
+            "--output-dir",
+            "man",
+            "man",
+            "daemon.md",
+            "config.md",
+            "tool.md",
+        ],
+    );
+
+    assert_success(&output);
+    let daemon = std::fs::read_to_string(dir.path().join("man/daemon.8")).unwrap();
+    assert!(daemon.starts_with(".TH \"DAEMON\" \"8\""), "{daemon}");
+    assert!(dir.path().join("man/config.5").exists());
+    assert!(dir.path().join("man/tool.1").exists());
+}
+
+#[test]
+fn test_convert_to_man_rejects_invalid_section() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    std::fs::write(
+        dir.path().join("tool.md"),
+        "---\nsection: 1/../../x\n---\n# Name\n\ntool\n",
+    )
+    .unwrap();
+
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &["convert-to", "--output-dir", "man", "man", "tool.md"],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Invalid man section \"1/../../x\""),
+        "{stderr}"
+    );
+    assert!(!dir.path().join("x").exists());
+}
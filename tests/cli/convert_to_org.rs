@@ -0,0 +1,99 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+
+fn markdown_to_org(input: &str) -> String {
+    let cmd = TestCommand::new();
+    let output = cmd.run_with_input(&["convert-to", "org"], input);
+    assert_success(&output);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_convert_to_org_headings_and_emphasis() {
+    let output = markdown_to_org(
+        "# Title\n\n## Section\n\nSome *emphasis*, **strong**, ~~gone~~ and `code`.\n",
+    );
+
+    assert_eq!(
+        output,
+        "* Title\n\n** Section\n\nSome /emphasis/, *strong*, +gone+ and ~code~.\n"
+    );
+}
+
+#[test]
+fn test_convert_to_org_front_matter_keywords() {
+    let output = markdown_to_org("---\ntitle: Notes\nauthor: Ann\ntags: [a, b]\n---\nText\n");
+
+    assert_eq!(
+        output,
+        "#+TITLE: Notes\n#+AUTHOR: Ann\n#+TAGS: a, b\n\nText\n"
+    );
+}
+
+#[test]
+fn test_convert_to_org_links_and_images() {
+    let output = markdown_to_org(
+        "[site](https://example.com), [doc](docs/a.md), <https://auto.example>\n\n![Logo](logo.png)\n",
+    );
+
+    assert_eq!(
+        output,
+        "[[https://example.com][site]], [[file:docs/a.md][doc]], https://auto.example\n\n\
+         #+ATTR_HTML: :alt Logo\n[[file:logo.png]]\n"
+    );
+}
+
+#[test]
+fn test_convert_to_org_source_block() {
+    let output = markdown_to_org("```rust\n* starred\nfn main() {}\n```\n\n```\nplain\n```\n");
+
+    assert_eq!(
+        output,
+        "#+BEGIN_SRC rust\n,* starred\nfn main() {}\n#+END_SRC\n\n#+BEGIN_EXAMPLE\nplain\n#+END_EXAMPLE\n"
+    );
+}
+
+#[test]
+fn test_convert_to_org_lists() {
+    let output = markdown_to_org("3. three\n4. four\n   - [x] done\n");
+
+    assert_eq!(output, "3. [@3] three\n4. four\n   - [X] done\n");
+}
+
+#[test]
+fn test_convert_to_org_table() {
+    let output = markdown_to_org("| Key | Value |\n|:--|--:|\n| a | 1 |\n");
+
+    assert_eq!(
+        output,
+        "| <l> | <r>   |\n| Key | Value |\n|-----+-------|\n| a   | 1     |\n"
+    );
+}
+
+#[test]
+fn test_convert_to_org_quote_alert_and_rule() {
+    let output = markdown_to_org("> quoted\n\n> [!WARNING]\n> careful\n\n---\n");
+
+    assert_eq!(
+        output,
+        "#+BEGIN_QUOTE\nquoted\n#+END_QUOTE\n\n#+BEGIN_WARNING\ncareful\n#+END_WARNING\n\n-----\n"
+    );
+}
+
+#[test]
+fn test_convert_to_org_footnotes() {
+    let output = markdown_to_org("Text[^note].\n\n[^note]: The note.\n\nAfter.\n");
+
+    assert_eq!(
+        output,
+        "Text[fn:note].\n\n[fn:note] The note.\n\n\nAfter.\n"
+    );
+}
+
+#[test]
+fn test_convert_to_org_guards_line_starts() {
+    let cmd = TestCommand::new();
+    let output = cmd.run_with_input(&["convert-to", "org", "--width", "3"], "Use * here\n");
+
+    assert_success(&output);
+    assert_output_contains(&output, "Use\n\u{200B}*\nhere\n");
+}
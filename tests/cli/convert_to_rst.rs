@@ -0,0 +1,152 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+
+#[test]
+fn test_convert_to_rst_headings_and_inline_markup() {
+    let cmd = TestCommand::new();
+    let input = "# Title\n\n## Section\n\nSome *emphasis*, **strong** and `code`.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "Title\n=====\n\nSection\n-------\n\nSome *emphasis*, **strong** and ``code``.\n"
+    );
+}
+
+#[test]
+fn test_convert_to_rst_markup_inside_words() {
+    let cmd = TestCommand::new();
+    let input = "A `co`de span\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "A ``co``\\ de span");
+}
+
+#[test]
+fn test_convert_to_rst_escapes_text() {
+    let cmd = TestCommand::new();
+    let input = "2 * 3 | name_ and snake_case\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "2 \\* 3 \\| name\\_ and snake_case");
+}
+
+#[test]
+fn test_convert_to_rst_escapes_line_start_once() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], "|\n\n\\*\n\n... more\n");
+
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "\\|\n\n\\*\n\n\\... more\n"
+    );
+}
+
+#[test]
+fn test_convert_to_rst_nested_list() {
+    let cmd = TestCommand::new();
+    let input = "- one\n- two\n  - nested\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "- one\n\n- two\n\n  - nested\n");
+}
+
+#[test]
+fn test_convert_to_rst_grid_table() {
+    let cmd = TestCommand::new();
+    let input = "| Fruit | Qty |\n|:--|--:|\n| apple | 3 |\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "+-------+-----+\n| Fruit | Qty |\n+=======+=====+\n| apple |   3 |\n+-------+-----+\n"
+    );
+}
+
+#[test]
+fn test_convert_to_rst_list_table() {
+    let cmd = TestCommand::new();
+    let input = "| Fruit | Qty |\n|---|---|\n| apple | 3 |\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst", "--table-style", "list-table"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        ".. list-table::\n   :header-rows: 1\n\n   * - Fruit\n     - Qty\n   * - apple\n     - 3\n"
+    );
+}
+
+#[test]
+fn test_convert_to_rst_code_block_and_admonition() {
+    let cmd = TestCommand::new();
+    let input = "```python\nprint(1)\n```\n\n> [!NOTE]\n> Remember.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        ".. code-block:: python\n\n   print(1)\n\n.. note::\n\n   Remember.\n"
+    );
+}
+
+#[test]
+fn test_convert_to_rst_link_references_and_footnotes() {
+    let cmd = TestCommand::new();
+    let input = "See [the site][home] and [docs](https://docs.rs)[^n].\n\n\
+                 [home]: https://example.com\n[^n]: The note.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "See `the site <home_>`__ and `docs <https://docs.rs>`__\\ [#n]_.\n\n\
+         .. _home: https://example.com\n\n.. [#n]\n   The note.\n"
+    );
+}
+
+#[test]
+fn test_convert_to_rst_inline_image_substitution() {
+    let cmd = TestCommand::new();
+    let input = "Logo ![the logo](logo.png) here\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "Logo |image1| here\n\n.. |image1| image:: logo.png\n   :alt: the logo\n"
+    );
+}
+
+#[test]
+fn test_convert_to_rst_document_title() {
+    let cmd = TestCommand::new();
+    let input = "---\ntitle: Report\nauthor: Ada\n---\nBody\n";
+
+    let output = cmd.run_with_input(&["convert-to", "rst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "======\nReport\n======\n\n:Author: Ada\n\nBody\n");
+}
@@ -0,0 +1,85 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+
+#[test]
+fn test_convert_to_text_removes_markup() {
+    let cmd = TestCommand::new();
+    let input = "# Hello World\n\nThis is **bold**, *emphasis* and `code`.\n\n> Quoted\n";
+
+    let output = cmd.run_with_input(&["convert-to", "text"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "Hello World\n\nThis is bold, emphasis and code.\n\n    Quoted\n"
+    );
+}
+
+#[test]
+fn test_convert_to_text_wraps_to_width() {
+    let cmd = TestCommand::new();
+    let input = "one two three four five six seven eight nine ten\n";
+
+    let output = cmd.run_with_input(&["convert-to", "text", "--width", "20"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "one two three four\nfive six seven eight\nnine ten\n"
+    );
+}
+
+#[test]
+fn test_convert_to_text_lists() {
+    let cmd = TestCommand::new();
+    let input = "- apple\n- kiwi\n  3. nested\n  4. again\n";
+
+    let output = cmd.run_with_input(&["convert-to", "text", "--bullet", "•"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "• apple\n• kiwi\n  3. nested\n  4. again\n");
+}
+
+#[test]
+fn test_convert_to_text_aligned_table() {
+    let cmd = TestCommand::new();
+    let input = "| Fruit | Qty |\n|:--|--:|\n| apple | 3 |\n| kiwi | 12 |\n";
+
+    let output = cmd.run_with_input(&["convert-to", "text"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "Fruit  Qty\n-----  ---\napple    3\nkiwi    12\n");
+}
+
+#[test]
+fn test_convert_to_text_link_footnotes() {
+    let cmd = TestCommand::new();
+    let input = "See [the docs](https://example.com/docs) and [more][ref].\n\n[ref]: https://example.com/more\n";
+
+    let output = cmd.run_with_input(&["convert-to", "text", "--link-footnotes"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "See the docs[1] and more[2].\n\n[1] https://example.com/docs\n[2] https://example.com/more\n"
+    );
+}
+
+#[test]
+fn test_convert_to_text_links_without_footnotes() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &["convert-to", "text"],
+        "See [the docs](https://example.com/docs).\n",
+    );
+
+    assert_success(&output);
+    assert_output_contains(&output, "See the docs.");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("https://"));
+}
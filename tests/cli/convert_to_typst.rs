@@ -0,0 +1,135 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+
+#[test]
+fn test_convert_to_typst_inline_markup() {
+    let cmd = TestCommand::new();
+    let input = "# Title\n\nSome *emphasis*, **strong**, ~~gone~~ and `code`.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "= Title\n\nSome #emph[emphasis], #strong[strong], #strike[gone] and `code`.\n"
+    );
+}
+
+#[test]
+fn test_convert_to_typst_escapes_special_characters() {
+    let cmd = TestCommand::new();
+    let input = "Costs $5 #1 <a> @me // not a comment\n\n1. item\n\n\\= not a heading\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "Costs \\$5 \\#1 \\<a\\> \\@me \\// not a comment");
+    assert_output_contains(&output, "\\= not a heading");
+}
+
+#[test]
+fn test_convert_to_typst_links_and_images() {
+    let cmd = TestCommand::new();
+    let input = "See [the site](https://example.com) and ![a \"cat\"](cat.png).\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "#link(\"https://example.com\")[the site]");
+    assert_output_contains(&output, "#image(\"cat.png\", alt: \"a \\\"cat\\\"\")");
+}
+
+#[test]
+fn test_convert_to_typst_lists() {
+    let cmd = TestCommand::new();
+    let input = "3. three\n4. four\n   - nested\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "3. three\n4. four\n   - nested\n");
+}
+
+#[test]
+fn test_convert_to_typst_table_alignment() {
+    let cmd = TestCommand::new();
+    let input = "| A | B | C |\n|:--|:-:|--:|\n| 1 | 2 | 3 |\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "#table(\n  columns: 3,\n  align: (left, center, right),\n  table.header([A], [B], [C]),\n  [1], [2], [3],\n)\n"
+    );
+}
+
+#[test]
+fn test_convert_to_typst_booktabs_table_style() {
+    let cmd = TestCommand::new();
+    let input = "| A |\n|---|\n| 1 |\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst", "--table-style", "booktabs"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "align: (auto,),");
+    assert_output_contains(&output, "stroke: none,");
+    assert_output_contains(&output, "table.hline(),");
+}
+
+#[test]
+fn test_convert_to_typst_code_block_language() {
+    let cmd = TestCommand::new();
+    let input = "```rust\nlet x = `a`;\n```\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "```rust\nlet x = `a`;\n```\n");
+}
+
+#[test]
+fn test_convert_to_typst_footnotes() {
+    let cmd = TestCommand::new();
+    let input = "First[^note] and again[^note].\n\n[^note]: The *note*.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst"], input);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "First#footnote[The #emph[note].]<fn-note> and again#footnote(<fn-note>).\n"
+    );
+}
+
+#[test]
+fn test_convert_to_typst_alert_callout() {
+    let cmd = TestCommand::new();
+    let input = "> [!TIP]\n> Use it.\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "stroke: (left: 3pt + rgb(\"#1a7f37\"))");
+    assert_output_contains(&output, "  #strong[Tip]\n\n  Use it.\n]");
+}
+
+#[test]
+fn test_convert_to_typst_document_metadata() {
+    let cmd = TestCommand::new();
+    let input = "---\ntitle: Report\nauthor: Ada\n---\nBody\n";
+
+    let output = cmd.run_with_input(&["convert-to", "typst", "--title-block"], input);
+
+    assert_success(&output);
+    assert_output_contains(
+        &output,
+        "#set document(title: \"Report\", author: \"Ada\")\n",
+    );
+    assert_output_contains(&output, "#text(size: 1.6em, weight: \"bold\")[Report]");
+    assert_output_contains(&output, "#linebreak() Ada");
+}
@@ -0,0 +1,116 @@
+use super::{create_temp_file, TestCommand};
+
+#[test]
+fn test_json_parse_error_shows_position() {
+    let cmd = TestCommand::new();
+    let input = "{\"blocks\": [\n  {\"Paragraph\": 3}\n]}";
+
+    let output = cmd.run_with_input(&["convert-to", "-f", "ast-json", "html"], input);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with("error: invalid type"), "{stderr}");
+    assert!(stderr.contains("--> <stdin>:2:17"), "{stderr}");
+    assert!(stderr.contains("2 |   {\"Paragraph\": 3}"), "{stderr}");
+    assert!(stderr.contains("  |                 ^"), "{stderr}");
+}
+
+#[test]
+fn test_yaml_parse_error_shows_file_path() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file("blocks:\n  - Paragraph: [\n");
+    let path = file.path().to_str().unwrap();
+
+    let output = cmd.run(&["convert-to", "-f", "ast-yaml", "markdown", path]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&format!("--> {path}:2:")), "{stderr}");
+    assert!(stderr.contains("^"), "{stderr}");
+}
+
+#[test]
+fn test_config_error_shows_position() {
+    let cmd = TestCommand::new();
+    let config = create_temp_file("[markdown]\nwidth = \"wide\"\n");
+    let path = config.path().to_str().unwrap();
+
+    let output = cmd.run(&["--config", path, "config", "show"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&format!("--> {path}:2:9")), "{stderr}");
+}
+
+#[test]
+fn test_lint_diagnostics_have_positions() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file("# Title\n\nText with ![](a.png).\n\n#### Deep\n");
+    let path = file.path().to_str().unwrap();
+
+    let output = cmd.run(&["lint", path]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("--> {path}:3:")), "{stdout}");
+    assert!(stdout.contains("5 | #### Deep"), "{stdout}");
+
+    let output = cmd.run(&["lint", "--report", "json", path]);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report[0]["rule"], "image-alt-text");
+    assert_eq!(report[0]["line"], 3);
+    assert_eq!(report[1]["rule"], "heading-increment");
+    assert_eq!(report[1]["line"], 5);
+    assert_eq!(report[1]["column"], 1);
+}
+
+#[test]
+fn test_lint_positions_inside_list_items() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file(
+        "- Item\n\n  ```\n  code\n  ```\n\n- # Listed\n\n#### Deep\n\nSee [here](#).\n",
+    );
+    let path = file.path().to_str().unwrap();
+
+    let output = cmd.run(&["lint", "--report", "json", path]);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report[0]["rule"], "code-block-language");
+    assert_eq!(report[0]["line"], 3);
+    assert_eq!(report[0]["column"], 3);
+    assert_eq!(report[1]["rule"], "heading-increment");
+    assert_eq!(report[1]["line"], 9);
+    // The caret points at the start of the link, not its closing bracket
+    assert_eq!(report[2]["rule"], "empty-link");
+    assert_eq!(report[2]["line"], 11);
+    assert_eq!(report[2]["column"], 5);
+}
+
+#[test]
+fn test_lint_omits_positions_it_cannot_match() {
+    let cmd = TestCommand::new();
+    // The parser does not read the quoted line as a heading, so the scanned
+    // headings do not line up with the parsed ones
+    let file = create_temp_file("# Title\n\n> ## Quoted\n\n#### Deep\n");
+    let path = file.path().to_str().unwrap();
+
+    let output = cmd.run(&["lint", "--report", "json", path]);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report[0]["rule"], "heading-increment");
+    assert!(report[0].get("line").is_none(), "{report}");
+}
+
+#[test]
+fn test_yaml_scan_error_has_single_location() {
+    let cmd = TestCommand::new();
+    let input = "blocks:\n  - \"unclosed\n";
+
+    let output = cmd.run_with_input(&["convert-to", "-f", "ast-yaml", "html"], input);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr
+            .starts_with("error: found unexpected end of stream, while scanning a quoted scalar\n"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("at line"), "{stderr}");
+    assert!(stderr.contains("--> <stdin>:3:1"), "{stderr}");
+}
@@ -0,0 +1,191 @@
+use super::{assert_success, TestCommand};
+use std::fs;
+use tempfile::TempDir;
+
+const RUNBOOK: &str = "# Runbook
+
+## Setup
+
+```sh file=setup.sh
+#!/bin/sh
+set -e
+```
+
+```sql
+create table t (id int);
+```
+
+### Data
+
+```sql
+insert into t values (1);
+```
+
+```sh file=setup.sh
+echo done
+```
+
+```json
+{\"debug\": true}
+```
+";
+
+/// Directory with `runbook.md` after running `extract-code` with `args` in it.
+fn extract(args: &[&str]) -> (TempDir, String) {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("runbook.md"), RUNBOOK).unwrap();
+    let mut all_args = vec!["extract-code"];
+    all_args.extend_from_slice(args);
+    all_args.push("runbook.md");
+
+    let cmd = TestCommand::new();
+    let output = cmd.run_in_dir(dir.path(), &all_args);
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    (dir, stdout)
+}
+
+#[test]
+fn test_extract_code_file_attribute() {
+    let (dir, stdout) = extract(&[]);
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("setup.sh")).unwrap(),
+        "#!/bin/sh\nset -e\necho done\n"
+    );
+    assert!(stdout.contains("Extracted: ./setup.sh"));
+    // Blocks without a file attribute are only written with --per-language
+    assert!(!dir.path().join("runbook.sql").exists());
+}
+
+#[test]
+fn test_extract_code_per_language() {
+    let (dir, _) = extract(&["--per-language", "--output-dir", "out"]);
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("out/runbook.sql")).unwrap(),
+        "create table t (id int);\ninsert into t values (1);\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("out/runbook.json")).unwrap(),
+        "{\"debug\": true}\n"
+    );
+    assert!(dir.path().join("out/setup.sh").exists());
+}
+
+#[test]
+fn test_extract_code_filters() {
+    let (dir, _) = extract(&["--per-language", "--lang", "sql"]);
+    assert!(dir.path().join("runbook.sql").exists());
+    assert!(!dir.path().join("setup.sh").exists());
+    assert!(!dir.path().join("runbook.json").exists());
+
+    let (dir, _) = extract(&["--per-language", "--attr", "file=setup.sh"]);
+    assert!(dir.path().join("setup.sh").exists());
+    assert!(!dir.path().join("runbook.sql").exists());
+}
+
+#[test]
+fn test_extract_code_comments() {
+    let (dir, _) = extract(&["--per-language", "--comments"]);
+
+    // The shebang stays on the first line
+    assert_eq!(
+        fs::read_to_string(dir.path().join("setup.sh")).unwrap(),
+        "#!/bin/sh\n# runbook.md: Runbook > Setup\nset -e\n# runbook.md: Runbook > Setup > Data\necho done\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("runbook.sql")).unwrap(),
+        "-- runbook.md: Runbook > Setup\ncreate table t (id int);\n-- runbook.md: Runbook > Setup > Data\ninsert into t values (1);\n"
+    );
+    // JSON has no comments
+    assert_eq!(
+        fs::read_to_string(dir.path().join("runbook.json")).unwrap(),
+        "{\"debug\": true}\n"
+    );
+}
+
+#[test]
+fn test_extract_code_from_stdin() {
+    let dir = TempDir::new().unwrap();
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &[
+            "extract-code",
+            "--per-language",
+            "--output-dir",
+            dir.path().to_str().unwrap(),
+        ],
+        "```python\nprint(1)\n```\n",
+    );
+
+    assert_success(&output);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("snippets.py")).unwrap(),
+        "print(1)\n"
+    );
+}
+
+#[test]
+fn test_extract_code_rejects_paths_outside_output_dir() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["extract-code"], "```sh file=../evil.sh\nrm -rf /\n```\n");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("must be a relative path inside the output directory: ../evil.sh"));
+}
+
+#[test]
+fn test_extract_code_rejects_languages_leaving_output_dir() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("runbook.md"),
+        "```x/../../escaped\ncontent\n```\n",
+    )
+    .unwrap();
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &[
+            "extract-code",
+            "--per-language",
+            "--output-dir",
+            "out/sub",
+            "runbook.md",
+        ],
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("must be a relative path inside the output directory"));
+    assert!(!dir.path().join("out/escaped").exists());
+    assert!(!dir.path().join("escaped").exists());
+}
+
+#[test]
+fn test_extract_code_per_language_rejects_inputs_with_the_same_name() {
+    let dir = TempDir::new().unwrap();
+    for input in ["docs", "ops"] {
+        fs::create_dir_all(dir.path().join(input)).unwrap();
+        fs::write(
+            dir.path().join(input).join("a.md"),
+            "```sql\nselect 1;\n```\n",
+        )
+        .unwrap();
+    }
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &["extract-code", "--per-language", "docs/a.md", "ops/a.md"],
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Inputs docs/a.md and ops/a.md would both be extracted to a.sql"));
+    assert!(!dir.path().join("a.sql").exists());
+}
@@ -0,0 +1,161 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+use std::fs;
+use tempfile::TempDir;
+
+const UNFORMATTED: &str = "# Title\n\nSome   text\n\n* a\n* b\n";
+const FORMATTED: &str = "# Title\n\nSome text\n\n * a\n * b\n";
+
+/// Directory with an unformatted `doc.md` and a formatted `ok.md`.
+fn documents() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("doc.md"), UNFORMATTED).unwrap();
+    fs::write(dir.path().join("ok.md"), FORMATTED).unwrap();
+    dir
+}
+
+#[test]
+fn test_diff_prints_changes_without_writing() {
+    let dir = documents();
+
+    let output = TestCommand::new().run_in_dir(dir.path(), &["format", "--diff", "doc.md"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "--- doc.md
++++ doc.md
+@@ -1,6 +1,6 @@
+ # Title
+ 
+-Some   text
++Some text
+ 
+-* a
+-* b
++ * a
++ * b
+"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("doc.md")).unwrap(),
+        UNFORMATTED
+    );
+}
+
+#[test]
+fn test_diff_of_formatted_files() {
+    let dir = documents();
+
+    let output = TestCommand::new().run_in_dir(dir.path(), &["format", "--diff", "ok.md"]);
+
+    assert_success(&output);
+    assert_output_contains(&output, "All files are already formatted");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("@@"));
+}
+
+#[test]
+fn test_diff_colors() {
+    let dir = documents();
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_in_dir(
+        dir.path(),
+        &["format", "--diff", "--color", "always", "doc.md"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\x1b[36m@@ -1,6 +1,6 @@\x1b[0m"));
+    assert!(stdout.contains("\x1b[31m-Some   text\x1b[0m"));
+    assert!(stdout.contains("\x1b[32m+Some text\x1b[0m"));
+
+    let output = cmd.run_in_dir(dir.path(), &["format", "--diff", "doc.md"]);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains('\x1b'));
+}
+
+#[test]
+fn test_diff_missing_final_newline() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("doc.md"), "Some   text").unwrap();
+
+    let output = TestCommand::new().run_in_dir(dir.path(), &["format", "--diff", "doc.md"]);
+
+    assert_output_contains(
+        &output,
+        "@@ -1 +1 @@\n-Some   text\n\\ No newline at end of file\n+Some text\n\\ No newline at end of file\n",
+    );
+}
+
+#[test]
+fn test_diff_of_completely_rewritten_file() {
+    let dir = TempDir::new().unwrap();
+    let lines = 5000;
+    let document: String = (0..lines).map(|i| format!("- item   {i}\n")).collect();
+    fs::write(dir.path().join("doc.md"), document).unwrap();
+
+    let output = TestCommand::new().run_in_dir(
+        dir.path(),
+        &["format", "--diff", "--color", "never", "doc.md"],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let diff: Vec<&str> = stdout.lines().collect();
+    assert_eq!(diff[2], "@@ -1,5000 +1,5000 @@");
+    assert_eq!(diff.len(), 3 + 2 * lines);
+    assert_eq!(diff[3], "-- item   0");
+    assert_eq!(diff[3 + lines], "+ - item 0");
+    assert_eq!(diff[2 + 2 * lines], "+ - item 4999");
+}
+
+#[test]
+fn test_json_report_in_dry_run() {
+    let dir = documents();
+
+    let output = TestCommand::new().run_in_dir(
+        dir.path(),
+        &["format", "--dry-run", "--report", "json", "doc.md", "ok.md"],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        report,
+        serde_json::json!([
+            {
+                "file": "doc.md",
+                "status": "needs-formatting",
+                "changes": [
+                    {
+                        "original": {"start": 3, "count": 1},
+                        "formatted": {"start": 3, "count": 1}
+                    },
+                    {
+                        "original": {"start": 5, "count": 2},
+                        "formatted": {"start": 5, "count": 2}
+                    }
+                ]
+            },
+            {"file": "ok.md", "status": "unchanged"}
+        ])
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("doc.md")).unwrap(),
+        UNFORMATTED
+    );
+}
+
+#[test]
+fn test_json_report_when_formatting() {
+    let dir = documents();
+
+    let output =
+        TestCommand::new().run_in_dir(dir.path(), &["format", "--report", "json", "doc.md"]);
+
+    assert_success(&output);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report[0]["status"], "formatted");
+    assert_eq!(report[0]["changes"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("doc.md")).unwrap(),
+        FORMATTED
+    );
+}
@@ -0,0 +1,146 @@
+use super::{assert_success, TestCommand};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const UNFORMATTED: &str = "* item\n";
+const FORMATTED: &str = " * item\n";
+
+/// Directory containing the given files, all in need of formatting.
+fn tree(files: &[&str]) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    for file in files {
+        let path = dir.path().join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, UNFORMATTED).unwrap();
+    }
+    dir
+}
+
+/// Lines printed by `format --dry-run` with `args` in `dir`.
+fn needs_formatting(dir: &Path, args: &[&str]) -> Vec<String> {
+    let mut all_args = vec!["format", "--dry-run"];
+    all_args.extend_from_slice(args);
+    let output = TestCommand::new().run_in_dir(dir, &all_args);
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("File needs formatting: "))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_format_directory_recursively() {
+    let dir = tree(&["a.md", "docs/b.markdown", "docs/guide/c.md", "notes.txt"]);
+
+    let output = TestCommand::new().run_in_dir(dir.path(), &["format", "."]);
+
+    assert_success(&output);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Formatted: a.md\nFormatted: docs/b.markdown\nFormatted: docs/guide/c.md\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("docs/guide/c.md")).unwrap(),
+        FORMATTED
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("notes.txt")).unwrap(),
+        UNFORMATTED
+    );
+}
+
+#[test]
+fn test_output_order_follows_arguments_and_paths() {
+    let dir = tree(&["z.md", "docs/b.md", "docs/a.md", "docs/sub/a.md"]);
+
+    assert_eq!(
+        needs_formatting(dir.path(), &["z.md", "docs", "docs/a.md"]),
+        ["z.md", "docs/a.md", "docs/b.md", "docs/sub/a.md"]
+    );
+}
+
+#[test]
+fn test_files_named_twice_are_formatted_once() {
+    let dir = tree(&["a.md", "docs/b.md"]);
+
+    assert_eq!(
+        needs_formatting(dir.path(), &["a.md", "./a.md", "docs", "./docs/b.md"]),
+        ["a.md", "docs/b.md"]
+    );
+}
+
+#[test]
+fn test_ignore_files() {
+    let dir = tree(&[
+        "keep.md",
+        "build/out.md",
+        "docs/draft.md",
+        "docs/final.md",
+        "docs/api/private.md",
+        "docs/api/public.md",
+    ]);
+    fs::write(dir.path().join(".gitignore"), "build/\n*draft*\n").unwrap();
+    fs::write(
+        dir.path().join("docs/.markdown-toolignore"),
+        "# Generated\napi/*.md\n!public.md\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        needs_formatting(dir.path(), &["."]),
+        ["docs/api/public.md", "docs/final.md", "keep.md"]
+    );
+}
+
+#[test]
+fn test_ignore_files_of_enclosing_repository() {
+    let dir = tree(&["docs/a.md", "docs/generated/b.md"]);
+    fs::create_dir(dir.path().join(".git")).unwrap();
+    fs::write(dir.path().join(".gitignore"), "/docs/generated\n").unwrap();
+
+    assert_eq!(needs_formatting(&dir.path().join("docs"), &["."]), ["a.md"]);
+}
+
+#[test]
+fn test_explicit_files_are_not_ignored() {
+    let dir = tree(&["build/out.md"]);
+    fs::write(dir.path().join(".gitignore"), "build/\n").unwrap();
+
+    assert!(needs_formatting(dir.path(), &["."]).is_empty());
+    assert_eq!(
+        needs_formatting(dir.path(), &["build/out.md"]),
+        ["build/out.md"]
+    );
+}
+
+#[test]
+fn test_include_and_exclude() {
+    let dir = tree(&["a.md", "b.mdx", "vendor/c.md", "docs/old/d.md", "docs/e.md"]);
+
+    assert_eq!(
+        needs_formatting(dir.path(), &["--include", "*.mdx", "."]),
+        ["b.mdx"]
+    );
+    assert_eq!(
+        needs_formatting(
+            dir.path(),
+            &["--exclude", "vendor", "--exclude", "docs/old", "."]
+        ),
+        ["a.md", "docs/e.md"]
+    );
+    assert_eq!(
+        needs_formatting(dir.path(), &["--include", "docs/**/*.md", "."]),
+        ["docs/e.md", "docs/old/d.md"]
+    );
+}
+
+#[test]
+fn test_invalid_glob() {
+    let dir = tree(&["a.md"]);
+
+    let output = TestCommand::new().run_in_dir(dir.path(), &["format", "--exclude", "[", "."]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid glob pattern: ["));
+}
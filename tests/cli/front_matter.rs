@@ -0,0 +1,151 @@
+use super::{assert_output_contains, assert_success, create_temp_file, TestCommand};
+use std::fs;
+
+const YAML_DOCUMENT: &str =
+    "---\ntitle: \"Guide & Notes\"\nauthor: [Ann, Bob]\n---\n\n# Hello\n\nSome   text.\n";
+
+const TOML_DOCUMENT: &str = "+++\ntitle = \"Guide\"\ndate = 2024-01-02\n+++\n\n# Hello\n";
+
+#[test]
+fn test_format_keeps_front_matter_verbatim() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file(YAML_DOCUMENT);
+
+    let output = cmd.run(&["format", file.path().to_str().unwrap()]);
+
+    assert_success(&output);
+    let content = fs::read_to_string(file.path()).unwrap();
+    assert_eq!(
+        content,
+        "---\ntitle: \"Guide & Notes\"\nauthor: [Ann, Bob]\n---\n\n# Hello\n\nSome text.\n"
+    );
+}
+
+#[test]
+fn test_convert_to_markdown_keeps_toml_front_matter() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["convert-to", "markdown"], TOML_DOCUMENT);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("+++\ntitle = \"Guide\"\ndate = 2024-01-02\n+++\n\n# Hello"));
+}
+
+#[test]
+fn test_ast_json_exposes_front_matter() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["convert-to", "ast-json"], YAML_DOCUMENT);
+
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["front_matter"]["format"], "yaml");
+    assert_eq!(json["front_matter"]["data"]["title"], "Guide & Notes");
+    assert_eq!(json["front_matter"]["data"]["author"][1], "Bob");
+    // The front matter is not part of the document body
+    assert_eq!(json["blocks"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_ast_json_roundtrip_restores_front_matter() {
+    let cmd = TestCommand::new();
+
+    let json = cmd.run_with_input(&["convert-to", "ast-json"], TOML_DOCUMENT);
+    assert_success(&json);
+    let output = cmd.run_with_input(
+        &["convert-to", "-f", "ast-json", "markdown"],
+        &String::from_utf8_lossy(&json.stdout),
+    );
+
+    assert_success(&output);
+    assert_output_contains(
+        &output,
+        "+++\ndate = \"2024-01-02\"\ntitle = \"Guide\"\n+++",
+    );
+    assert_output_contains(&output, "# Hello");
+}
+
+#[test]
+fn test_html_title_block() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["convert-to", "html", "--title-block"], YAML_DOCUMENT);
+
+    assert_success(&output);
+    assert_output_contains(&output, "<h1 class=\"title\">Guide &amp; Notes</h1>");
+    assert_output_contains(&output, "<p class=\"author\">Ann, Bob</p>");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("title:"));
+}
+
+#[test]
+fn test_latex_title_block() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["convert-to", "latex", "--title-block"], YAML_DOCUMENT);
+
+    assert_success(&output);
+    assert_output_contains(&output, "\\title{Guide \\& Notes}");
+    assert_output_contains(&output, "\\author{Ann, Bob}");
+    assert_output_contains(&output, "\\maketitle");
+}
+
+#[test]
+fn test_invalid_front_matter_is_reported() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &["convert-to", "html"],
+        "+++\ntitle = [unclosed\n+++\n# Hello\n",
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid TOML front matter"));
+    assert!(stderr.contains("<stdin>:2:"));
+}
+
+#[test]
+fn test_thematic_breaks_are_not_front_matter() {
+    let cmd = TestCommand::new();
+    let input = "---\n\nSome intro text.\n\n---\n\n# Title\n";
+
+    let output = cmd.run_with_input(&["convert-to", "markdown"], input);
+    assert_success(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), input);
+
+    let file = create_temp_file(input);
+    let path = file.path().to_str().unwrap();
+    assert_success(&cmd.run(&["format", path]));
+    assert!(fs::read_to_string(path)
+        .unwrap()
+        .contains("Some intro text."));
+}
+
+#[test]
+fn test_text_between_rules_that_is_not_yaml() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &["convert-to", "html"],
+        "---\nNote: this is: bad\n---\n# Hello\n",
+    );
+
+    assert_success(&output);
+    assert_output_contains(&output, "Note: this is: bad");
+    assert_output_contains(&output, "<h1>Hello</h1>");
+}
+
+#[test]
+fn test_lint_ignores_front_matter() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file("---\ntitle: x\n---\n# Hello\n\n# Hello\n");
+
+    let output = cmd.run(&["lint", file.path().to_str().unwrap()]);
+
+    assert_success(&output);
+    assert_output_contains(&output, ":6:1");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("warning[").count(), 1);
+}
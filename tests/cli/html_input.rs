@@ -0,0 +1,134 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+
+fn html_to_markdown(input: &str) -> String {
+    let cmd = TestCommand::new();
+    let output = cmd.run_with_input(&["convert-to", "--from", "html", "markdown"], input);
+    assert_success(&output);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_html_input_headings_and_inline_styles() {
+    let output = html_to_markdown(
+        "<h1>Title</h1>\n<h3>Sub</h3>\n<p>Some <em>emphasis</em>, <b> bold</b>, <del>old</del> and <code>code</code>.</p>\n",
+    );
+
+    assert_eq!(
+        output,
+        "# Title\n\n### Sub\n\nSome *emphasis*, **bold**, ~~old~~ and `code`.\n"
+    );
+}
+
+#[test]
+fn test_html_input_links_and_images() {
+    let output = html_to_markdown(
+        "<p><a href=\"https://example.com\" title=\"Home\">site</a> <img src=\"logo.png\" alt=\"Logo\"> <a name=\"anchor\">plain</a></p>",
+    );
+
+    assert_eq!(
+        output,
+        "[site](https://example.com \"Home\") ![Logo](logo.png) plain\n"
+    );
+}
+
+#[test]
+fn test_html_input_implied_end_tags_and_whitespace() {
+    let output = html_to_markdown("<p>first\n   paragraph<p>second<br>\n  line\n");
+
+    assert_eq!(output, "first paragraph\n\nsecond  \nline\n");
+}
+
+#[test]
+fn test_html_input_lists() {
+    let output = html_to_markdown(
+        "<ul><li>one<li><input type=\"checkbox\" checked> done<li><input type=\"checkbox\"> todo</ul><ol start=\"3\"><li>three</li><li>four</li></ol>",
+    );
+
+    assert_eq!(
+        output,
+        " - one\n - [X] done\n - [ ] todo\n\n 3. three\n 4. four\n"
+    );
+}
+
+#[test]
+fn test_html_input_code_block_with_language() {
+    let output = html_to_markdown(
+        "<pre><code class=\"language-rust\">fn main() {\n    println!(\"&lt;hi&gt;\");\n}\n</code></pre>",
+    );
+
+    assert_eq!(
+        output,
+        "```rust\nfn main() {\n    println!(\"<hi>\");\n}\n```\n"
+    );
+}
+
+#[test]
+fn test_html_input_table() {
+    let cmd = TestCommand::new();
+    let input = "<table><thead><tr><th>Key</th><th align=\"right\">Value</th></tr></thead>\
+                 <tbody><tr><td>a|b</td><td>1</td></tr><tr><td>c</td></tr></tbody></table>";
+
+    let output = cmd.run_with_input(&["convert-to", "-f", "html", "ast-json"], input);
+
+    assert_success(&output);
+    assert_output_contains(&output, "\"alignments\":[\"None\",\"Right\"]");
+    assert_output_contains(&output, "{\"Text\":\"a\\\\|b\"}");
+    assert_output_contains(&output, "[[{\"Text\":\"c\"}],[]]");
+}
+
+#[test]
+fn test_html_input_unmapped_elements_are_kept_as_html() {
+    let output = html_to_markdown(
+        "<details><summary>More</summary>\n\n<p>Hidden</p></details>\n<table><tr><td colspan=\"2\">merged</td></tr></table>\n<p>Text <span class=\"k\">kept</span></p><!-- note -->",
+    );
+
+    assert_eq!(
+        output,
+        "<details><summary>More</summary>\n<p>Hidden</p></details>\n\n\
+         <table><tr><td colspan=\"2\">merged</td></tr></table>\n\n\
+         Text <span class=\"k\">kept</span>\n\n<!-- note -->\n"
+    );
+}
+
+#[test]
+fn test_html_input_escapes_markdown_syntax() {
+    let output = html_to_markdown(
+        "<p>2 * 3 &lt;b&gt; [x] snake_case</p><p>1. not a list</p><p># not a heading</p>",
+    );
+
+    assert_eq!(
+        output,
+        "2 \\* 3 \\<b> \\[x\\] snake_case\n\n1\\. not a list\n\n\\# not a heading\n"
+    );
+}
+
+#[test]
+fn test_html_input_head_becomes_front_matter() {
+    let output = html_to_markdown(
+        "<!DOCTYPE html><html><head><title>My  Page</title><meta name=\"author\" content=\"Jane &amp; Co\"><link rel=\"stylesheet\" href=\"s.css\"></head><body><div><p>Body</p></div></body></html>",
+    );
+
+    assert_eq!(
+        output,
+        "---\nauthor: Jane & Co\ntitle: My Page\n---\n\nBody\n"
+    );
+}
+
+#[test]
+fn test_html_input_blockquote_and_rule() {
+    let output = html_to_markdown("<blockquote><p>Quoted</p></blockquote><hr><p>After</p>");
+
+    assert_eq!(output, "> Quoted\n\n---\n\nAfter\n");
+}
+
+#[test]
+fn test_html_input_keeps_inline_svg_in_paragraph() {
+    let output = html_to_markdown(
+        "<p>Icon <svg viewBox=\"0 0 9 9\"><g><path d=\"M0\"/><circle></circle></g></svg> done.</p><p>Next</p>",
+    );
+
+    assert_eq!(
+        output,
+        "Icon <svg viewBox=\"0 0 9 9\"><g><path d=\"M0\"/><circle></circle></g></svg> done.\n\nNext\n"
+    );
+}
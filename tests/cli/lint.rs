@@ -0,0 +1,127 @@
+use super::{assert_output_contains, assert_success, create_temp_file, TestCommand};
+use std::fs;
+
+#[test]
+fn test_lint_clean_document() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file(
+        "# Title\n\n## Section\n\nSee <https://example.com>.\n\n```rust\nfn main() {}\n```\n",
+    );
+
+    let output = cmd.run(&["lint", file.path().to_str().unwrap()]);
+
+    assert_success(&output);
+    assert_output_contains(&output, "No problems found");
+}
+
+#[test]
+fn test_lint_reports_heading_problems() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file("# Title\n\n### Deep heading.\n\n## Title\n");
+
+    let output = cmd.run(&["lint", file.path().to_str().unwrap()]);
+
+    assert_success(&output);
+    assert_output_contains(&output, "warning[heading-increment]");
+    assert_output_contains(&output, "warning[heading-punctuation]");
+    assert_output_contains(&output, "warning[duplicate-heading]");
+}
+
+#[test]
+fn test_lint_reports_links_images_and_code() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file(
+        "Visit https://example.com or [here](#).\n\n![](diagram.png)\n\n```\nplain\n```\n",
+    );
+
+    let output = cmd.run(&["lint", file.path().to_str().unwrap()]);
+
+    // empty-link is an error by default
+    assert!(!output.status.success());
+    assert_output_contains(&output, "[bare-url]");
+    assert_output_contains(&output, "error[empty-link]");
+    assert_output_contains(&output, "[image-alt-text]");
+    assert_output_contains(&output, "[code-block-language]");
+}
+
+#[test]
+fn test_lint_disable_rules() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file("[here](#)\n\n```\nplain\n```\n");
+
+    let output = cmd.run(&[
+        "lint",
+        "--disable",
+        "empty-link,code-block-language",
+        file.path().to_str().unwrap(),
+    ]);
+
+    assert_success(&output);
+    assert_output_contains(&output, "No problems found");
+}
+
+#[test]
+fn test_lint_rules_from_config_file() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(".markdown-tool.toml"),
+        "[lint.rules]\nheading-increment = \"error\"\nbare-url = \"off\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("doc.md"),
+        "# A\n\n### B\n\nhttps://example.com\n",
+    )
+    .unwrap();
+
+    let output = cmd.run_in_dir(dir.path(), &["lint", "doc.md"]);
+
+    assert!(!output.status.success());
+    assert_output_contains(&output, "error[heading-increment]");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("bare-url"));
+}
+
+#[test]
+fn test_lint_json_report() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file("![](a.png)\n");
+
+    let output = cmd.run(&["lint", "--report", "json", file.path().to_str().unwrap()]);
+
+    assert_success(&output);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let first = &report[0];
+    assert_eq!(first["rule"], "image-alt-text");
+    assert_eq!(first["severity"], "warning");
+    assert!(first["file"].as_str().is_some());
+}
+
+#[test]
+fn test_lint_unknown_rule() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file("# Title\n");
+
+    let output = cmd.run(&[
+        "lint",
+        "--disable",
+        "no-such-rule",
+        file.path().to_str().unwrap(),
+    ]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unknown lint rule: no-such-rule"));
+}
+
+#[test]
+fn test_lint_list_rules() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run(&["lint", "--list-rules"]);
+
+    assert_success(&output);
+    assert_output_contains(&output, "heading-increment");
+    assert_output_contains(&output, "code-block-language");
+}
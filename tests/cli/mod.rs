@@ -1,10 +1,35 @@
+pub mod code_format;
+pub mod config_file;
+pub mod convert_to_asciidoc;
 pub mod convert_to_ast;
+pub mod convert_to_docx;
+pub mod convert_to_epub;
+pub mod convert_to_files;
 pub mod convert_to_html;
 pub mod convert_to_latex;
+pub mod convert_to_man;
 pub mod convert_to_markdown;
+pub mod convert_to_org;
+pub mod convert_to_rst;
+pub mod convert_to_text;
+pub mod convert_to_typst;
+pub mod diagnostics;
 pub mod error_handling;
+pub mod extract_code;
+pub mod format_diff;
+pub mod format_directory;
+pub mod front_matter;
+pub mod html_input;
+pub mod lint;
+pub mod org_input;
+pub mod output_file;
+pub mod query;
+pub mod standalone;
+pub mod template;
+pub mod toc;
+pub mod watch;
 
-use std::io::Write;
+use std::io::{Cursor, Read, Write};
 use std::process::Command;
 use tempfile::NamedTempFile;
 
@@ -41,6 +66,25 @@ pub fn run(&self, args: &[&str]) -> std::process::Output {
             .output()
             .expect("Failed to execute command")
     }
+
+    pub fn run_in_dir(&self, dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+        Command::new(&self.binary_path)
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to execute command")
+    }
+
+    /// Start a long-running command, e.g. with `--watch`; the caller must kill it.
+    pub fn spawn_in_dir(&self, dir: &std::path::Path, args: &[&str]) -> std::process::Child {
+        Command::new(&self.binary_path)
+            .args(args)
+            .current_dir(dir)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .expect("Failed to start command")
+    }
 }
 
 #[allow(dead_code)]
@@ -51,6 +95,22 @@ pub fn create_temp_file(content: &str) -> NamedTempFile {
     file
 }
 
+/// Content of an entry of a DOCX or EPUB package, read with the `zip` crate
+/// rather than the tool's own ZIP code. Reading checks the CRC of the entry.
+pub fn zip_entry(archive: &[u8], name: &str) -> Option<String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(archive)).expect("Invalid ZIP archive");
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return None,
+        Err(err) => panic!("Invalid ZIP entry {name}: {err}"),
+    };
+    let mut content = Vec::new();
+    entry
+        .read_to_end(&mut content)
+        .unwrap_or_else(|err| panic!("Corrupt ZIP entry {name}: {err}"));
+    Some(String::from_utf8_lossy(&content).to_string())
+}
+
 pub fn assert_output_contains(output: &std::process::Output, expected: &str) {
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(
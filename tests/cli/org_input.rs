@@ -0,0 +1,113 @@
+use super::{assert_success, TestCommand};
+
+fn org_to_markdown(input: &str) -> String {
+    let cmd = TestCommand::new();
+    let output = cmd.run_with_input(&["convert-to", "--from", "org", "markdown"], input);
+    assert_success(&output);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_org_input_headlines_and_emphasis() {
+    let output = org_to_markdown(
+        "* Title\n** Section\nSome *bold*, /italic/, +struck+, =verbatim= and ~code~.\n",
+    );
+
+    assert_eq!(
+        output,
+        "# Title\n\n## Section\n\nSome **bold**, *italic*, ~~struck~~, `verbatim` and `code`.\n"
+    );
+}
+
+#[test]
+fn test_org_input_keywords_become_front_matter() {
+    let output = org_to_markdown("#+TITLE: Notes\n#+AUTHOR: Ann\n\nText\n");
+
+    assert_eq!(output, "---\nauthor: Ann\ntitle: Notes\n---\n\nText\n");
+}
+
+#[test]
+fn test_org_input_links() {
+    let output = org_to_markdown(
+        "See [[https://orgmode.org][Org]], [[file:docs/a.md][docs]], https://example.com and [[file:img.png]].\n",
+    );
+
+    assert_eq!(
+        output,
+        "See [Org](https://orgmode.org), [docs](docs/a.md), <https://example.com> and\n![](img.png).\n"
+    );
+}
+
+#[test]
+fn test_org_input_source_blocks() {
+    let output = org_to_markdown(
+        "#+begin_src python :results output\ndef f():\n    ,* not a heading\n#+end_src\n\n: fixed\n: width\n",
+    );
+
+    assert_eq!(
+        output,
+        "```python\ndef f():\n    * not a heading\n```\n\n```\nfixed\nwidth\n```\n"
+    );
+}
+
+#[test]
+fn test_org_input_lists() {
+    let output = org_to_markdown(
+        "- first\n- second\n  continued\n  + nested\n- [X] done\n- term :: text\n\n\n1. [@5] five\n2. six\n",
+    );
+
+    assert_eq!(
+        output,
+        " - first\n - second continued\n   \n    - nested\n - [X] done\n - **term**: text\n\n 5. five\n 6. six\n"
+    );
+}
+
+#[test]
+fn test_org_input_table() {
+    let output =
+        org_to_markdown("| Name | Qty |\n|------+-----|\n| <l> | <r> |\n| a \\vert{} b | 1 |\n");
+
+    assert_eq!(
+        output,
+        "| Name   | Qty |\n| ------ | --: |\n| a \\| b |   1 |\n"
+    );
+}
+
+#[test]
+fn test_org_input_skips_drawers_and_comments() {
+    let output = org_to_markdown("* Task\n:PROPERTIES:\n:ID: 1\n:END:\n# a comment\nBody\n");
+
+    assert_eq!(output, "# Task\n\nBody\n");
+}
+
+#[test]
+fn test_org_input_quote_and_special_blocks() {
+    let output = org_to_markdown(
+        "#+BEGIN_QUOTE\nQuoted.\n#+END_QUOTE\n\n#+BEGIN_NOTE\nNoted.\n#+END_NOTE\n",
+    );
+
+    assert_eq!(output, "> Quoted.\n\n> [!NOTE]\n> Noted.\n");
+}
+
+#[test]
+fn test_org_input_footnotes() {
+    let output = org_to_markdown("Text[fn:1].\n\n[fn:1] The note.\n");
+
+    assert_eq!(output, "Text[^1].\n\n[^1]: The note.\n");
+}
+
+#[test]
+fn test_org_round_trip() {
+    let cmd = TestCommand::new();
+    let markdown = "# Notes\n\nSome **bold** and [a link](https://example.com).\n\n - one\n - two\n\n```sh\necho hi\n```\n";
+
+    let org = cmd.run_with_input(&["convert-to", "org"], markdown);
+    assert_success(&org);
+    let output = cmd.run_with_input(
+        &["convert-to", "-f", "org", "markdown"],
+        &String::from_utf8_lossy(&org.stdout),
+    );
+
+    assert_success(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), markdown);
+}
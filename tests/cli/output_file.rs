@@ -0,0 +1,76 @@
+use super::{assert_success, TestCommand};
+use std::fs;
+
+#[test]
+fn test_convert_to_output_file() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("result.html");
+
+    let output = cmd.run_with_input(
+        &["convert-to", "-o", out.to_str().unwrap(), "html"],
+        "# Title",
+    );
+
+    assert_success(&output);
+    assert!(output.stdout.is_empty());
+    let content = fs::read_to_string(&out).unwrap();
+    assert!(content.contains("<h1>Title</h1>"));
+    assert!(content.ends_with('\n') && !content.ends_with("\n\n"));
+}
+
+#[test]
+fn test_convert_to_output_after_subcommand() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("nested").join("result.tex");
+
+    let output = cmd.run_with_input(
+        &["convert-to", "latex", "--output", out.to_str().unwrap()],
+        "# Title",
+    );
+
+    assert_success(&output);
+    let content = fs::read_to_string(&out).unwrap();
+    assert!(content.contains("\\section{Title}"));
+}
+
+#[test]
+fn test_stdout_has_single_trailing_newline() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["convert-to", "markdown"], "# Title\n");
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "# Title\n");
+}
+
+#[test]
+fn test_unchanged_output_is_not_rewritten() {
+    let cmd = TestCommand::new();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("result.md");
+    let args = ["convert-to", "-o", out.to_str().unwrap(), "markdown"];
+
+    assert_success(&cmd.run_with_input(&args, "# Title"));
+    let first = fs::metadata(&out).unwrap().modified().unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    assert_success(&cmd.run_with_input(&args, "# Title"));
+    let second = fs::metadata(&out).unwrap().modified().unwrap();
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_output_conflicts_with_output_dir() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &["convert-to", "-o", "a.html", "--output-dir", "out", "html"],
+        "# Title",
+    );
+
+    assert!(!output.status.success());
+}
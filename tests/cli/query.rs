@@ -0,0 +1,145 @@
+use super::{assert_success, TestCommand};
+
+const RUNBOOK: &str = "# Runbook
+
+Start at [home](https://home.example).
+
+## Installation
+
+Get [the package](https://pkg.example) or <https://mirror.example>.
+
+```sql file=schema.sql
+create table t (id int);
+```
+
+### Checks
+
+```sh
+./check.sh
+```
+
+- [x] installed
+- [ ] configured
+
+## Usage
+
+```sql
+select * from t;
+```
+";
+
+fn query(args: &[&str]) -> String {
+    let cmd = TestCommand::new();
+    let mut all_args = vec!["query"];
+    all_args.extend_from_slice(args);
+    let output = cmd.run_with_input(&all_args, RUNBOOK);
+    assert_success(&output);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_query_code_blocks_by_language() {
+    let stdout = query(&["code-block[lang=sql]"]);
+
+    assert_eq!(
+        stdout,
+        "```sql file=schema.sql\ncreate table t (id int);\n```\n\n```sql\nselect * from t;\n```\n"
+    );
+}
+
+#[test]
+fn test_query_code_block_info_attributes() {
+    let stdout = query(&["code-block[file=schema.sql]", "--to", "ast-json"]);
+
+    let matches: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(matches.as_array().unwrap().len(), 1);
+    assert_eq!(
+        matches[0]["CodeBlock"]["literal"],
+        "create table t (id int);"
+    );
+}
+
+#[test]
+fn test_query_links_in_section() {
+    // Links in the section and its subsections, not elsewhere
+    let stdout = query(&[
+        "section[title=Installation] link, section[title=Installation] autolink",
+        "-t",
+        "text",
+    ]);
+
+    assert_eq!(stdout, "the package\nhttps://mirror.example\n");
+}
+
+#[test]
+fn test_query_child_combinator() {
+    let stdout = query(&["section[title=Installation] > code-block", "-t", "text"]);
+    assert!(stdout.contains("create table"));
+    assert!(!stdout.contains("check.sh"));
+
+    let stdout = query(&["section[level=2] > section > h3", "-t", "text"]);
+    assert_eq!(stdout, "Checks\n");
+}
+
+#[test]
+fn test_query_sections_render_with_content() {
+    let stdout = query(&["section[id=checks]"]);
+
+    assert!(stdout.starts_with("### Checks\n\n```sh\n./check.sh\n```"));
+    assert!(stdout.contains("[ ] configured"));
+    assert!(!stdout.contains("Usage"));
+}
+
+#[test]
+fn test_query_attribute_operators() {
+    assert_eq!(
+        query(&["heading[level<=2]", "-t", "text"]),
+        "Runbook\nInstallation\nUsage\n"
+    );
+    assert_eq!(query(&["h2[text^=inst i]", "-t", "text"]), "Installation\n");
+    assert_eq!(query(&["link[href*=pkg]", "-t", "text"]), "the package\n");
+    assert!(query(&["list-item[task=incomplete]"]).contains("[ ] configured"));
+    assert_eq!(query(&["image"]), "");
+}
+
+#[test]
+fn test_query_ast_json_output() {
+    let stdout = query(&["autolink", "--to", "ast-json"]);
+
+    assert_eq!(stdout, "[{\"Autolink\":\"https://mirror.example\"}]\n");
+}
+
+#[test]
+fn test_query_files_and_input_format() {
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("a.md"), "# A\n").unwrap();
+    std::fs::write(dir.path().join("b.org"), "* B\n").unwrap();
+
+    let cmd = TestCommand::new();
+    let output = cmd.run_in_dir(dir.path(), &["query", "heading", "-t", "text", "a.md"]);
+    assert_success(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "A\n");
+
+    let output = cmd.run_in_dir(dir.path(), &["query", "h1", "-f", "org", "*.org"]);
+    assert_success(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "# B\n");
+}
+
+#[test]
+fn test_query_invalid_selector() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["query", "code-block[lang=sql"], RUNBOOK);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Invalid selector at column 20: expected ']'"),
+        "{stderr}"
+    );
+
+    let output = cmd.run_with_input(&["query", "codeblock"], RUNBOOK);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unknown node kind 'codeblock'"), "{stderr}");
+}
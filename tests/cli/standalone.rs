@@ -0,0 +1,130 @@
+use super::{assert_output_contains, assert_success, TestCommand};
+
+const DOCUMENT: &str = "---\ntitle: Report\nauthor: Ann\n---\n\n# Results\n\nSee [the site](https://example.com) and ~~old~~ data.\n\n| a | b |\n|---|---|\n| 1 | 2 |\n";
+
+#[test]
+fn test_latex_fragment_by_default() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["convert-to", "latex"], DOCUMENT);
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("\\documentclass"));
+    assert!(!stdout.contains("\\begin{document}"));
+}
+
+#[test]
+fn test_latex_standalone_loads_needed_packages() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &[
+            "convert-to",
+            "latex",
+            "--standalone",
+            "--table-style",
+            "booktabs",
+        ],
+        DOCUMENT,
+    );
+
+    assert_success(&output);
+    assert_output_contains(&output, "\\documentclass{article}");
+    assert_output_contains(&output, "\\usepackage{booktabs}");
+    assert_output_contains(&output, "\\usepackage[normalem]{ulem}");
+    assert_output_contains(&output, "\\usepackage{hyperref}");
+    assert_output_contains(&output, "\\title{Report}");
+    assert_output_contains(&output, "\\begin{document}");
+    assert_output_contains(&output, "\\end{document}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // No images or code blocks in the document
+    assert!(!stdout.contains("graphicx"));
+    assert!(!stdout.contains("listings"));
+    assert!(!stdout.contains("minted"));
+}
+
+#[test]
+fn test_latex_standalone_code_style_package() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &[
+            "convert-to",
+            "latex",
+            "--standalone",
+            "--code-style",
+            "minted",
+            "--document-class",
+            "report",
+        ],
+        "# Code\n\n```rust\nfn main() {}\n```\n",
+    );
+
+    assert_success(&output);
+    assert_output_contains(&output, "\\documentclass{report}");
+    assert_output_contains(&output, "\\usepackage{minted}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("hyperref"));
+    assert!(!stdout.contains("\\title"));
+}
+
+#[test]
+fn test_latex_standalone_task_list_package() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &["convert-to", "latex", "--standalone"],
+        "# Todo\n\n- [x] Done\n- [ ] Open\n",
+    );
+
+    assert_success(&output);
+    assert_output_contains(&output, "\\usepackage{amssymb}");
+    assert_output_contains(&output, "$\\boxtimes$ Done");
+    assert_output_contains(&output, "$\\square$ Open");
+
+    let output = cmd.run_with_input(&["convert-to", "latex", "--standalone"], "- Plain item\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("amssymb"), "{stdout}");
+}
+
+#[test]
+fn test_html_standalone_document() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &[
+            "convert-to",
+            "html",
+            "--standalone",
+            "--css",
+            "style.css",
+            "--css",
+            "print.css",
+        ],
+        DOCUMENT,
+    );
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("<!DOCTYPE html>\n<html>\n<head>\n"));
+    assert_output_contains(&output, "<title>Report</title>");
+    assert_output_contains(&output, "<meta name=\"author\" content=\"Ann\">");
+    assert_output_contains(&output, "<link rel=\"stylesheet\" href=\"style.css\">");
+    assert_output_contains(&output, "<link rel=\"stylesheet\" href=\"print.css\">");
+    assert_output_contains(&output, "<body>\n<h1>Results</h1>");
+    assert!(stdout.ends_with("</body>\n</html>\n"));
+}
+
+#[test]
+fn test_html_standalone_title_from_heading() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &["convert-to", "html", "--standalone"],
+        "# Getting <Started>\n\nText.\n",
+    );
+
+    assert_success(&output);
+    assert_output_contains(&output, "<title>Getting &lt;Started&gt;</title>");
+}
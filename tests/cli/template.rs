@@ -0,0 +1,166 @@
+use super::{assert_output_contains, assert_success, create_temp_file, TestCommand};
+
+const DOCUMENT: &str =
+    "---\ntitle: Tips & Tricks\nauthors: [Ann, Bob]\n---\n\n# Intro\n\n## Setup\n\nText.\n";
+
+#[test]
+fn test_html_template_placeholders() {
+    let cmd = TestCommand::new();
+    let template = create_temp_file(
+        "<title>$title$</title>\n<nav>$toc$</nav>\n<main>$body$</main>\n<footer>$$1 by $for(authors)$$authors$$sep$, $endfor$</footer>\n",
+    );
+
+    let output = cmd.run_with_input(
+        &[
+            "convert-to",
+            "html",
+            "--template",
+            template.path().to_str().unwrap(),
+        ],
+        DOCUMENT,
+    );
+
+    assert_success(&output);
+    assert_output_contains(&output, "<title>Tips &amp; Tricks</title>");
+    assert_output_contains(&output, "<nav><ul");
+    assert_output_contains(&output, "<a href=\"#setup\">Setup</a>");
+    assert_output_contains(&output, "<main><h1><a id=\"intro\"></a>Intro</h1>");
+    assert_output_contains(&output, "<footer>$1 by Ann, Bob</footer>");
+}
+
+#[test]
+fn test_html_template_toc_links_resolve() {
+    let cmd = TestCommand::new();
+    let template = create_temp_file("<nav>$toc$</nav>\n<main>$body$</main>\n");
+    let input = "# Intro\n\n## Setup\n\n## Setup\n\n### Tips & *tricks*\n";
+
+    let output = cmd.run_with_input(
+        &[
+            "convert-to",
+            "html",
+            "--heading-ids",
+            "--anchor-prefix",
+            "s-",
+            "--template",
+            template.path().to_str().unwrap(),
+        ],
+        input,
+    );
+
+    assert_success(&output);
+    let html = String::from_utf8_lossy(&output.stdout);
+    let anchors: Vec<&str> = html
+        .split("href=\"#")
+        .skip(1)
+        .map(|rest| &rest[..rest.find('"').unwrap()])
+        .collect();
+    assert_eq!(
+        anchors,
+        ["s-intro", "s-setup", "s-setup-1", "s-tips--tricks"]
+    );
+    for anchor in anchors {
+        assert_eq!(
+            html.matches(&format!(" id=\"{anchor}\"")).count(),
+            1,
+            "No unique id {anchor} in {html}"
+        );
+    }
+}
+
+#[test]
+fn test_html_template_toc_turns_on_heading_ids() {
+    let cmd = TestCommand::new();
+    let input = "# Intro\n\n## Setup\n";
+    let convert = |template: &str| {
+        let template = create_temp_file(template);
+        cmd.run_with_input(
+            &[
+                "convert-to",
+                "html",
+                "--template",
+                template.path().to_str().unwrap(),
+            ],
+            input,
+        )
+    };
+
+    let output = convert("<nav>$toc$</nav>\n<main>$body$</main>\n");
+    assert_success(&output);
+    assert_output_contains(&output, "<a href=\"#setup\">Setup</a>");
+    assert_output_contains(&output, "<h2><a id=\"setup\"></a>Setup</h2>");
+
+    // Without the table of contents headings stay plain
+    let output = convert("<main>$body$</main>\n");
+    assert_success(&output);
+    assert_output_contains(&output, "<h2>Setup</h2>");
+}
+
+#[test]
+fn test_template_conditionals() {
+    let cmd = TestCommand::new();
+    let template =
+        create_temp_file("$if(draft)$DRAFT$else$FINAL$endif$ $if(subtitle)$[$subtitle$]$endif$\n");
+
+    let output = cmd.run_with_input(
+        &[
+            "convert-to",
+            "html",
+            "--template",
+            template.path().to_str().unwrap(),
+        ],
+        "---\ndraft: true\n---\n\nText.\n",
+    );
+
+    assert_success(&output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "DRAFT \n");
+}
+
+#[test]
+fn test_latex_template_uses_latex_settings() {
+    let cmd = TestCommand::new();
+    let template = create_temp_file(
+        "\\documentclass{$documentclass$}\n$for(packages)$$packages$\n$endfor$\\title{$title$}\n\\begin{document}\n$body$\n\\end{document}\n",
+    );
+
+    let output = cmd.run_with_input(
+        &[
+            "convert-to",
+            "latex",
+            "--code-style",
+            "listings",
+            "--document-class",
+            "company",
+            "--template",
+            template.path().to_str().unwrap(),
+        ],
+        "---\ntitle: 100% done\n---\n\n```rust\nfn main() {}\n```\n",
+    );
+
+    assert_success(&output);
+    assert_output_contains(&output, "\\documentclass{company}");
+    assert_output_contains(&output, "\\usepackage{listings}");
+    assert_output_contains(&output, "\\title{100\\% done}");
+    assert_output_contains(&output, "\\begin{lstlisting}[language=rust]");
+}
+
+#[test]
+fn test_template_error_shows_position() {
+    let cmd = TestCommand::new();
+    let template = create_temp_file("<html>\n$if(title)$\n<h1>$title$</h1>\n");
+
+    let output = cmd.run_with_input(
+        &[
+            "convert-to",
+            "html",
+            "--template",
+            template.path().to_str().unwrap(),
+        ],
+        "# Hello\n",
+    );
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("$if(...)$ without matching $endif$"));
+    assert!(stderr.contains(":2:1"));
+}
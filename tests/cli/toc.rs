@@ -0,0 +1,123 @@
+use super::{assert_output_contains, assert_success, create_temp_file, TestCommand};
+use std::fs;
+
+const DOCUMENT: &str = "# Guide\n\n## Install\n\n### From source\n\n## Usage\n\n## Usage\n";
+
+#[test]
+fn test_toc_from_stdin() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["toc"], DOCUMENT);
+
+    assert_success(&output);
+    assert_output_contains(&output, "[Guide](#guide)");
+    assert_output_contains(&output, "[From source](#from-source)");
+    // Repeated headings get numbered anchors
+    assert_output_contains(&output, "[Usage](#usage-1)");
+}
+
+#[test]
+fn test_toc_depth_and_anchor_prefix() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(
+        &[
+            "toc",
+            "--min-depth",
+            "2",
+            "--max-depth",
+            "2",
+            "--anchor-prefix",
+            "doc-",
+        ],
+        DOCUMENT,
+    );
+
+    assert_success(&output);
+    assert_output_contains(&output, "[Install](#doc-install)");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Guide"));
+    assert!(!stdout.contains("From source"));
+}
+
+#[test]
+fn test_toc_insert_between_markers() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file(
+        "# Guide\n\n<!-- toc -->\n- [Old](#old)\n<!-- tocstop -->\n\nSome   *unformatted*   text.\n\n## Install\n",
+    );
+
+    let output = cmd.run(&["toc", "--insert", file.path().to_str().unwrap()]);
+
+    assert_success(&output);
+    let content = fs::read_to_string(file.path()).unwrap();
+    assert!(content.contains("[Install](#install)"));
+    assert!(!content.contains("[Old](#old)"));
+    assert!(content.contains("<!-- tocstop -->"));
+    // The rest of the file is left as is
+    assert!(content.contains("Some   *unformatted*   text."));
+}
+
+#[test]
+fn test_toc_insert_requires_marker() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file("# Guide\n");
+
+    let output = cmd.run(&["toc", "--insert", file.path().to_str().unwrap()]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No <!-- toc --> marker found"));
+}
+
+#[test]
+fn test_format_dry_run_reports_stale_toc() {
+    let cmd = TestCommand::new();
+    let file = create_temp_file("# Guide\n\n<!-- toc -->\n\n<!-- tocstop -->\n\n## Install\n");
+    let path = file.path().to_str().unwrap();
+
+    let output = cmd.run(&["format", "--dry-run", path]);
+    assert!(!output.status.success());
+    assert_output_contains(&output, "Table of contents is out of date");
+
+    assert_success(&cmd.run(&["toc", "--insert", path]));
+
+    let output = cmd.run(&["format", "--dry-run", path]);
+    assert_success(&output);
+    assert_output_contains(&output, "All files are already formatted");
+}
+
+#[test]
+fn test_toc_links_match_html_heading_ids() {
+    let cmd = TestCommand::new();
+    let document = format!("<!-- toc -->\n<!-- tocstop -->\n\n{DOCUMENT}\n## *Styled* heading\n");
+    let file = create_temp_file(&document);
+    let path = file.path().to_str().unwrap();
+    assert_success(&cmd.run(&["toc", "--insert", "--anchor-prefix", "doc-", path]));
+
+    let output = cmd.run(&[
+        "convert-to",
+        "html",
+        "--heading-ids",
+        "--anchor-prefix",
+        "doc-",
+        path,
+    ]);
+    assert_success(&output);
+    let html = String::from_utf8_lossy(&output.stdout);
+
+    let links: Vec<&str> = html
+        .split("href=\"#")
+        .skip(1)
+        .map(|rest| &rest[..rest.find('"').unwrap()])
+        .collect();
+    assert_eq!(links.len(), 6);
+    assert!(html.contains("<h2><a id=\"doc-styled-heading\"></a><em>Styled</em> heading</h2>"));
+    for anchor in links {
+        assert!(
+            html.contains(&format!(" id=\"{anchor}\"")),
+            "No heading with id {anchor} in {html}"
+        );
+    }
+    assert!(html.contains("<h2><a id=\"doc-usage-1\"></a>Usage</h2>"));
+}
@@ -0,0 +1,134 @@
+use super::TestCommand;
+use std::fs;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// Wait until `path` satisfies `condition`, failing after a few seconds.
+fn wait_for(path: &Path, condition: impl Fn(&str) -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        if fs::read_to_string(path).is_ok_and(|content| condition(&content)) {
+            return;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "Timed out waiting for {}",
+            path.display()
+        );
+        sleep(Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn test_convert_watch_regenerates_changed_outputs() {
+    let cmd = TestCommand::new();
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("a.md"), "# First\n").unwrap();
+    fs::write(dir.path().join("b.md"), "# Other\n").unwrap();
+
+    let mut child = cmd.spawn_in_dir(
+        dir.path(),
+        &[
+            "convert-to",
+            "html",
+            "--watch",
+            "--output-dir",
+            "out",
+            "a.md",
+            "b.md",
+        ],
+    );
+
+    let a_html = dir.path().join("out/a.html");
+    let b_html = dir.path().join("out/b.html");
+    wait_for(&a_html, |content| content.contains("<h1>First</h1>"));
+    wait_for(&b_html, |content| content.contains("<h1>Other</h1>"));
+    let b_modified = fs::metadata(&b_html).unwrap().modified().unwrap();
+
+    // An invalid input is reported without stopping the watcher
+    fs::write(dir.path().join("a.md"), "+++\ntitle = [\n+++\n").unwrap();
+    sleep(Duration::from_millis(1000));
+    fs::write(dir.path().join("a.md"), "# Second\n").unwrap();
+    wait_for(&a_html, |content| content.contains("<h1>Second</h1>"));
+
+    child.kill().unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid TOML front matter"));
+    // Outputs of unchanged inputs are not regenerated
+    assert_eq!(
+        fs::metadata(&b_html).unwrap().modified().unwrap(),
+        b_modified
+    );
+}
+
+#[test]
+fn test_format_watch_formats_changed_files() {
+    let cmd = TestCommand::new();
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("doc.md");
+    fs::write(&file, "# Title\n\nSome   text.\n").unwrap();
+
+    let mut child = cmd.spawn_in_dir(dir.path(), &["format", "--watch", "doc.md"]);
+    wait_for(&file, |content| content == "# Title\n\nSome text.\n");
+
+    fs::write(&file, "# Title\n\nMore   text.\n").unwrap();
+    wait_for(&file, |content| content == "# Title\n\nMore text.\n");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_format_watch_sees_changes_made_while_formatting() {
+    let cmd = TestCommand::new();
+    let dir = TempDir::new().unwrap();
+    let slow = dir.path().join("slow.md");
+    let other = dir.path().join("other.md");
+    fs::write(&slow, "Slow   text.\n\n```sh\nls\n```\n").unwrap();
+    fs::write(&other, "Other text.\n").unwrap();
+
+    let mut child = cmd.spawn_in_dir(
+        dir.path(),
+        &[
+            "format",
+            "--watch",
+            "--code-formatter",
+            "sh=sh -c 'sleep 2; cat'",
+            "slow.md",
+            "other.md",
+        ],
+    );
+    wait_for(&slow, |content| content.starts_with("Slow text.\n"));
+
+    // Save the other file while the slow one is being formatted
+    fs::write(&slow, "More   text.\n\n```sh\nls\n```\n").unwrap();
+    sleep(Duration::from_millis(1500));
+    fs::write(&other, "Changed   text.\n").unwrap();
+    wait_for(&other, |content| content == "Changed text.\n");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn test_watch_requires_input_files() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run_with_input(&["convert-to", "html", "--watch"], "# Hello\n");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--watch requires input files"));
+}
+
+#[test]
+fn test_format_watch_conflicts_with_dry_run() {
+    let cmd = TestCommand::new();
+
+    let output = cmd.run(&["format", "--watch", "--dry-run", "doc.md"]);
+
+    assert!(!output.status.success());
+}